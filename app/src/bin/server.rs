@@ -0,0 +1,3 @@
+fn main() {
+    project_harmonia::server::main();
+}
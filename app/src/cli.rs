@@ -59,27 +59,26 @@ fn apply_subcommand(
                     "hosting world '{}' on port {port} from CLI",
                     world_load.world_name
                 );
-                let server = RenetServer::new(ConnectionConfig {
-                    server_channels_config: network_channels.get_server_configs(),
-                    client_channels_config: network_channels.get_client_configs(),
-                    ..Default::default()
-                });
-                let transport = network::create_server(*port).context("unable to create server")?;
-
-                commands.insert_resource(server);
-                commands.insert_resource(transport);
-                commands.insert_resource(WorldName(world_load.world_name.clone()));
-                commands.trigger(GameLoad);
+                host(
+                    &mut commands,
+                    &network_channels,
+                    world_load.world_name.clone(),
+                    *port,
+                )?;
             }
-            GameCommand::Join { ip, port } => {
+            GameCommand::Join {
+                ip,
+                port,
+                admin_password,
+            } => {
                 info!("joining world at {ip}:{port} from CLI");
                 let client = RenetClient::new(ConnectionConfig {
                     server_channels_config: network_channels.get_server_configs(),
                     client_channels_config: network_channels.get_client_configs(),
                     ..Default::default()
                 });
-                let transport =
-                    network::create_client(*ip, *port).context("unable to create client")?;
+                let transport = network::create_client(*ip, *port, admin_password.as_deref())
+                    .context("unable to create client")?;
 
                 commands.insert_resource(client);
                 commands.insert_resource(transport);
@@ -90,6 +89,28 @@ fn apply_subcommand(
     Ok(())
 }
 
+/// Starts a server on the port and loads the world into it.
+pub(super) fn host(
+    commands: &mut Commands,
+    network_channels: &RepliconChannels,
+    world_name: String,
+    port: u16,
+) -> Result<()> {
+    let server = RenetServer::new(ConnectionConfig {
+        server_channels_config: network_channels.get_server_configs(),
+        client_channels_config: network_channels.get_client_configs(),
+        ..Default::default()
+    });
+    let transport = network::create_server(port).context("unable to create server")?;
+
+    commands.insert_resource(server);
+    commands.insert_resource(transport);
+    commands.insert_resource(WorldName(world_name));
+    commands.trigger(GameLoad);
+
+    Ok(())
+}
+
 fn quick_load(
     mut commands: Commands,
     cli: Res<Cli>,
@@ -171,6 +192,10 @@ enum GameCommand {
         /// Server port.
         #[clap(short, long, default_value_t = DEFAULT_PORT)]
         port: u16,
+
+        /// Password to use chat commands on a dedicated server.
+        #[clap(long)]
+        admin_password: Option<String>,
     },
 }
 
@@ -1,5 +1,6 @@
 mod cli;
 mod cursor_controller;
+pub mod server;
 
 use avian3d::{prelude::*, sync::SyncConfig};
 use bevy::{
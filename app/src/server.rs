@@ -0,0 +1,145 @@
+//! Headless dedicated server.
+//!
+//! Uses the same engine plugins as headless test apps.
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use anyhow::Result;
+use avian3d::{prelude::*, sync::SyncConfig};
+use bevy::{app::ScheduleRunnerPlugin, log::LogPlugin, prelude::*};
+use bevy_enhanced_input::prelude::*;
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::RepliconRenetPlugins;
+use clap::{builder::NonEmptyStringValueParser, Parser};
+use project_harmonia_base::{
+    core::GameState,
+    error_message::error_message,
+    game_world::{chat::ChatAdmin, navigation::Obstacle, GameSave},
+    headless::HeadlessPlugins,
+    network::DEFAULT_PORT,
+    settings::Settings,
+    CorePlugins,
+};
+use vleue_navigator::prelude::*;
+
+use super::cli;
+
+pub fn main() {
+    let cli = ServerCli::parse();
+    let tick = Duration::from_secs_f64(1.0 / cli.tick_rate);
+    let admin = ChatAdmin::Password(cli.admin_password.clone());
+
+    App::new()
+        .insert_resource(SyncConfig {
+            position_to_transform: false,
+            ..Default::default()
+        })
+        .insert_resource(Time::<Fixed>::from_duration(tick))
+        .insert_resource(cli)
+        .insert_resource(admin)
+        // Normally loaded on startup, but requires a window.
+        .init_resource::<Settings>()
+        .add_plugins((
+            LogPlugin::default(),
+            HeadlessPlugins.set(ScheduleRunnerPlugin::run_loop(tick)),
+        ))
+        .add_plugins((
+            RepliconPlugins,
+            RepliconRenetPlugins,
+            EnhancedInputPlugin,
+            VleueNavigatorPlugin,
+            NavmeshUpdaterPlugin::<Collider, Obstacle>::default(),
+            PhysicsPlugins::default()
+                .build()
+                .disable::<CcdPlugin>()
+                .disable::<SleepingPlugin>(),
+            CorePlugins,
+            ServerPlugin,
+        ))
+        .run();
+}
+
+/// Hosts the world from [`ServerCli`] and saves it on exit.
+///
+/// Exit is requested on Ctrl+C and on termination signals,
+/// so the world is also saved when stopped by a container runtime or a service manager.
+struct ServerPlugin;
+
+impl Plugin for ServerPlugin {
+    fn build(&self, app: &mut App) {
+        // Replaces Bevy's terminal handler, which reacts only to Ctrl+C.
+        if let Err(e) = ctrlc::set_handler(|| TERMINATION_REQUESTED.store(true, Ordering::Relaxed))
+        {
+            error!("unable to set termination handler: {e}");
+        }
+
+        app.add_systems(
+            OnExit(GameState::ManifestsLoading),
+            host.pipe(error_message),
+        )
+        .add_systems(Update, exit_on_termination)
+        .add_systems(Last, save_on_exit.run_if(in_state(GameState::InGame)));
+    }
+}
+
+/// Set from the signal handler thread.
+static TERMINATION_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+fn host(
+    mut commands: Commands,
+    cli: Res<ServerCli>,
+    network_channels: Res<RepliconChannels>,
+) -> Result<()> {
+    info!(
+        "hosting world '{}' on port {} at {} ticks per second",
+        cli.world_name, cli.port, cli.tick_rate
+    );
+    cli::host(
+        &mut commands,
+        &network_channels,
+        cli.world_name.clone(),
+        cli.port,
+    )
+}
+
+fn exit_on_termination(mut exit_events: EventWriter<AppExit>) {
+    if TERMINATION_REQUESTED.swap(false, Ordering::Relaxed) {
+        info!("received termination signal, exiting");
+        exit_events.send(AppExit::Success);
+    }
+}
+
+/// Saves the world in the same frame in which the exit was requested.
+///
+/// The runner checks for exit after the frame, so the save observer runs before it.
+fn save_on_exit(mut commands: Commands, mut exit_events: EventReader<AppExit>) {
+    if exit_events.read().next().is_some() {
+        info!("saving world before exit");
+        commands.trigger(GameSave);
+    }
+}
+
+#[derive(Parser, Resource)]
+#[command(author, version, about = "Dedicated server for Project Harmonia")]
+struct ServerCli {
+    /// World name to host.
+    #[arg(short, long)]
+    world_name: String,
+
+    /// Port to use.
+    #[arg(short, long, default_value_t = DEFAULT_PORT)]
+    port: u16,
+
+    /// Simulation updates per second.
+    #[arg(short, long, default_value_t = 30.0)]
+    tick_rate: f64,
+
+    /// Password that clients pass on connection to use chat commands.
+    ///
+    /// Commands are disabled if not set.
+    #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+    admin_password: Option<String>,
+}
@@ -0,0 +1,126 @@
+//! Measures how long it takes to place an obstacle in a city with walls and walking actors
+//! until all affected navmesh tiles are rebuilt.
+//!
+//! Goes through the same systems as the game: tiles are marked on obstacle changes,
+//! rebuilt asynchronously and actors update their paths and avoidance on each tick.
+//!
+//! Run with `cargo bench -p project_harmonia_base --bench navmesh_rebuild --features test_app`.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use project_harmonia_base::game_world::{
+    navigation::{
+        nav_tiles::{TILES_PER_SIDE, TILE_SIZE},
+        Obstacle,
+    },
+    test_app::{self, GameTestAppExt},
+};
+
+/// Wall thickness without agent radius, which is added by navmesh generation.
+const WALL_THICKNESS: f32 = 0.15;
+
+const WALL_HEIGHT: f32 = 2.8;
+
+/// Side of a house formed by 4 walls.
+const HOUSE_SIZE: f32 = 8.0;
+
+/// Distance between house origins.
+const HOUSE_SPACING: f32 = 20.0;
+
+/// Number of actors walking between houses during the measurement.
+const ACTORS_COUNT: usize = 20;
+
+/// Half of the diagonal along which actors are placed.
+const HALF_ACTORS_SPAN: f32 = ACTORS_COUNT as f32 * HOUSE_SPACING / 2.0;
+
+fn navmesh_rebuild(c: &mut Criterion) {
+    let mut group = c.benchmark_group("navmesh_rebuild");
+    // Each iteration waits for asynchronous rebuilds over multiple app updates.
+    group.sample_size(10);
+
+    for walls_count in [100, 300, 600] {
+        let mut app = test_app::new_app();
+        app.start_game();
+        let city_entity = app.spawn_city();
+        for (start, end) in walls(walls_count) {
+            app.world_mut()
+                .spawn(wall(start, end))
+                .set_parent(city_entity);
+        }
+        app.wait_for_navmesh(city_entity);
+
+        let names = vec!["Walker"; ACTORS_COUNT];
+        let (_, actors) = app.spawn_family(city_entity, &names);
+        for (index, &actor_entity) in actors.iter().enumerate() {
+            let row = index as f32 * HOUSE_SPACING - HALF_ACTORS_SPAN;
+            app.world_mut()
+                .get_mut::<Transform>(actor_entity)
+                .unwrap()
+                .translation = Vec3::new(-row, 0.0, row);
+            app.walk_to(actor_entity, Vec3::new(row, 0.0, -row));
+        }
+        app.update();
+
+        // Between houses, where actors walk, and across the border of 4 tiles.
+        let (start, end) = (Vec2::new(-4.0, -4.0), Vec2::new(4.0, 4.0));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(walls_count),
+            &walls_count,
+            |b, _| {
+                b.iter(|| {
+                    let obstacle_entity = app
+                        .world_mut()
+                        .spawn(wall(start, end))
+                        .set_parent(city_entity)
+                        .id();
+                    app.wait_for_navmesh(city_entity);
+
+                    app.world_mut()
+                        .entity_mut(obstacle_entity)
+                        .despawn_recursive();
+                    app.wait_for_navmesh(city_entity);
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Generates wall segments for the specified number of walls, grouped into houses.
+fn walls(count: usize) -> Vec<(Vec2, Vec2)> {
+    let houses_per_row = (TILES_PER_SIDE as f32 * TILE_SIZE / HOUSE_SPACING) as usize - 1;
+    let origin = -(houses_per_row as f32 * HOUSE_SPACING) / 2.0;
+    (0..count)
+        .map(|index| {
+            let house = index / 4;
+            let corner = Vec2::new(
+                origin + (house % houses_per_row) as f32 * HOUSE_SPACING,
+                origin + (house / houses_per_row) as f32 * HOUSE_SPACING,
+            );
+            let (start, end) = match index % 4 {
+                0 => (Vec2::ZERO, Vec2::X),
+                1 => (Vec2::X, Vec2::ONE),
+                2 => (Vec2::ONE, Vec2::Y),
+                _ => (Vec2::Y, Vec2::ZERO),
+            };
+            (corner + start * HOUSE_SIZE, corner + end * HOUSE_SIZE)
+        })
+        .collect()
+}
+
+/// Returns an obstacle between the points in the city space.
+fn wall(start: Vec2, end: Vec2) -> impl Bundle {
+    let disp = end - start;
+    let center = start.midpoint(end);
+    (
+        Obstacle,
+        Collider::cuboid(disp.length(), WALL_HEIGHT, WALL_THICKNESS),
+        Transform::from_xyz(center.x, WALL_HEIGHT / 2.0, center.y)
+            .with_rotation(Quat::from_rotation_y(-disp.to_angle())),
+    )
+}
+
+criterion_group!(benches, navmesh_rebuild);
+criterion_main!(benches);
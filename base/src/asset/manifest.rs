@@ -1,16 +1,26 @@
+pub mod career_manifest;
+pub mod ground_manifest;
+pub mod mood_manifest;
 pub mod object_manifest;
+pub mod outfit_manifest;
 pub mod road_manifest;
+pub mod trait_manifest;
 
-use std::{env, path::Path};
+use std::path::{Path, PathBuf};
 
-use bevy::prelude::*;
+use bevy::{asset::io::file::FileAssetReader, prelude::*};
 use serde::{Deserialize, Serialize};
 use strum::{EnumIter, IntoEnumIterator};
 use walkdir::WalkDir;
 
 use crate::core::GameState;
+use career_manifest::{CareerLoader, CareerManifest};
+use ground_manifest::{GroundLoader, GroundManifest};
+use mood_manifest::{MoodLoader, MoodManifest};
 use object_manifest::{ObjectLoader, ObjectManifest};
+use outfit_manifest::{OutfitLoader, OutfitManifest};
 use road_manifest::{RoadLoader, RoadManifest};
+use trait_manifest::{TraitLoader, TraitManifest};
 
 pub(super) struct ManifestPlugin;
 
@@ -18,8 +28,19 @@ impl Plugin for ManifestPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<ObjectManifest>()
             .init_asset::<RoadManifest>()
+            .init_asset::<GroundManifest>()
+            .init_asset::<TraitManifest>()
+            .init_asset::<OutfitManifest>()
+            .init_asset::<MoodManifest>()
+            .init_asset::<CareerManifest>()
             .init_asset_loader::<ObjectLoader>()
             .init_asset_loader::<RoadLoader>()
+            .init_asset_loader::<GroundLoader>()
+            .init_asset_loader::<TraitLoader>()
+            .init_asset_loader::<OutfitLoader>()
+            .init_asset_loader::<MoodLoader>()
+            .init_asset_loader::<CareerLoader>()
+            .init_resource::<ManifestsDir>()
             .add_systems(
                 Update,
                 wait_for_loading.run_if(in_state(GameState::ManifestsLoading)),
@@ -39,8 +60,18 @@ fn wait_for_loading(
 ) {
     let objects = manifests.objects.iter().map(|handle| handle.id().untyped());
     let roads = manifests.roads.iter().map(Into::into);
+    let grounds = manifests.grounds.iter().map(Into::into);
+    let traits = manifests.traits.iter().map(Into::into);
+    let outfits = manifests.outfits.iter().map(Into::into);
+    let moods = manifests.moods.iter().map(Into::into);
+    let careers = manifests.careers.iter().map(Into::into);
     if objects
         .chain(roads)
+        .chain(grounds)
+        .chain(traits)
+        .chain(outfits)
+        .chain(moods)
+        .chain(careers)
         .all(|handle| asset_server.is_loaded(handle))
     {
         info!("finished loading asset manifests");
@@ -48,21 +79,44 @@ fn wait_for_loading(
     }
 }
 
+/// Directory in which manifests are searched.
+///
+/// Should point to the same directory as the default asset source.
+/// Needs to be inserted only if [`AssetPlugin::file_path`] is customized.
+#[derive(Resource)]
+pub struct ManifestsDir(pub PathBuf);
+
+impl Default for ManifestsDir {
+    fn default() -> Self {
+        // Resolved the same way as the default asset source.
+        Self(FileAssetReader::get_base_path().join("assets"))
+    }
+}
+
 /// Resource keep manifests loaded.
 #[derive(Resource)]
 struct AssetManifests {
     objects: Vec<Handle<ObjectManifest>>,
     roads: Vec<Handle<RoadManifest>>,
+    grounds: Vec<Handle<GroundManifest>>,
+    traits: Vec<Handle<TraitManifest>>,
+    outfits: Vec<Handle<OutfitManifest>>,
+    moods: Vec<Handle<MoodManifest>>,
+    careers: Vec<Handle<CareerManifest>>,
 }
 
 impl FromWorld for AssetManifests {
     fn from_world(world: &mut World) -> Self {
-        let assets_dir =
-            Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join("assets");
+        let assets_dir = world.resource::<ManifestsDir>().0.clone();
 
         let mut manifests = AssetManifests {
             objects: Default::default(),
             roads: Default::default(),
+            grounds: Default::default(),
+            traits: Default::default(),
+            outfits: Default::default(),
+            moods: Default::default(),
+            careers: Default::default(),
         };
         let asset_server = world.resource::<AssetServer>();
         for path in WalkDir::new(&assets_dir)
@@ -86,6 +140,21 @@ impl FromWorld for AssetManifests {
                 ManifestFormat::Road => {
                     manifests.roads.push(asset_server.load(relative_path));
                 }
+                ManifestFormat::Ground => {
+                    manifests.grounds.push(asset_server.load(relative_path));
+                }
+                ManifestFormat::Trait => {
+                    manifests.traits.push(asset_server.load(relative_path));
+                }
+                ManifestFormat::Outfit => {
+                    manifests.outfits.push(asset_server.load(relative_path));
+                }
+                ManifestFormat::Mood => {
+                    manifests.moods.push(asset_server.load(relative_path));
+                }
+                ManifestFormat::Career => {
+                    manifests.careers.push(asset_server.load(relative_path));
+                }
             }
         }
 
@@ -97,6 +166,11 @@ impl FromWorld for AssetManifests {
 enum ManifestFormat {
     Object,
     Road,
+    Ground,
+    Trait,
+    Outfit,
+    Mood,
+    Career,
 }
 
 impl ManifestFormat {
@@ -118,6 +192,11 @@ impl ManifestFormat {
         match self {
             ManifestFormat::Object => &["object.ron"],
             ManifestFormat::Road => &["road.ron"],
+            ManifestFormat::Ground => &["ground.ron"],
+            ManifestFormat::Trait => &["trait.ron"],
+            ManifestFormat::Outfit => &["outfit.ron"],
+            ManifestFormat::Mood => &["mood.ron"],
+            ManifestFormat::Career => &["career.ron"],
         }
     }
 }
@@ -147,13 +226,18 @@ mod tests {
     use super::*;
     use crate::{
         combined_scene_collider::SceneColliderConstructor,
-        game_world::object::{
-            door::Door,
-            placing_object::{side_snap::SideSnap, wall_snap::WallSnap},
-            wall_mount::WallMount,
+        game_world::{
+            navigation::{nav_area::AreaFootprint, Obstacle},
+            object::{
+                door::Door,
+                placing_object::{side_snap::SideSnap, wall_snap::WallSnap},
+                wall_mount::WallMount,
+            },
         },
     };
+    use ground_manifest::GroundManifestDeserializer;
     use object_manifest::ObjectManifestDeserializer;
+    use outfit_manifest::OutfitManifestDeserializer;
     use road_manifest::RoadManifestDeserializer;
 
     #[test]
@@ -163,10 +247,17 @@ mod tests {
         registry.register::<WallSnap>();
         registry.register::<SideSnap>();
         registry.register::<Door>();
+        registry.register::<AreaFootprint>();
+        registry.register::<Obstacle>();
         registry.register::<SceneColliderConstructor>();
 
         let mut objects_count = 0;
         let mut roads_count = 0;
+        let mut grounds_count = 0;
+        let mut traits_count = 0;
+        let mut outfits_count = 0;
+        let mut moods_count = 0;
+        let mut careers_count = 0;
         for path in WalkDir::new("../app/assets/base")
             .into_iter()
             .filter_map(|entry| entry.ok())
@@ -192,11 +283,38 @@ mod tests {
                     ron::Options::default().from_str_seed(&string, seed)?;
                     roads_count += 1;
                 }
+                ManifestFormat::Ground => {
+                    let seed = GroundManifestDeserializer { dir: None };
+                    ron::Options::default().from_str_seed(&string, seed)?;
+                    grounds_count += 1;
+                }
+                ManifestFormat::Trait => {
+                    ron::from_str::<TraitManifest>(&string)?;
+                    traits_count += 1;
+                }
+                ManifestFormat::Outfit => {
+                    let seed = OutfitManifestDeserializer { dir: None };
+                    ron::Options::default().from_str_seed(&string, seed)?;
+                    outfits_count += 1;
+                }
+                ManifestFormat::Mood => {
+                    ron::from_str::<MoodManifest>(&string)?;
+                    moods_count += 1;
+                }
+                ManifestFormat::Career => {
+                    ron::from_str::<CareerManifest>(&string)?;
+                    careers_count += 1;
+                }
             }
         }
 
         assert!(objects_count > 0);
         assert!(roads_count > 0);
+        assert!(grounds_count > 0);
+        assert!(traits_count > 0);
+        assert!(outfits_count > 0);
+        assert!(moods_count > 0);
+        assert!(careers_count > 0);
 
         Ok(())
     }
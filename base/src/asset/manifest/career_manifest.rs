@@ -0,0 +1,80 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    scene::ron,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{GeneralManifest, ManifestFormat};
+use crate::game_world::actor::skills::SkillKind;
+
+#[derive(Default)]
+pub(super) struct CareerLoader;
+
+impl AssetLoader for CareerLoader {
+    type Asset = CareerManifest;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut string = String::new();
+        reader.read_to_string(&mut string).await?;
+
+        let manifest: CareerManifest = ron::from_str(&string)?;
+        anyhow::ensure!(
+            !manifest.levels.is_empty(),
+            "career should have at least one level"
+        );
+
+        Ok(manifest)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        ManifestFormat::Career.extensions()
+    }
+}
+
+/// Job that can be assigned to actors.
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct CareerManifest {
+    pub general: GeneralManifest,
+    pub glyph: String,
+
+    /// Skill that improves work performance.
+    pub skill: SkillKind,
+
+    /// Positions from the lowest to the highest.
+    pub levels: Vec<CareerLevel>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CareerLevel {
+    pub name: String,
+
+    /// Income for a full shift.
+    pub pay: u32,
+
+    /// Hour when the shift starts.
+    pub start_hour: f32,
+
+    /// Hour when the shift ends.
+    ///
+    /// Could be less than [`Self::start_hour`] for night shifts.
+    pub end_hour: f32,
+}
+
+impl CareerLevel {
+    /// Returns `true` if the time of day is within the shift.
+    pub fn is_working_time(&self, time_of_day: f32) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&time_of_day)
+        } else {
+            time_of_day >= self.start_hour || time_of_day < self.end_hour
+        }
+    }
+}
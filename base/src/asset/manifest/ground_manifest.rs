@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AssetPath, AsyncReadExt, LoadContext},
+    prelude::*,
+    scene::ron,
+};
+use serde::{de::DeserializeSeed, Deserialize, Deserializer, Serialize};
+
+use super::{GeneralManifest, ManifestFormat, MapPaths};
+use crate::asset;
+
+#[derive(Default)]
+pub(super) struct GroundLoader;
+
+impl AssetLoader for GroundLoader {
+    type Asset = GroundManifest;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut string = String::new();
+        reader.read_to_string(&mut string).await?;
+
+        let dir = load_context.path().parent();
+        let seed = GroundManifestDeserializer { dir };
+
+        let manifest = ron::Options::default().from_str_seed(&string, seed)?;
+
+        Ok(manifest)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        ManifestFormat::Ground.extensions()
+    }
+}
+
+/// Ground material that can be painted on city terrain.
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct GroundManifest {
+    pub general: GeneralManifest,
+    pub material: AssetPath<'static>,
+    pub preview: AssetPath<'static>,
+}
+
+impl MapPaths for GroundManifest {
+    fn map_paths(&mut self, dir: &Path) {
+        asset::change_parent_dir(&mut self.material, dir);
+        asset::change_parent_dir(&mut self.preview, dir);
+    }
+}
+
+pub(super) struct GroundManifestDeserializer<'a> {
+    pub(super) dir: Option<&'a Path>,
+}
+
+impl<'de> DeserializeSeed<'de> for GroundManifestDeserializer<'_> {
+    type Value = GroundManifest;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        GroundManifest::deserialize(deserializer).map(|mut manifest| {
+            if let Some(dir) = self.dir {
+                manifest.map_paths(dir);
+            }
+            manifest
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    scene::ron,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{GeneralManifest, ManifestFormat};
+use crate::game_world::actor::{mood::MoodEvent, needs::NeedKind};
+
+#[derive(Default)]
+pub(super) struct MoodLoader;
+
+impl AssetLoader for MoodLoader {
+    type Asset = MoodManifest;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut string = String::new();
+        reader.read_to_string(&mut string).await?;
+
+        let manifest = ron::from_str(&string)?;
+
+        Ok(manifest)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        ManifestFormat::Mood.extensions()
+    }
+}
+
+/// Modifier that temporarily affects actor mood.
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct MoodManifest {
+    pub general: GeneralManifest,
+    pub glyph: String,
+
+    /// Added to the mood while the modifier is active.
+    pub value: f32,
+
+    pub trigger: MoodTrigger,
+}
+
+/// Condition under which a mood modifier is applied.
+#[derive(Serialize, Deserialize)]
+pub enum MoodTrigger {
+    /// Active while the need is below the value.
+    Need { kind: NeedKind, below: f32 },
+
+    /// Applied on the event for the specified number of game hours.
+    Event { event: MoodEvent, hours: f32 },
+}
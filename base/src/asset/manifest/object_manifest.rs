@@ -66,6 +66,7 @@ pub struct ObjectManifest {
     pub general: GeneralManifest,
     pub scene: AssetPath<'static>,
     pub category: ObjectCategory,
+    pub price: u32,
     pub preview_translation: Vec3,
     pub components: Vec<Box<dyn PartialReflect>>,
     pub place_components: Vec<Box<dyn PartialReflect>>,
@@ -86,6 +87,7 @@ enum ObjectManifestField {
     General,
     Scene,
     Category,
+    Price,
     PreviewTranslation,
     Components,
     PlaceComponents,
@@ -167,6 +169,7 @@ impl<'de> Visitor<'de> for ObjectManifestDeserializer<'_> {
         let mut general = None;
         let mut scene = None;
         let mut category = None;
+        let mut price = None;
         let mut preview_translation = None;
         let mut components = None;
         let mut place_components = None;
@@ -197,6 +200,14 @@ impl<'de> Visitor<'de> for ObjectManifestDeserializer<'_> {
                     }
                     category = Some(map.next_value()?);
                 }
+                ObjectManifestField::Price => {
+                    if price.is_some() {
+                        return Err(de::Error::duplicate_field(
+                            ObjectManifestField::Price.into(),
+                        ));
+                    }
+                    price = Some(map.next_value()?);
+                }
                 ObjectManifestField::PreviewTranslation => {
                     if preview_translation.is_some() {
                         return Err(de::Error::duplicate_field(
@@ -244,6 +255,8 @@ impl<'de> Visitor<'de> for ObjectManifestDeserializer<'_> {
             scene.ok_or_else(|| de::Error::missing_field(ObjectManifestField::Scene.into()))?;
         let category = category
             .ok_or_else(|| de::Error::missing_field(ObjectManifestField::Category.into()))?;
+        let price =
+            price.ok_or_else(|| de::Error::missing_field(ObjectManifestField::Price.into()))?;
         let preview_translation = preview_translation.ok_or_else(|| {
             de::Error::missing_field(ObjectManifestField::PreviewTranslation.into())
         })?;
@@ -255,6 +268,7 @@ impl<'de> Visitor<'de> for ObjectManifestDeserializer<'_> {
             general,
             scene,
             category,
+            price,
             preview_translation,
             components,
             place_components,
@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AssetPath, AsyncReadExt, LoadContext},
+    prelude::*,
+    scene::ron,
+};
+use serde::{de::DeserializeSeed, Deserialize, Deserializer, Serialize};
+use strum::{EnumCount, EnumIter};
+
+use super::{GeneralManifest, ManifestFormat, MapPaths};
+use crate::asset;
+
+#[derive(Default)]
+pub(super) struct OutfitLoader;
+
+impl AssetLoader for OutfitLoader {
+    type Asset = OutfitManifest;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut string = String::new();
+        reader.read_to_string(&mut string).await?;
+
+        let dir = load_context.path().parent();
+        let seed = OutfitManifestDeserializer { dir };
+
+        let manifest = ron::Options::default().from_str_seed(&string, seed)?;
+
+        Ok(manifest)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        ManifestFormat::Outfit.extensions()
+    }
+}
+
+/// Hair or clothing piece that can be put on human actors.
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct OutfitManifest {
+    pub general: GeneralManifest,
+    pub category: OutfitCategory,
+    pub scene: AssetPath<'static>,
+
+    /// Name of the skeleton bone to attach the scene to.
+    ///
+    /// Used for rigid pieces, which should be modeled in the bone space.
+    /// If not set, skinned meshes of the scene will be bound to the actor skeleton by joint names.
+    #[serde(default)]
+    pub bone: Option<String>,
+}
+
+impl MapPaths for OutfitManifest {
+    fn map_paths(&mut self, dir: &Path) {
+        asset::change_parent_dir(&mut self.scene, dir);
+    }
+}
+
+/// Slot that an outfit piece occupies.
+///
+/// Actor can wear only one piece per category.
+#[derive(Clone, Copy, Debug, Deserialize, EnumCount, EnumIter, Eq, Hash, PartialEq, Serialize)]
+pub enum OutfitCategory {
+    Hair,
+    Top,
+    Bottom,
+    Shoes,
+}
+
+impl OutfitCategory {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            OutfitCategory::Hair => "💇",
+            OutfitCategory::Top => "👕",
+            OutfitCategory::Bottom => "👖",
+            OutfitCategory::Shoes => "👟",
+        }
+    }
+}
+
+pub(super) struct OutfitManifestDeserializer<'a> {
+    pub(super) dir: Option<&'a Path>,
+}
+
+impl<'de> DeserializeSeed<'de> for OutfitManifestDeserializer<'_> {
+    type Value = OutfitManifest;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        OutfitManifest::deserialize(deserializer).map(|mut manifest| {
+            if let Some(dir) = self.dir {
+                manifest.map_paths(dir);
+            }
+            manifest
+        })
+    }
+}
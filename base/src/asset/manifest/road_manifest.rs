@@ -46,6 +46,10 @@ pub struct RoadManifest {
     pub material: AssetPath<'static>,
     pub preview: AssetPath<'static>,
     pub half_width: f32,
+
+    /// Width of walkable area on each side of the road.
+    #[serde(default)]
+    pub sidewalk_width: f32,
 }
 
 impl MapPaths for RoadManifest {
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    scene::ron,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{GeneralManifest, ManifestFormat};
+use crate::game_world::actor::needs::NeedKind;
+
+#[derive(Default)]
+pub(super) struct TraitLoader;
+
+impl AssetLoader for TraitLoader {
+    type Asset = TraitManifest;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut string = String::new();
+        reader.read_to_string(&mut string).await?;
+
+        let manifest = ron::from_str(&string)?;
+
+        Ok(manifest)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        ManifestFormat::Trait.extensions()
+    }
+}
+
+/// Personality trait that can be assigned to actors in the family editor.
+#[derive(TypePath, Serialize, Deserialize, Asset)]
+pub struct TraitManifest {
+    pub general: GeneralManifest,
+    pub glyph: String,
+    pub description: String,
+
+    /// Multipliers for need decay rates.
+    ///
+    /// Needs that are not listed decay at the normal rate.
+    #[serde(default)]
+    pub need_rates: HashMap<NeedKind, f32>,
+
+    /// Multiplier for need gains from social tasks.
+    #[serde(default = "default_multiplier")]
+    pub social: f32,
+}
+
+fn default_multiplier() -> f32 {
+    1.0
+}
@@ -121,6 +121,10 @@ mod tests {
                 .into_iter()
                 .filter_map(|entry| entry.ok())
             {
+                // Ground manifests share the extension.
+                if entry.path().to_string_lossy().ends_with(".ground.ron") {
+                    continue;
+                }
                 if let Some(extension) = entry.path().extension() {
                     if extension == MATERIAL_EXTENSION {
                         let data = fs::read_to_string(entry.path())?;
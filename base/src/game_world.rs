@@ -1,5 +1,7 @@
 pub mod actor;
+pub mod chat;
 pub mod city;
+pub mod clock;
 pub mod commands_history;
 pub mod family;
 pub mod highlighting;
@@ -7,6 +9,8 @@ pub mod navigation;
 pub mod object;
 mod player_camera;
 mod segment;
+#[cfg(any(test, feature = "test_app"))]
+pub mod test_app;
 
 use std::fs;
 
@@ -20,8 +24,10 @@ use bevy_replicon::prelude::*;
 use serde::de::DeserializeSeed;
 
 use super::{core::GameState, error_message::error_message, game_paths::GamePaths};
-use actor::{Actor, ActorPlugin};
+use actor::{travel::TravelTimer, Actor, ActorPlugin};
+use chat::ChatPlugin;
 use city::CityPlugin;
+use clock::{ClockPlugin, WorldClock};
 use commands_history::CommandHistoryPlugin;
 use family::FamilyPlugin;
 use highlighting::HighlightingPlugin;
@@ -36,7 +42,9 @@ impl Plugin for GameWorldPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
             ActorPlugin,
+            ChatPlugin,
             CityPlugin,
+            ClockPlugin,
             SegmentPlugin,
             FamilyPlugin,
             HighlightingPlugin,
@@ -74,11 +82,14 @@ fn save(
     fs::create_dir_all(&game_paths.worlds)
         .with_context(|| format!("unable to create {world_path:?}"))?;
 
-    // Extract components that we don't replicate, but serialize.
+    // Extract components and resources that we don't replicate, but serialize.
     let mut scene = DynamicSceneBuilder::from_world(world)
         .deny_all()
         .allow_component::<Transform>()
+        .allow_component::<TravelTimer>()
+        .allow_resource::<WorldClock>()
         .extract_entities(actors.iter())
+        .extract_resources()
         .build();
 
     // Extract all replicated components that are reflected.
@@ -166,4 +177,5 @@ pub(super) enum Layer {
     PlacingWall,
     Road,
     PlacingRoad,
+    Water,
 }
@@ -1,7 +1,16 @@
+pub mod aging;
 mod animation_state;
+pub mod appearance;
+mod death;
 pub(super) mod human;
+pub mod job;
+pub mod mood;
 pub mod needs;
+pub mod skills;
 pub mod task;
+mod thought_bubble;
+pub mod traits;
+pub mod travel;
 
 use std::fmt::Write;
 
@@ -27,17 +36,40 @@ use crate::{
     asset::collection::{AssetCollection, Collection},
     core::GameState,
 };
+use aging::{Age, AgingPlugin, LifeStage};
 use animation_state::{AnimationState, AnimationStatePlugin};
+use appearance::AppearancePlugin;
+use death::{DeathPlugin, Deprivation};
 use human::HumanPlugin;
+use job::JobPlugin;
+use mood::{Mood, MoodPlugin};
 use needs::NeedsPlugin;
+use skills::SkillsPlugin;
 use task::{TaskGroups, TaskPlugin};
+use thought_bubble::ThoughtBubblePlugin;
+use traits::{Traits, TraitsPlugin};
+use travel::TravelPlugin;
 
 pub(super) struct ActorPlugin;
 
 impl Plugin for ActorPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Collection<ActorAnimation>>()
-            .add_plugins((AnimationStatePlugin, NeedsPlugin, HumanPlugin, TaskPlugin))
+            .add_plugins((
+                AgingPlugin,
+                AnimationStatePlugin,
+                AppearancePlugin,
+                DeathPlugin,
+                NeedsPlugin,
+                HumanPlugin,
+                JobPlugin,
+                MoodPlugin,
+                SkillsPlugin,
+                TaskPlugin,
+                ThoughtBubblePlugin,
+                TraitsPlugin,
+                TravelPlugin,
+            ))
             .register_type::<Transform>()
             .register_type::<Actor>()
             .register_type::<FirstName>()
@@ -127,6 +159,11 @@ pub struct SelectedActor;
     FirstName,
     LastName,
     Sex,
+    Traits,
+    Age,
+    LifeStage,
+    Deprivation,
+    Mood,
     Replicated,
     ParentSync,
     Navigation,
@@ -0,0 +1,266 @@
+use bevy::{ecs::entity::MapEntities, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use super::Actor;
+use crate::{
+    core::GameState,
+    game_world::{clock::WorldClock, family::Family},
+};
+
+pub(super) struct AgingPlugin;
+
+impl Plugin for AgingPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Age>()
+            .register_type::<LifeStage>()
+            .register_type::<AgingSettings>()
+            .replicate::<Age>()
+            .replicate::<LifeStage>()
+            .replicate::<AgingSettings>()
+            .add_mapped_client_event::<AgingSettingsChange>(ChannelKind::Unordered)
+            .add_systems(
+                PreUpdate,
+                apply_settings
+                    .after(ClientSet::Receive)
+                    .run_if(server_or_singleplayer),
+            )
+            .add_systems(
+                Update,
+                (
+                    (grow, update_stages).chain().run_if(server_or_singleplayer),
+                    update_scales,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn apply_settings(
+    mut change_events: EventReader<FromClient<AgingSettingsChange>>,
+    mut families: Query<&mut AgingSettings, With<Family>>,
+) {
+    for FromClient { client_id, event } in change_events.read() {
+        match families.get_mut(event.family_entity) {
+            Ok(mut settings) => {
+                info!(
+                    "`{client_id:?}` changes aging settings for family `{}`",
+                    event.family_entity
+                );
+                *settings = event.settings;
+            }
+            Err(e) => error!(
+                "unable to change aging settings for `{}`: {e}",
+                event.family_entity
+            ),
+        }
+    }
+}
+
+/// Increments age of all actors when a new day starts.
+fn grow(
+    mut last_day: Local<Option<u32>>,
+    clock: Res<WorldClock>,
+    families: Query<&AgingSettings>,
+    mut actors: Query<(Entity, &Actor, &mut Age)>,
+) {
+    let day = clock.day();
+    let Some(last_day) = last_day.replace(day) else {
+        return;
+    };
+    if day <= last_day {
+        return;
+    }
+
+    let passed_days = day - last_day;
+    for (entity, actor, mut age) in &mut actors {
+        let Ok(settings) = families.get(actor.family_entity) else {
+            continue;
+        };
+        if settings.enabled {
+            debug!("aging `{entity}` by {passed_days} days");
+            **age += passed_days;
+        }
+    }
+}
+
+fn update_stages(
+    families: Query<Ref<AgingSettings>>,
+    mut actors: Query<(Entity, &Actor, Ref<Age>, &mut LifeStage)>,
+) {
+    for (entity, actor, age, mut stage) in &mut actors {
+        let Ok(settings) = families.get(actor.family_entity) else {
+            continue;
+        };
+        if !age.is_changed() && !settings.is_changed() {
+            continue;
+        }
+
+        let new_stage = LifeStage::new(**age, settings.lifespan);
+        if stage.set_if_neq(new_stage) {
+            info!("`{entity}` becomes `{new_stage:?}`");
+        }
+    }
+}
+
+fn update_scales(mut actors: Query<(&LifeStage, &mut Transform), Changed<LifeStage>>) {
+    for (stage, mut transform) in &mut actors {
+        transform.scale = Vec3::splat(stage.scale());
+    }
+}
+
+/// Age of an actor in days.
+#[derive(Component, Clone, Copy, Deref, DerefMut, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Age(u32);
+
+impl Default for Age {
+    fn default() -> Self {
+        // New actors start as young adults.
+        Self(LifeStage::Adult.start_day(Lifespan::Normal))
+    }
+}
+
+/// Life stage of an actor.
+///
+/// Calculated from [`Age`] and the family's [`AgingSettings`].
+#[derive(
+    Component,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    EnumIter,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Reflect,
+    Serialize,
+)]
+#[reflect(Component)]
+pub enum LifeStage {
+    Baby,
+    Child,
+    Teen,
+    #[default]
+    Adult,
+    Elder,
+}
+
+impl LifeStage {
+    fn new(age: u32, lifespan: Lifespan) -> Self {
+        [Self::Elder, Self::Adult, Self::Teen, Self::Child]
+            .into_iter()
+            .find(|stage| age >= stage.start_day(lifespan))
+            .unwrap_or(Self::Baby)
+    }
+
+    /// Returns the age in days at which the stage begins.
+    fn start_day(self, lifespan: Lifespan) -> u32 {
+        let days = match self {
+            Self::Baby => 0,
+            Self::Child => 2,
+            Self::Teen => 7,
+            Self::Adult => 14,
+            Self::Elder => 42,
+        };
+
+        (days as f32 * lifespan.multiplier()) as u32
+    }
+
+    /// Returns actor model scale.
+    fn scale(self) -> f32 {
+        match self {
+            Self::Baby => 0.4,
+            Self::Child => 0.65,
+            Self::Teen => 0.9,
+            Self::Adult => 1.0,
+            Self::Elder => 0.95,
+        }
+    }
+
+    /// Returns movement speed multiplier.
+    pub(crate) fn speed_factor(self) -> f32 {
+        match self {
+            Self::Baby => 0.3,
+            Self::Child => 0.9,
+            Self::Teen | Self::Adult => 1.0,
+            Self::Elder => 0.7,
+        }
+    }
+
+    /// Returns `true` if actors at this stage can walk on their own.
+    pub(super) fn can_walk(self) -> bool {
+        self != Self::Baby
+    }
+
+    /// Returns `true` if actors at this stage can participate in conversations.
+    pub(super) fn can_talk(self) -> bool {
+        self >= Self::Child
+    }
+
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Baby => "👶",
+            Self::Child => "🧒",
+            Self::Teen => "🧑",
+            Self::Adult => "🧑",
+            Self::Elder => "🧓",
+        }
+    }
+}
+
+/// Aging configuration for a family.
+#[derive(Component, Clone, Copy, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct AgingSettings {
+    /// Whether family members grow older.
+    pub enabled: bool,
+    pub lifespan: Lifespan,
+}
+
+impl Default for AgingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lifespan: Default::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, EnumIter, PartialEq, Reflect, Serialize)]
+pub enum Lifespan {
+    Short,
+    #[default]
+    Normal,
+    Long,
+}
+
+impl Lifespan {
+    fn multiplier(self) -> f32 {
+        match self {
+            Self::Short => 0.5,
+            Self::Normal => 1.0,
+            Self::Long => 2.0,
+        }
+    }
+}
+
+/// An event of changing [`AgingSettings`] for a family.
+///
+/// Emitted by players.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct AgingSettingsChange {
+    pub family_entity: Entity,
+    pub settings: AgingSettings,
+}
+
+impl MapEntities for AgingSettingsChange {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.family_entity = entity_mapper.map_entity(self.family_entity);
+    }
+}
@@ -1,7 +1,10 @@
 use bevy::{animation::RepeatAnimation, prelude::*, scene::SceneInstanceReady, utils::Duration};
 use strum::EnumCount;
 
-use super::{ActorAnimation, Movement, Sex};
+use super::{
+    mood::{Mood, MoodLevel},
+    ActorAnimation, Movement, Sex,
+};
 use crate::{
     asset::collection::Collection,
     core::GameState,
@@ -51,6 +54,11 @@ fn init_scene(
         };
 
         state.nodes[AnimationNode::Idle as usize] = graph.add_clip(idle_handle, 1.0, graph.root);
+        state.nodes[AnimationNode::SadIdle as usize] = graph.add_clip(
+            actor_animations.handle(ActorAnimation::ThoughtfulNod),
+            1.0,
+            graph.root,
+        );
         state.nodes[AnimationNode::Walk as usize] = graph.add_clip(walk_handle, 1.0, graph.root);
         state.nodes[AnimationNode::Run as usize] = graph.add_clip(run_handle, 1.0, graph.root);
         state.nodes[AnimationNode::Montage as usize] = graph.add_blend(1.0, graph.root);
@@ -72,7 +80,13 @@ fn init_scene(
 
 fn update(
     mut commands: Commands,
-    mut actors: Query<(Entity, &mut AnimationState, &Navigation, Ref<NavPath>)>,
+    mut actors: Query<(
+        Entity,
+        &mut AnimationState,
+        &Navigation,
+        Ref<NavPath>,
+        Option<&Mood>,
+    )>,
     mut players: Query<(
         &mut AnimationPlayer,
         &mut AnimationTransitions,
@@ -80,7 +94,7 @@ fn update(
     )>,
     mut graphs: ResMut<Assets<AnimationGraph>>,
 ) {
-    for (actor_entity, mut state, navigation, path) in &mut actors {
+    for (actor_entity, mut state, navigation, path, mood) in &mut actors {
         let Some(player_entity) = state.player_entity else {
             continue;
         };
@@ -122,7 +136,11 @@ fn update(
         }
 
         let node = if path.is_empty() {
-            AnimationNode::Idle
+            if mood.is_some_and(|mood| mood.level() <= MoodLevel::Sad) {
+                AnimationNode::SadIdle
+            } else {
+                AnimationNode::Idle
+            }
         } else if navigation.speed() <= Movement::Walk.speed() {
             AnimationNode::Walk
         } else {
@@ -145,7 +163,7 @@ const DEFAULT_TRANSITION_TIME: Duration = Duration::from_millis(200);
 
 /// Manages actor animations based on the current state.
 ///
-/// State animations are driven by the actor's navigation speed.
+/// State animations are driven by the actor's navigation speed and mood.
 /// State animations can be temporarily overridden by a montage.
 #[derive(Component, Default)]
 pub(super) struct AnimationState {
@@ -209,6 +227,8 @@ pub(super) struct MontageFinished;
 enum AnimationNode {
     #[default]
     Idle,
+    /// Idle for actors in a bad mood.
+    SadIdle,
     Walk,
     Run,
     Montage,
@@ -0,0 +1,334 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    asset::AssetPath,
+    ecs::system::SystemParam,
+    gltf::GltfMaterialName,
+    prelude::*,
+    render::mesh::{morph::MorphWeights, skinning::SkinnedMesh},
+    scene::SceneInstanceReady,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, EnumIter};
+
+use crate::{
+    asset::manifest::outfit_manifest::{OutfitCategory, OutfitManifest},
+    game_world::family::editor::EditorAppearance,
+};
+
+pub(super) struct AppearancePlugin;
+
+impl Plugin for AppearancePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Appearance>()
+            .replicate::<Appearance>()
+            .add_observer(init_scene::<Appearance>)
+            .add_observer(init_scene::<EditorAppearance>)
+            .add_observer(bind_skin)
+            .add_systems(
+                PostUpdate,
+                (update::<Appearance>, update::<EditorAppearance>),
+            );
+    }
+}
+
+/// Name of the actor scene material that will be tinted with the skin tone.
+const SKIN_MATERIAL: &str = "Alpha_Body_MAT";
+
+fn init_scene<C: Component + AsRef<Appearance>>(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    mut applier: AppearanceApplier,
+    actors: Query<&C>,
+) {
+    if let Ok(appearance) = actors.get(trigger.entity()) {
+        applier.apply(&mut commands, trigger.entity(), appearance.as_ref());
+    }
+}
+
+fn update<C: Component + AsRef<Appearance>>(
+    mut commands: Commands,
+    mut applier: AppearanceApplier,
+    actors: Query<(Entity, Ref<C>)>,
+) {
+    // Newly added actors will be initialized after the scene spawn.
+    for (entity, appearance) in actors
+        .iter()
+        .filter(|(_, appearance)| appearance.is_changed() && !appearance.is_added())
+    {
+        applier.apply(&mut commands, entity, (*appearance).as_ref());
+    }
+}
+
+/// Binds joints of skinned outfit pieces to the skeleton of the wearer.
+fn bind_skin(
+    trigger: Trigger<SceneInstanceReady>,
+    pieces: Query<&OutfitPiece, With<SkinnedPiece>>,
+    all_pieces: Query<(Entity, &OutfitPiece)>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    mut skinned_meshes: Query<&mut SkinnedMesh>,
+) {
+    let Ok(piece) = pieces.get(trigger.entity()) else {
+        return;
+    };
+
+    let outfit_entities: HashSet<_> = all_pieces
+        .iter()
+        .filter(|(_, other)| other.actor_entity == piece.actor_entity)
+        .flat_map(|(entity, _)| children.iter_descendants(entity))
+        .collect();
+
+    let mut bones = HashMap::new();
+    for entity in children
+        .iter_descendants(piece.actor_entity)
+        .filter(|entity| !outfit_entities.contains(entity))
+    {
+        if let Ok(name) = names.get(entity) {
+            bones.entry(name.as_str()).or_insert(entity);
+        }
+    }
+
+    debug!(
+        "binding skin of `{}` to `{}`",
+        trigger.entity(),
+        piece.actor_entity
+    );
+    let mut iter = skinned_meshes.iter_many_mut(children.iter_descendants(trigger.entity()));
+    while let Some(mut skinned_mesh) = iter.fetch_next() {
+        for joint in &mut skinned_mesh.joints {
+            let Ok(name) = names.get(*joint) else {
+                continue;
+            };
+            if let Some(&bone_entity) = bones.get(name.as_str()) {
+                *joint = bone_entity;
+            } else {
+                warn!("actor `{}` doesn't have bone '{name}'", piece.actor_entity);
+            }
+        }
+    }
+}
+
+/// Applies [`Appearance`] to the actor scene.
+#[derive(SystemParam)]
+struct AppearanceApplier<'w, 's> {
+    asset_server: Res<'w, AssetServer>,
+    outfit_manifests: Res<'w, Assets<OutfitManifest>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    children: Query<'w, 's, &'static Children>,
+    names: Query<'w, 's, &'static Name>,
+    pieces: Query<'w, 's, (Entity, &'static OutfitPiece)>,
+    morph_weights: Query<'w, 's, &'static mut MorphWeights>,
+    skin_materials: Query<
+        'w,
+        's,
+        (
+            &'static GltfMaterialName,
+            &'static mut MeshMaterial3d<StandardMaterial>,
+        ),
+    >,
+}
+
+impl AppearanceApplier<'_, '_> {
+    fn apply(&mut self, commands: &mut Commands, actor_entity: Entity, appearance: &Appearance) {
+        debug!("applying appearance for `{actor_entity}`");
+        for (entity, piece) in &self.pieces {
+            if piece.actor_entity == actor_entity {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let mut iter = self
+            .morph_weights
+            .iter_many_mut(self.children.iter_descendants(actor_entity));
+        while let Some(mut morph_weights) = iter.fetch_next() {
+            for (weight, &value) in morph_weights
+                .weights_mut()
+                .iter_mut()
+                .zip(&appearance.body_shape)
+            {
+                *weight = dequantize(value);
+            }
+        }
+
+        let skin_color = appearance.skin_color();
+        let mut iter = self
+            .skin_materials
+            .iter_many_mut(self.children.iter_descendants(actor_entity));
+        while let Some((material_name, mut material_handle)) = iter.fetch_next() {
+            if material_name.0 != SKIN_MATERIAL {
+                continue;
+            }
+            let Some(material) = self.materials.get(&*material_handle) else {
+                continue;
+            };
+
+            // Scene materials are shared between actors.
+            let mut material = material.clone();
+            material.base_color = skin_color;
+            *material_handle = self.materials.add(material).into();
+        }
+
+        for path in appearance.outfit.iter().flatten() {
+            let Some(manifest) = self
+                .asset_server
+                .get_handle(path)
+                .and_then(|handle| self.outfit_manifests.get(&handle))
+            else {
+                error!("'{path}' is missing, ignoring");
+                continue;
+            };
+
+            let scene_root = SceneRoot(self.asset_server.load(manifest.scene.clone()));
+            let piece = OutfitPiece { actor_entity };
+            if let Some(bone) = &manifest.bone {
+                let Some(bone_entity) =
+                    self.children
+                        .iter_descendants(actor_entity)
+                        .find(|&entity| {
+                            self.names
+                                .get(entity)
+                                .is_ok_and(|name| name.as_str() == bone)
+                        })
+                else {
+                    // The scene could be not spawned yet, the piece will be attached on spawn.
+                    debug!("unable to find bone '{bone}' for `{actor_entity}`");
+                    continue;
+                };
+                commands.entity(bone_entity).with_child((piece, scene_root));
+            } else {
+                commands
+                    .entity(actor_entity)
+                    .with_child((piece, SkinnedPiece, scene_root));
+            }
+        }
+    }
+}
+
+/// Visual look of an actor.
+///
+/// Replicated in a compact form: body shape weights and skin tone are quantized into bytes.
+#[derive(Component, Clone, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Appearance {
+    /// Morph target weights.
+    body_shape: [u8; BodyShape::COUNT],
+
+    /// Index in [`Appearance::SKIN_TONES`].
+    skin_tone: u8,
+
+    /// Paths to outfit manifests for each category.
+    outfit: [Option<AssetPath<'static>>; OutfitCategory::COUNT],
+}
+
+impl Appearance {
+    pub const SKIN_TONES: [Color; 8] = [
+        Color::srgb(0.98, 0.87, 0.78),
+        Color::srgb(0.94, 0.78, 0.66),
+        Color::srgb(0.87, 0.69, 0.55),
+        Color::srgb(0.78, 0.58, 0.44),
+        Color::srgb(0.65, 0.46, 0.33),
+        Color::srgb(0.52, 0.35, 0.24),
+        Color::srgb(0.38, 0.25, 0.17),
+        Color::srgb(0.25, 0.16, 0.11),
+    ];
+
+    pub fn body_shape(&self, shape: BodyShape) -> f32 {
+        dequantize(self.body_shape[shape as usize])
+    }
+
+    /// Sets weight for the shape, the value will be clamped to `0.0..=1.0`.
+    pub fn set_body_shape(&mut self, shape: BodyShape, weight: f32) {
+        self.body_shape[shape as usize] = (weight.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8;
+    }
+
+    pub fn skin_tone(&self) -> usize {
+        self.skin_tone.into()
+    }
+
+    /// Sets index of the tone from [`Self::SKIN_TONES`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn set_skin_tone(&mut self, index: usize) {
+        assert!(
+            index < Self::SKIN_TONES.len(),
+            "skin tone index should be less than {}",
+            Self::SKIN_TONES.len()
+        );
+        self.skin_tone = index as u8;
+    }
+
+    pub fn skin_color(&self) -> Color {
+        Self::SKIN_TONES
+            .get(self.skin_tone())
+            .copied()
+            .unwrap_or(Self::SKIN_TONES[0])
+    }
+
+    pub fn outfit(&self, category: OutfitCategory) -> Option<&AssetPath<'static>> {
+        self.outfit[category as usize].as_ref()
+    }
+
+    /// Replaces the piece for the category.
+    pub fn set_outfit(&mut self, category: OutfitCategory, path: Option<AssetPath<'static>>) {
+        self.outfit[category as usize] = path;
+    }
+}
+
+impl Default for Appearance {
+    fn default() -> Self {
+        Self {
+            body_shape: Default::default(),
+            skin_tone: 2,
+            outfit: Default::default(),
+        }
+    }
+}
+
+impl AsRef<Appearance> for Appearance {
+    fn as_ref(&self) -> &Appearance {
+        self
+    }
+}
+
+impl From<EditorAppearance> for Appearance {
+    fn from(value: EditorAppearance) -> Self {
+        value.0
+    }
+}
+
+fn dequantize(value: u8) -> f32 {
+    value as f32 / u8::MAX as f32
+}
+
+/// Body shape morph targets.
+///
+/// Variants are mapped to morph target indices of actor meshes.
+/// Meshes without morph targets are not affected.
+#[derive(Clone, Copy, Debug, EnumCount, EnumIter)]
+pub enum BodyShape {
+    Weight,
+    Muscles,
+}
+
+impl BodyShape {
+    pub fn name(self) -> &'static str {
+        match self {
+            BodyShape::Weight => "Weight",
+            BodyShape::Muscles => "Muscles",
+        }
+    }
+}
+
+/// Spawned outfit scene.
+#[derive(Component)]
+struct OutfitPiece {
+    actor_entity: Entity,
+}
+
+/// Marks [`OutfitPiece`] that should be bound to the actor skeleton.
+#[derive(Component)]
+struct SkinnedPiece;
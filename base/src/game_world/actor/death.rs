@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_replicon::prelude::*;
+
+use super::{
+    needs::Need,
+    task::{ActiveTask, Task},
+    Actor,
+};
+use crate::game_world::{family::FamilyMembers, navigation::following::Following};
+
+pub(super) struct DeathPlugin;
+
+impl Plugin for DeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(remove_member).add_systems(
+            Update,
+            update_deprivation
+                .run_if(on_timer(Duration::from_secs(1)))
+                .run_if(server_or_singleplayer),
+        );
+    }
+}
+
+/// Number of seconds an actor can survive with a depleted need.
+///
+/// Equals to 12 in-game hours at normal speed.
+const MAX_DEPRIVATION: u32 = 12 * 60;
+
+fn update_deprivation(
+    mut commands: Commands,
+    mut actors: Query<(Entity, &Name, &Children, &mut Deprivation)>,
+    needs: Query<&Need>,
+    followers: Query<(&Following, &Children)>,
+    tasks: Query<Entity, (With<Task>, With<ActiveTask>)>,
+) {
+    for (entity, name, children, mut deprivation) in &mut actors {
+        if needs.iter_many(children).any(|need| need.0 <= 0.0) {
+            **deprivation += 1;
+        } else {
+            **deprivation = 0;
+        }
+
+        if **deprivation < MAX_DEPRIVATION {
+            continue;
+        }
+
+        info!("`{entity}` ('{name}') dies");
+        // Cancel tasks that target the actor.
+        for (following, follower_children) in &followers {
+            if following.0 == entity {
+                for task_entity in tasks.iter_many(follower_children) {
+                    commands.entity(task_entity).despawn();
+                }
+            }
+        }
+
+        // Linked tasks of other actors are removed on despawn.
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn remove_member(
+    trigger: Trigger<OnRemove, Actor>,
+    actors: Query<&Actor>,
+    mut families: Query<&mut FamilyMembers>,
+) {
+    let actor = actors.get(trigger.entity()).unwrap();
+    if let Ok(mut members) = families.get_mut(actor.family_entity) {
+        debug!(
+            "removing `{}` from family `{}`",
+            trigger.entity(),
+            actor.family_entity
+        );
+        members.retain(|&entity| entity != trigger.entity());
+    }
+}
+
+/// Number of seconds during which at least one of the actor needs was depleted.
+#[derive(Component, Default, Deref, DerefMut)]
+pub(super) struct Deprivation(u32);
@@ -5,13 +5,17 @@ use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 use super::{
+    appearance::Appearance,
     needs::{Bladder, Energy, Fun, Hunger, Hygiene, Need, Social},
+    skills::{Charisma, Cooking, Fitness, Logic, Skill},
+    traits::Traits,
     FirstName, LastName, Sex,
 };
 use crate::{
     asset::collection::{AssetCollection, Collection},
     game_world::family::editor::{
-        ActorBundle, EditorFirstName, EditorLastName, EditorSex, FamilyScene, ReflectActorBundle,
+        ActorBundle, EditorAppearance, EditorFirstName, EditorLastName, EditorSex, EditorTraits,
+        FamilyScene, ReflectActorBundle,
     },
 };
 
@@ -24,6 +28,7 @@ impl Plugin for HumanPlugin {
             .register_type::<HumanBundle>()
             .init_resource::<Collection<HumanScene>>()
             .add_observer(init_needs)
+            .add_observer(init_skills)
             .add_systems(Update, (update_sex::<EditorSex>, update_sex::<Sex>))
             .add_systems(PostUpdate, fill_scene.run_if(resource_added::<FamilyScene>));
     }
@@ -52,6 +57,27 @@ fn init_needs(
     }
 }
 
+fn init_skills(
+    trigger: Trigger<OnAdd, Children>,
+    mut commands: Commands,
+    actors: Query<&Children, With<Human>>,
+    skills: Query<(), With<Skill>>,
+) {
+    let Ok(children) = actors.get(trigger.entity()) else {
+        return;
+    };
+
+    if skills.iter_many(children).next().is_none() {
+        debug!("initializing human skills `{}`", trigger.entity());
+        commands.entity(trigger.entity()).with_children(|parent| {
+            parent.spawn(Charisma);
+            parent.spawn(Cooking);
+            parent.spawn(Fitness);
+            parent.spawn(Logic);
+        });
+    }
+}
+
 fn update_sex<C: Component + Into<HumanScene> + Copy>(
     human_scenes: Res<Collection<HumanScene>>,
     mut actors: Query<(Entity, &C, &mut SceneRoot), Changed<C>>,
@@ -65,9 +91,18 @@ fn update_sex<C: Component + Into<HumanScene> + Copy>(
 /// Fills [`FamilyScene`] with editing human actors.
 fn fill_scene(
     mut family_scene: ResMut<FamilyScene>,
-    actors: Query<(&EditorFirstName, &EditorLastName, &EditorSex), With<EditorHuman>>,
+    actors: Query<
+        (
+            &EditorFirstName,
+            &EditorLastName,
+            &EditorSex,
+            &EditorTraits,
+            &EditorAppearance,
+        ),
+        With<EditorHuman>,
+    >,
 ) {
-    for (first_name, last_name, &sex) in &actors {
+    for (first_name, last_name, &sex, traits, appearance) in &actors {
         debug!(
             "adding human '{} {}' to family scene '{}'",
             first_name.0, last_name.0, family_scene.name
@@ -76,6 +111,8 @@ fn fill_scene(
             first_name: first_name.clone().into(),
             last_name: last_name.clone().into(),
             sex: sex.into(),
+            traits: traits.clone().into(),
+            appearance: appearance.clone().into(),
             human: Human,
         }));
     }
@@ -94,6 +131,8 @@ struct HumanBundle {
     first_name: FirstName,
     last_name: LastName,
     sex: Sex,
+    traits: Traits,
+    appearance: Appearance,
     human: Human,
 }
 
@@ -0,0 +1,245 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::AssetPath, ecs::entity::MapEntities, prelude::*, time::common_conditions::on_timer,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    mood::Mood,
+    skills::{Skill, SkillKind},
+    travel::{Departure, TravelDestination, Traveling},
+    Actor, Movement,
+};
+use crate::{
+    asset::manifest::career_manifest::CareerManifest,
+    core::GameState,
+    game_world::{
+        clock::WorldClock,
+        family::{Budget, FamilyControllers},
+        navigation::{NavDestination, Navigation},
+    },
+};
+
+pub(super) struct JobPlugin;
+
+impl Plugin for JobPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Job>()
+            .register_type::<AtWork>()
+            .replicate::<Job>()
+            .replicate::<AtWork>()
+            .add_mapped_client_event::<JobChange>(ChannelKind::Unordered)
+            .add_observer(hide)
+            .add_observer(show)
+            .add_systems(
+                PreUpdate,
+                change_jobs
+                    .after(ClientSet::Receive)
+                    .run_if(server_or_singleplayer),
+            )
+            .add_systems(
+                Update,
+                update_shifts
+                    .run_if(on_timer(Duration::from_secs(1)))
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn change_jobs(
+    mut commands: Commands,
+    controllers: Res<FamilyControllers>,
+    mut change_events: EventReader<FromClient<JobChange>>,
+    actors: Query<&Actor>,
+) {
+    for FromClient { client_id, event } in change_events.read() {
+        let actor = match actors.get(event.actor_entity) {
+            Ok(actor) => actor,
+            Err(e) => {
+                error!("unable to change job for `{}`: {e}", event.actor_entity);
+                continue;
+            }
+        };
+        if !controllers.controls(*client_id, actor.family_entity) {
+            error!(
+                "`{client_id:?}` tried to change job for `{}` from another family",
+                event.actor_entity
+            );
+            continue;
+        }
+
+        info!(
+            "`{client_id:?}` changes job for `{}` to {:?}",
+            event.actor_entity, event.career
+        );
+        let mut entity = commands.entity(event.actor_entity);
+        match &event.career {
+            Some(career) => {
+                entity.remove::<AtWork>().insert(Job::new(career.clone()));
+            }
+            None => {
+                entity.remove::<(Job, AtWork)>();
+            }
+        }
+    }
+}
+
+/// Sends actors to work and pays them when the shift ends.
+///
+/// Actors walk to a city exit before the shift and walk back to where they left from after it.
+/// Only income is handled here, periodic expenses are charged by [`bills`](crate::game_world::family::bills).
+fn update_shifts(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<CareerManifest>>,
+    mut actors: Query<
+        (
+            Entity,
+            &Actor,
+            &Children,
+            &Mood,
+            &mut Job,
+            Option<&AtWork>,
+            Option<&Departure>,
+            &mut Navigation,
+            &mut NavDestination,
+        ),
+        Without<Traveling>,
+    >,
+    skills: Query<(&SkillKind, &Skill)>,
+    mut families: Query<&mut Budget>,
+) {
+    for (entity, actor, children, &mood, mut job, at_work, departure, mut navigation, mut dest) in
+        &mut actors
+    {
+        let Some(manifest) = asset_server
+            .get_handle(&job.career)
+            .and_then(|handle| manifests.get(&handle))
+        else {
+            error!("'{}' is missing, ignoring", job.career);
+            continue;
+        };
+
+        let level_index = job.level.min(manifest.levels.len() - 1);
+        let level = &manifest.levels[level_index];
+        let working_time = level.is_working_time(clock.time_of_day());
+        let going_to_work =
+            departure.is_some_and(|departure| departure.destination() == TravelDestination::Work);
+        if working_time && at_work.is_none() && departure.is_none() {
+            info!("`{entity}` goes to work as '{}'", level.name);
+            commands
+                .entity(entity)
+                .insert(Departure::new(TravelDestination::Work));
+        } else if !working_time && going_to_work {
+            info!("`{entity}` missed the shift");
+            commands.entity(entity).remove::<Departure>();
+        } else if let Some(at_work) = at_work.filter(|_| !working_time) {
+            let skill_factor = skills
+                .iter_many(children)
+                .find(|(&kind, _)| kind == manifest.skill)
+                .map(|(_, skill)| skill.gain_factor())
+                .unwrap_or(1.0);
+            let performance = mood.performance() * skill_factor;
+            let income = (level.pay as f32 * performance).round() as u32;
+            if let Ok(mut budget) = families.get_mut(actor.family_entity) {
+                info!("`{entity}` returns from work with {income}");
+                budget.earn(income);
+            }
+
+            job.performance = (job.performance + (performance - 1.0) * PERFORMANCE_GAIN)
+                .clamp(0.0, Job::MAX_PERFORMANCE);
+            if job.performance >= Job::MAX_PERFORMANCE && level_index + 1 < manifest.levels.len() {
+                info!(
+                    "`{entity}` promoted to '{}'",
+                    manifest.levels[level_index + 1].name
+                );
+                job.level = level_index + 1;
+                job.performance = Job::default_performance();
+            }
+
+            commands.entity(entity).remove::<AtWork>();
+            *navigation = Navigation::new(Movement::Walk.speed());
+            **dest = Some(at_work.home);
+        }
+    }
+}
+
+/// Performance change per shift for the work performance that differs from normal by 1.
+const PERFORMANCE_GAIN: f32 = 40.0;
+
+fn hide(trigger: Trigger<OnAdd, AtWork>, mut actors: Query<&mut Visibility>) {
+    debug!("hiding `{}` at work", trigger.entity());
+    let mut visibility = actors.get_mut(trigger.entity()).unwrap();
+    *visibility = Visibility::Hidden;
+}
+
+fn show(trigger: Trigger<OnRemove, AtWork>, mut actors: Query<&mut Visibility>) {
+    debug!("showing `{}` after work", trigger.entity());
+    if let Ok(mut visibility) = actors.get_mut(trigger.entity()) {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Career of an actor.
+#[derive(Component, Clone, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct Job {
+    /// Path to the career manifest.
+    pub career: AssetPath<'static>,
+
+    /// Index of the current career level.
+    pub level: usize,
+
+    /// Accumulated work performance.
+    ///
+    /// Actor gets promoted when it reaches [`Self::MAX_PERFORMANCE`].
+    pub performance: f32,
+}
+
+impl Job {
+    pub const MAX_PERFORMANCE: f32 = 100.0;
+
+    fn new(career: AssetPath<'static>) -> Self {
+        Self {
+            career,
+            level: 0,
+            performance: Self::default_performance(),
+        }
+    }
+
+    fn default_performance() -> f32 {
+        Self::MAX_PERFORMANCE / 2.0
+    }
+}
+
+/// Indicates that the actor is currently at work.
+///
+/// Inserted when the actor reaches a city exit.
+/// Such actors are hidden and excluded from navigation.
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct AtWork {
+    /// Position from which the actor left for work.
+    ///
+    /// The actor walks back to it after the shift.
+    pub(super) home: Vec3,
+}
+
+/// An event of assigning or removing a job for an actor.
+///
+/// Emitted by players.
+#[derive(Clone, Deserialize, Event, Serialize)]
+pub struct JobChange {
+    pub actor_entity: Entity,
+    pub career: Option<AssetPath<'static>>,
+}
+
+impl MapEntities for JobChange {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.actor_entity = entity_mapper.map_entity(self.actor_entity);
+    }
+}
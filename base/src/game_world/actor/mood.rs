@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use bevy::{asset::AssetPath, prelude::*, time::common_conditions::on_timer};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::needs::{Need, NeedKind};
+use crate::{
+    asset::manifest::mood_manifest::{MoodManifest, MoodTrigger},
+    core::GameState,
+    game_world::clock::WorldClock,
+};
+
+pub(super) struct MoodPlugin;
+
+impl Plugin for MoodPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Mood>()
+            .register_type::<MoodModifiers>()
+            .replicate::<Mood>()
+            .replicate::<MoodModifiers>()
+            .add_observer(apply_event)
+            .add_systems(
+                Update,
+                (update_modifiers, update_mood)
+                    .chain()
+                    .run_if(on_timer(Duration::from_secs(1)))
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Adds or refreshes modifiers triggered by the event.
+fn apply_event(
+    trigger: Trigger<MoodEvent>,
+    clock: Res<WorldClock>,
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<MoodManifest>>,
+    mut actors: Query<&mut MoodModifiers>,
+) {
+    let Ok(mut modifiers) = actors.get_mut(trigger.entity()) else {
+        return;
+    };
+
+    for (id, manifest) in manifests.iter() {
+        let MoodTrigger::Event { event, hours } = manifest.trigger else {
+            continue;
+        };
+        if event != *trigger.event() {
+            continue;
+        }
+        let Some(path) = asset_server.get_path(id) else {
+            continue;
+        };
+
+        debug!(
+            "applying '{}' to `{}`",
+            manifest.general.name,
+            trigger.entity()
+        );
+        let expires = clock.minutes() + (hours * 60.0) as u64;
+        modifiers.insert(path.into_owned(), Some(expires));
+    }
+}
+
+/// Removes expired modifiers and updates modifiers that depend on needs.
+fn update_modifiers(
+    clock: Res<WorldClock>,
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<MoodManifest>>,
+    mut actors: Query<(Entity, &Children, &mut MoodModifiers)>,
+    needs: Query<(&NeedKind, &Need)>,
+) {
+    let minutes = clock.minutes();
+    for (entity, children, mut modifiers) in &mut actors {
+        let expired =
+            |modifier: &MoodModifier| modifier.expires.is_some_and(|expires| expires <= minutes);
+        if modifiers.iter().any(expired) {
+            debug!("removing expired modifiers from `{entity}`");
+            modifiers.retain(|modifier| !expired(modifier));
+        }
+
+        for (id, manifest) in manifests.iter() {
+            let MoodTrigger::Need { kind, below } = manifest.trigger else {
+                continue;
+            };
+            let Some(path) = asset_server.get_path(id) else {
+                continue;
+            };
+
+            let active = needs
+                .iter_many(children)
+                .any(|(&need_kind, need)| need_kind == kind && need.0 < below);
+            let path = path.into_owned();
+            if active {
+                if !modifiers.contains(&path) {
+                    debug!("applying '{}' to `{entity}`", manifest.general.name);
+                    modifiers.insert(path, None);
+                }
+            } else if modifiers.contains(&path) {
+                debug!("removing '{}' from `{entity}`", manifest.general.name);
+                modifiers.remove(&path);
+            }
+        }
+    }
+}
+
+/// Calculates mood from the average of needs and active modifiers.
+fn update_mood(
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<MoodManifest>>,
+    mut actors: Query<(&Children, &MoodModifiers, &mut Mood)>,
+    needs: Query<&Need>,
+) {
+    for (children, modifiers, mut mood) in &mut actors {
+        let (sum, count) = needs
+            .iter_many(children)
+            .fold((0.0, 0), |(sum, count), need| (sum + need.0, count + 1));
+        let base = if count > 0 {
+            sum / count as f32
+        } else {
+            Mood::default().0
+        };
+
+        let modifiers_value: f32 = modifiers
+            .iter()
+            .filter_map(|modifier| asset_server.get_handle(&modifier.path))
+            .filter_map(|handle| manifests.get(&handle))
+            .map(|manifest| manifest.value)
+            .sum();
+
+        let value = (base + modifiers_value).clamp(0.0, 100.0);
+        // Avoid replicating insignificant changes.
+        if (mood.0 - value).abs() >= 0.5 {
+            mood.0 = value;
+        }
+    }
+}
+
+/// Emotional state of an actor in range `[0, 100]`.
+///
+/// Calculated on the server.
+#[derive(Component, Clone, Copy, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+#[require(MoodModifiers)]
+pub struct Mood(pub f32);
+
+impl Mood {
+    pub fn level(self) -> MoodLevel {
+        match self.0 {
+            ..20.0 => MoodLevel::Miserable,
+            ..40.0 => MoodLevel::Sad,
+            ..60.0 => MoodLevel::Neutral,
+            ..80.0 => MoodLevel::Happy,
+            _ => MoodLevel::Ecstatic,
+        }
+    }
+
+    /// Multiplier for task results.
+    ///
+    /// Equals to 1 for neutral mood.
+    pub fn performance(self) -> f32 {
+        0.75 + 0.5 * self.0 / 100.0
+    }
+}
+
+impl Default for Mood {
+    fn default() -> Self {
+        Self(50.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MoodLevel {
+    Miserable,
+    Sad,
+    Neutral,
+    Happy,
+    Ecstatic,
+}
+
+impl MoodLevel {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            MoodLevel::Miserable => "😭",
+            MoodLevel::Sad => "🙁",
+            MoodLevel::Neutral => "😐",
+            MoodLevel::Happy => "🙂",
+            MoodLevel::Ecstatic => "😄",
+        }
+    }
+}
+
+/// Active mood modifiers of an actor.
+#[derive(Component, Default, Deref, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct MoodModifiers(Vec<MoodModifier>);
+
+impl MoodModifiers {
+    fn contains(&self, path: &AssetPath<'static>) -> bool {
+        self.0.iter().any(|modifier| modifier.path == *path)
+    }
+
+    /// Inserts a new modifier or updates expiration time of the existing.
+    fn insert(&mut self, path: AssetPath<'static>, expires: Option<u64>) {
+        if let Some(modifier) = self.0.iter_mut().find(|modifier| modifier.path == path) {
+            modifier.expires = expires;
+        } else {
+            self.0.push(MoodModifier { path, expires });
+        }
+    }
+
+    fn remove(&mut self, path: &AssetPath<'static>) {
+        self.0.retain(|modifier| modifier.path != *path);
+    }
+
+    fn retain(&mut self, f: impl FnMut(&MoodModifier) -> bool) {
+        self.0.retain(f);
+    }
+}
+
+#[derive(Clone, Deserialize, Reflect, Serialize)]
+pub struct MoodModifier {
+    /// Path to the mood manifest.
+    pub path: AssetPath<'static>,
+
+    /// Game minute at which the modifier will be removed.
+    ///
+    /// Modifiers without expiration are removed when the trigger condition is no longer met.
+    pub expires: Option<u64>,
+}
+
+/// Event that triggers mood modifiers for the target actor.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Event, PartialEq, Serialize)]
+pub enum MoodEvent {
+    Chat,
+    Joke,
+}
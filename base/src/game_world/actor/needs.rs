@@ -31,10 +31,11 @@ impl Plugin for NeedsPlugin {
     }
 }
 
-fn update_values(mut needs: Query<(&mut Need, &NeedRate)>) {
-    for (mut need, rate) in &mut needs {
-        if need.0 > rate.0 {
-            need.0 += rate.0;
+fn update_values(mut needs: Query<(&mut Need, &NeedRate, &NeedRateFactor)>) {
+    for (mut need, rate, factor) in &mut needs {
+        let rate = rate.0 * factor.0;
+        if need.0 > rate {
+            need.0 += rate;
         } else {
             need.0 = 0.0;
         }
@@ -45,6 +46,7 @@ fn update_values(mut needs: Query<(&mut Need, &NeedRate)>) {
 #[reflect(Component)]
 #[require(
     Need,
+    NeedKind(|| NeedKind::Hunger),
     NeedGlyph(|| NeedGlyph("🍴")),
     NeedRate(|| NeedRate(-0.4)),
 )]
@@ -54,6 +56,7 @@ pub(crate) struct Hunger;
 #[reflect(Component)]
 #[require(
     Need,
+    NeedKind(|| NeedKind::Social),
     NeedGlyph(|| NeedGlyph("💬")),
     NeedRate(|| NeedRate(-0.1)),
 )]
@@ -63,6 +66,7 @@ pub(crate) struct Social;
 #[reflect(Component)]
 #[require(
     Need,
+    NeedKind(|| NeedKind::Hygiene),
     NeedGlyph(|| NeedGlyph("🚿")),
     NeedRate(|| NeedRate(-0.3)),
 )]
@@ -72,6 +76,7 @@ pub(crate) struct Hygiene;
 #[reflect(Component)]
 #[require(
     Need,
+    NeedKind(|| NeedKind::Fun),
     NeedGlyph(|| NeedGlyph("🎉")),
     NeedRate(|| NeedRate(-0.1)),
 )]
@@ -81,6 +86,7 @@ pub(crate) struct Fun;
 #[reflect(Component)]
 #[require(
     Need,
+    NeedKind(|| NeedKind::Energy),
     NeedGlyph(|| NeedGlyph("🔋")),
     NeedRate(|| NeedRate(-0.2)),
 )]
@@ -90,6 +96,7 @@ pub(crate) struct Energy;
 #[reflect(Component)]
 #[require(
     Need,
+    NeedKind(|| NeedKind::Bladder),
     NeedGlyph(|| NeedGlyph("🚽")),
     NeedRate(|| NeedRate(-0.5)),
 )]
@@ -97,7 +104,7 @@ pub(crate) struct Bladder;
 
 #[derive(Component, Debug, Deserialize, Reflect, Serialize)]
 #[reflect(Component)]
-#[require(ParentSync, Replicated)]
+#[require(ParentSync, Replicated, NeedRateFactor)]
 pub struct Need(pub f32);
 
 impl Default for Need {
@@ -109,5 +116,26 @@ impl Default for Need {
 #[derive(Component)]
 struct NeedRate(f32);
 
+/// Multiplier for [`NeedRate`] from actor traits.
+#[derive(Component)]
+pub(super) struct NeedRateFactor(pub(super) f32);
+
+impl Default for NeedRateFactor {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Identifies need type in manifests.
+#[derive(Clone, Component, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum NeedKind {
+    Hunger,
+    Social,
+    Hygiene,
+    Fun,
+    Energy,
+    Bladder,
+}
+
 #[derive(Component)]
 pub struct NeedGlyph(pub &'static str);
@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::task::ActiveTask;
+use crate::{core::GameState, game_world::clock::WorldClock};
+
+pub(super) struct SkillsPlugin;
+
+impl Plugin for SkillsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Cooking>()
+            .register_type::<Fitness>()
+            .register_type::<Charisma>()
+            .register_type::<Logic>()
+            .register_type::<Skill>()
+            .replicate::<Cooking>()
+            .replicate::<Fitness>()
+            .replicate::<Charisma>()
+            .replicate::<Logic>()
+            .replicate::<Skill>()
+            .add_systems(
+                Update,
+                train
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Adds experience to skills trained by active tasks for each passed game minute.
+fn train(
+    mut last_minute: Local<Option<u64>>,
+    clock: Res<WorldClock>,
+    tasks: Query<(&Parent, &SkillTraining), With<ActiveTask>>,
+    actors: Query<&Children>,
+    mut skills: Query<(&SkillKind, &mut Skill)>,
+) {
+    let minute = clock.minutes();
+    let Some(last_minute) = last_minute.replace(minute) else {
+        return;
+    };
+    if minute <= last_minute {
+        return;
+    }
+
+    let passed_minutes = (minute - last_minute) as u32;
+    for (parent, training) in &tasks {
+        let Ok(children) = actors.get(**parent) else {
+            continue;
+        };
+        let mut iter = skills.iter_many_mut(children);
+        while let Some((&kind, mut skill)) = iter.fetch_next() {
+            if kind == training.0 {
+                trace!("training {kind:?} for `{}`", **parent);
+                skill.add_experience(passed_minutes);
+            }
+        }
+    }
+}
+
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+#[require(
+    Skill,
+    SkillKind(|| SkillKind::Cooking),
+    SkillGlyph(|| SkillGlyph("🍳")),
+)]
+pub(crate) struct Cooking;
+
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+#[require(
+    Skill,
+    SkillKind(|| SkillKind::Fitness),
+    SkillGlyph(|| SkillGlyph("💪")),
+)]
+pub(crate) struct Fitness;
+
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+#[require(
+    Skill,
+    SkillKind(|| SkillKind::Charisma),
+    SkillGlyph(|| SkillGlyph("😎")),
+)]
+pub(crate) struct Charisma;
+
+#[derive(Component, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+#[require(
+    Skill,
+    SkillKind(|| SkillKind::Logic),
+    SkillGlyph(|| SkillGlyph("🧠")),
+)]
+pub(crate) struct Logic;
+
+/// Skill of an actor.
+///
+/// Stores experience in game minutes of practice.
+#[derive(Component, Debug, Default, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+#[require(ParentSync, Replicated)]
+pub struct Skill(u32);
+
+impl Skill {
+    pub const MAX_LEVEL: u32 = 10;
+
+    /// Minutes of practice required for the first level.
+    ///
+    /// Each next level requires this amount multiplied by the level.
+    const MINUTES_PER_LEVEL: u32 = 60;
+
+    pub fn level(&self) -> u32 {
+        (0..Self::MAX_LEVEL)
+            .take_while(|&level| self.0 >= Self::required_experience(level + 1))
+            .count() as u32
+    }
+
+    /// Returns progress to the next level in range `[0, 1]`.
+    pub fn progress(&self) -> f32 {
+        let level = self.level();
+        if level == Self::MAX_LEVEL {
+            return 1.0;
+        }
+
+        let current = Self::required_experience(level);
+        let next = Self::required_experience(level + 1);
+        (self.0 - current) as f32 / (next - current) as f32
+    }
+
+    /// Multiplier for need gains from tasks that use the skill.
+    pub(super) fn gain_factor(&self) -> f32 {
+        1.0 + 0.1 * self.level() as f32
+    }
+
+    fn add_experience(&mut self, minutes: u32) {
+        self.0 = (self.0 + minutes).min(Self::required_experience(Self::MAX_LEVEL));
+    }
+
+    fn required_experience(level: u32) -> u32 {
+        Self::MINUTES_PER_LEVEL * level * (level + 1) / 2
+    }
+}
+
+#[derive(Clone, Component, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum SkillKind {
+    Cooking,
+    Fitness,
+    Charisma,
+    Logic,
+}
+
+impl SkillKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SkillKind::Cooking => "Cooking",
+            SkillKind::Fitness => "Fitness",
+            SkillKind::Charisma => "Charisma",
+            SkillKind::Logic => "Logic",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SkillGlyph(pub &'static str);
+
+/// Skill that is trained while the task is active.
+#[derive(Component, Clone, Copy)]
+pub(super) struct SkillTraining(pub(super) SkillKind);
@@ -1,17 +1,27 @@
 mod friendly;
+mod group_activity;
 mod linked_task;
 mod move_here;
 
-use std::any;
+use std::{any, cmp::Reverse, time::Duration};
 
 use bevy::{ecs::entity::MapEntities, prelude::*, reflect::GetTypeRegistration};
 use bevy_replicon::prelude::*;
 use bitflags::bitflags;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use super::{animation_state::AnimationState, Actor, ActorTaskGroups, SelectedActor};
-use crate::game_world::{city::ActiveCity, family::FamilyMode, navigation::NavDestination};
+use super::{
+    animation_state::AnimationState, job::AtWork, travel::Traveling, Actor, ActorTaskGroups,
+    SelectedActor,
+};
+use crate::{
+    core::GameState,
+    game_world::{
+        city::ActiveCity, clock::WorldClock, family::FamilyMode, navigation::NavDestination,
+    },
+};
 use friendly::FriendlyPlugins;
+use group_activity::GroupActivityPlugin;
 use linked_task::LinkedTaskPlugin;
 use move_here::MoveHerePlugin;
 
@@ -19,18 +29,35 @@ pub(super) struct TaskPlugin;
 
 impl Plugin for TaskPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((FriendlyPlugins, LinkedTaskPlugin, MoveHerePlugin))
-            .replicate::<ActiveTask>()
-            .add_client_event::<TaskCancel>(ChannelKind::Unordered)
-            .add_observer(spawn_available.never_param_warn())
-            .add_observer(cleanup)
-            .add_systems(
-                PreUpdate,
-                cancel
-                    .after(ClientSet::Receive)
-                    .run_if(server_or_singleplayer),
-            )
-            .add_systems(PostUpdate, activate_queued.run_if(server_or_singleplayer));
+        app.add_plugins((
+            FriendlyPlugins,
+            GroupActivityPlugin,
+            LinkedTaskPlugin,
+            MoveHerePlugin,
+        ))
+        .register_type::<TaskOrder>()
+        .register_type::<TaskPriority>()
+        .replicate::<ActiveTask>()
+        .replicate::<TaskOrder>()
+        .replicate::<TaskPriority>()
+        .add_client_event::<TaskCancel>(ChannelKind::Unordered)
+        .add_mapped_client_event::<TaskMove>(ChannelKind::Ordered)
+        .add_mapped_client_event::<TaskPrioritize>(ChannelKind::Ordered)
+        .add_observer(spawn_available.never_param_warn())
+        .add_observer(cleanup)
+        .add_systems(
+            PreUpdate,
+            (cancel, move_queued, prioritize)
+                .after(ClientSet::Receive)
+                .run_if(server_or_singleplayer),
+        )
+        .add_systems(
+            PostUpdate,
+            (order_new, activate_queued)
+                .chain()
+                .run_if(server_or_singleplayer)
+                .run_if(in_state(GameState::InGame)),
+        );
     }
 }
 
@@ -72,17 +99,41 @@ fn spawn_available(
     });
 }
 
+/// Places newly added tasks at the end of the queue.
+fn order_new(mut tasks: Query<(Ref<Task>, &mut TaskOrder)>) {
+    let mut last_order = tasks
+        .iter()
+        .map(|(_, order)| order.0)
+        .max()
+        .unwrap_or_default();
+
+    // Tasks loaded from a save already have an order.
+    for (_, mut order) in tasks
+        .iter_mut()
+        .filter(|(task, order)| task.is_added() && order.0 == 0)
+    {
+        last_order += 1;
+        order.0 = last_order;
+    }
+}
+
 fn activate_queued(
     mut commands: Commands,
-    tasks: Query<(Entity, &Name, &TaskGroups), Without<ActiveTask>>,
-    mut actors: Query<(&Children, &mut ActorTaskGroups)>,
+    clock: Res<WorldClock>,
+    tasks: Query<(Entity, &Name, &TaskGroups, &TaskPriority, &TaskOrder), Without<ActiveTask>>,
+    mut actors: Query<(&Children, &mut ActorTaskGroups), (Without<AtWork>, Without<Traveling>)>,
 ) {
+    // Tasks of actors at work or on the way wait for their return.
     for (children, mut actor_groups) in &mut actors {
-        for (entity, name, &groups) in tasks.iter_many(children) {
+        let mut queued: Vec<_> = tasks.iter_many(children).collect();
+        queued.sort_by_key(|&(.., &priority, &order)| queue_key(priority, order));
+        for (entity, name, &groups, ..) in queued {
             if !groups.intersects(**actor_groups) {
                 debug!("activating '{name}' for `{entity}`");
                 actor_groups.insert(groups);
-                commands.entity(entity).insert(ActiveTask);
+                commands.entity(entity).insert(ActiveTask {
+                    activation_time: clock.elapsed_secs(),
+                });
             }
         }
     }
@@ -103,6 +154,78 @@ fn cancel(
     }
 }
 
+fn move_queued(
+    mut move_events: EventReader<FromClient<TaskMove>>,
+    tasks: Query<&Parent, (With<Task>, Without<ActiveTask>)>,
+    actors: Query<&Children>,
+    mut queued: Query<(Entity, &mut TaskPriority, &mut TaskOrder), Without<ActiveTask>>,
+) {
+    for FromClient { client_id, event } in move_events.read() {
+        let Ok(parent) = tasks.get(event.task_entity) else {
+            error!("task `{}` is not queued", event.task_entity);
+            continue;
+        };
+
+        info!(
+            "`{client_id:?}` moves task `{}` to {}",
+            event.task_entity, event.index
+        );
+        let children = actors.get(**parent).unwrap();
+        let mut entities: Vec<_> = queued
+            .iter_many(children)
+            .map(|(entity, &priority, &order)| (entity, priority, order))
+            .collect();
+        entities.sort_by_key(|&(_, priority, order)| queue_key(priority, order));
+
+        let mut orders: Vec<_> = entities.iter().map(|&(.., order)| order).collect();
+        orders.sort();
+
+        let from = entities
+            .iter()
+            .position(|&(entity, ..)| entity == event.task_entity)
+            .expect("moved task should be queued");
+        let (_, mut priority, _) = entities.remove(from);
+        let index = event.index.min(entities.len());
+
+        // Adopt the priority of the neighbour to keep the position after sorting.
+        if let Some(&(_, next_priority, _)) = entities.get(index) {
+            priority = next_priority;
+        } else if let Some(&(_, prev_priority, _)) = entities.last() {
+            priority = prev_priority;
+        }
+        entities.insert(index, (event.task_entity, priority, Default::default()));
+
+        for ((entity, priority, _), order) in entities.into_iter().zip(orders) {
+            let (_, mut task_priority, mut task_order) = queued.get_mut(entity).unwrap();
+            task_priority.set_if_neq(priority);
+            task_order.set_if_neq(order);
+        }
+    }
+}
+
+fn prioritize(
+    mut prioritize_events: EventReader<FromClient<TaskPrioritize>>,
+    mut tasks: Query<&mut TaskPriority, With<Task>>,
+) {
+    for FromClient { client_id, event } in prioritize_events.read() {
+        match tasks.get_mut(event.task_entity) {
+            Ok(mut priority) => {
+                info!(
+                    "`{client_id:?}` changes priority of task `{}` to {:?}",
+                    event.task_entity, event.priority
+                );
+                *priority = event.priority;
+            }
+            Err(e) => error!("unable to prioritize task `{}`: {e}", event.task_entity),
+        }
+    }
+}
+
+/// Returns a key for sorting queued tasks in activation order.
+pub fn queue_key(priority: TaskPriority, order: TaskOrder) -> (Reverse<TaskPriority>, TaskOrder) {
+    (Reverse(priority), order)
+}
+
 fn cleanup(
     trigger: Trigger<OnRemove, TaskGroups>,
     tasks: Query<(&Parent, &TaskGroups), With<ActiveTask>>,
@@ -139,11 +262,104 @@ pub struct AvailableTasks {
 }
 
 #[derive(Component, Default)]
-#[require(Name, TaskGroups, ParentSync, Replicated)]
+#[require(
+    Name,
+    TaskGroups,
+    TaskOrder,
+    TaskPriority,
+    ExpectedDuration,
+    ParentSync,
+    Replicated
+)]
 pub struct Task;
 
+/// Position of a task in the queue.
+///
+/// Queued tasks are activated in this order within the same [`TaskPriority`].
+/// Assigned on the server when the task is added.
+#[derive(
+    Component,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Reflect,
+    Serialize,
+)]
+#[reflect(Component)]
+pub struct TaskOrder(u64);
+
+/// Queued tasks with higher priority are activated first.
+#[derive(
+    Component,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Reflect,
+    Serialize,
+)]
+#[reflect(Component)]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl TaskPriority {
+    /// Returns the next priority, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Low => Self::Normal,
+            Self::Normal => Self::High,
+            Self::High => Self::Low,
+        }
+    }
+
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Low => "⬇",
+            Self::Normal => "",
+            Self::High => "⬆",
+        }
+    }
+}
+
+/// Approximate time to complete a task after its activation at normal speed.
+///
+/// Used only for displaying the progress.
+#[derive(Component, Clone, Copy, Deref)]
+pub struct ExpectedDuration(pub(super) Duration);
+
+impl Default for ExpectedDuration {
+    fn default() -> Self {
+        Self(Duration::from_secs(5))
+    }
+}
+
 #[derive(Component, Serialize, Deserialize)]
-pub struct ActiveTask;
+pub struct ActiveTask {
+    /// Value of [`WorldClock::elapsed_secs`] at the moment of activation.
+    activation_time: f64,
+}
+
+impl ActiveTask {
+    /// Returns time since the activation converted to normal speed.
+    pub fn elapsed(&self, clock: &WorldClock) -> Duration {
+        clock.normal_time_since(self.activation_time)
+    }
+}
 
 bitflags! {
     #[derive(Default, Component, Clone, Copy, Debug)]
@@ -165,6 +381,36 @@ pub struct TaskSelect;
 #[derive(Deserialize, Event, Serialize, Deref)]
 pub struct TaskCancel(pub Entity);
 
+/// An event of moving a queued task to the specified position in the queue.
+///
+/// Index 0 moves the task to the front. Emitted by players.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct TaskMove {
+    pub task_entity: Entity,
+    pub index: usize,
+}
+
+impl MapEntities for TaskMove {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.task_entity = entity_mapper.map_entity(self.task_entity);
+    }
+}
+
+/// An event of changing priority of a task.
+///
+/// Emitted by players.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct TaskPrioritize {
+    pub task_entity: Entity,
+    pub priority: TaskPriority,
+}
+
+impl MapEntities for TaskPrioritize {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.task_entity = entity_mapper.map_entity(self.task_entity);
+    }
+}
+
 #[derive(Event, Clone, Copy, Serialize, Deserialize)]
 pub struct TaskRequest<C> {
     pub entity: Entity,
@@ -1,13 +1,20 @@
+mod group_chat;
+mod tell_joke;
 mod tell_secret;
 
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
+use group_chat::GroupChatPlugin;
+use tell_joke::TellJokePlugin;
 use tell_secret::TellSecretPlugin;
 
 pub(super) struct FriendlyPlugins;
 
 impl PluginGroup for FriendlyPlugins {
     fn build(self) -> PluginGroupBuilder {
-        PluginGroupBuilder::start::<Self>().add(TellSecretPlugin)
+        PluginGroupBuilder::start::<Self>()
+            .add(GroupChatPlugin)
+            .add(TellJokePlugin)
+            .add(TellSecretPlugin)
     }
 }
@@ -0,0 +1,304 @@
+use std::time::Duration;
+
+use bevy::{animation::RepeatAnimation, ecs::entity::MapEntities, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset::collection::Collection,
+    core::GameState,
+    game_world::actor::{
+        aging::LifeStage,
+        animation_state::{AnimationState, Montage, MontageFinished},
+        mood::{Mood, MoodEvent},
+        needs::{Need, Social},
+        skills::{SkillKind, SkillTraining},
+        task::{
+            group_activity::{self, ActivityRole, ActivityStep, FormationSlot},
+            linked_task::{JoinRequests, JoinResponse, LinkedTask, QueuedJoin},
+            ActiveTask, AvailableTasks, ExpectedDuration, Task, TaskAppExt, TaskGroups,
+        },
+        traits::TraitEffects,
+        Actor, ActorAnimation, SelectedActor,
+    },
+};
+
+pub(super) struct GroupChatPlugin;
+
+impl Plugin for GroupChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_mapped_task::<GroupChat>()
+            .add_mapped_task::<JoinGroupChat>()
+            .add_observer(add_to_list)
+            .add_observer(invite)
+            .add_observer(advance)
+            .add_systems(
+                Update,
+                play_steps
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Maximum number of actors that can be invited.
+const MAX_GUESTS: usize = 4;
+
+/// Distance from the target in which other actors will be invited.
+const INVITE_RADIUS: f32 = 5.0;
+
+fn add_to_list(
+    trigger: Trigger<OnAdd, AvailableTasks>,
+    mut commands: Commands,
+    available_tasks: Single<&AvailableTasks>,
+    actors: Query<&LifeStage, With<Actor>>,
+    selected_stage: Single<&LifeStage, With<SelectedActor>>,
+) {
+    if !selected_stage.can_talk() {
+        return;
+    }
+
+    if let Ok(target_stage) = actors.get(available_tasks.interaction_entity) {
+        if !target_stage.can_talk() {
+            return;
+        }
+
+        debug!("listing task");
+        commands.entity(trigger.entity()).with_children(|parent| {
+            parent.spawn(GroupChat {
+                target_entity: available_tasks.interaction_entity,
+            });
+        });
+    }
+}
+
+/// Invites the target and nearby actors and assigns formation slots.
+fn invite(
+    trigger: Trigger<OnAdd, ActiveTask>,
+    mut commands: Commands,
+    client: Option<Res<RepliconClient>>,
+    join_requests: JoinRequests,
+    tasks: Query<(&Parent, &GroupChat)>,
+    actors: Query<(Entity, &Parent, &Transform, &LifeStage), With<Actor>>,
+) {
+    if !server_or_singleplayer(client) {
+        return;
+    }
+    let Ok((parent, group_chat)) = tasks.get(trigger.entity()) else {
+        return;
+    };
+    let Ok((_, &host_city, ..)) = actors.get(**parent) else {
+        commands.entity(trigger.entity()).despawn();
+        return;
+    };
+    let Ok((_, _, target_transform, _)) = actors.get(group_chat.target_entity) else {
+        commands.entity(trigger.entity()).despawn();
+        return;
+    };
+
+    // Translations are relative to the city, so actors from other cities may appear nearby.
+    let anchor = target_transform.translation;
+    let mut candidates: Vec<_> = actors
+        .iter()
+        .filter(|&(entity, ..)| entity != **parent)
+        .filter(|&(_, &city, ..)| city == host_city)
+        .filter(|(.., stage)| stage.can_talk())
+        .map(|(entity, _, transform, _)| (entity, transform.translation.distance(anchor)))
+        .filter(|&(_, distance)| distance <= INVITE_RADIUS)
+        .collect();
+    candidates.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    let guests: Vec<_> = candidates
+        .into_iter()
+        .map(|(entity, _)| (entity, join_requests.respond(entity, TaskGroups::LEGS)))
+        .filter(|&(_, response)| response != JoinResponse::Refuse)
+        .take(MAX_GUESTS)
+        .collect();
+    if guests.is_empty() {
+        info!("nobody accepted group chat from `{}`", **parent);
+        commands.entity(trigger.entity()).despawn();
+        return;
+    }
+
+    info!("`{}` starts group chat with {guests:?}", **parent);
+    let mut slots = group_activity::formation_slots(anchor, guests.len() + 1).into_iter();
+    let host_slot = slots.next().unwrap();
+
+    let guest_tasks = guests
+        .into_iter()
+        .zip(slots)
+        .map(|((guest_entity, response), position)| {
+            let mut entity = commands.spawn((
+                JoinGroupChat {
+                    host_entity: **parent,
+                },
+                ActivityRole::Guest,
+                FormationSlot { position, anchor },
+                LinkedTask(vec![trigger.entity()]),
+            ));
+            entity.set_parent(guest_entity);
+            if response == JoinResponse::Queue {
+                entity.insert(QueuedJoin::default());
+            }
+            entity.id()
+        })
+        .collect();
+
+    commands.entity(trigger.entity()).insert((
+        ActivityRole::Host,
+        FormationSlot {
+            position: host_slot,
+            anchor,
+        },
+        LinkedTask(guest_tasks),
+    ));
+}
+
+/// Plays montages for the current step.
+///
+/// Each participant speaks once in slot order while others listen.
+fn play_steps(
+    mut commands: Commands,
+    actor_animations: Res<Collection<ActorAnimation>>,
+    hosts: Query<(Entity, &Parent, &LinkedTask, Ref<ActivityStep>), With<GroupChat>>,
+    guest_tasks: Query<&Parent>,
+    mut actors: Query<(
+        &mut Transform,
+        &mut AnimationState,
+        &Children,
+        &TraitEffects,
+        &Mood,
+    )>,
+    mut needs: Query<&mut Need, With<Social>>,
+) {
+    for (task_entity, parent, linked_task, step) in &hosts {
+        if !step.is_changed() {
+            continue;
+        }
+
+        let participants: Vec<_> = [**parent]
+            .into_iter()
+            .chain(guest_tasks.iter_many(&**linked_task).map(|parent| **parent))
+            .collect();
+
+        let Some(&speaker_entity) = participants.get(**step) else {
+            info!("finishing group chat of `{}`", **parent);
+            for &actor_entity in &participants {
+                let Ok((_, _, children, effects, mood)) = actors.get(actor_entity) else {
+                    continue;
+                };
+                let gain = SOCIAL_GAIN * effects.social * mood.performance();
+                let mut iter = needs.iter_many_mut(children);
+                while let Some(mut need) = iter.fetch_next() {
+                    need.0 = (need.0 + gain).clamp(0.0, 100.0);
+                }
+                commands.trigger_targets(MoodEvent::Chat, actor_entity);
+            }
+            commands.entity(task_entity).despawn();
+            continue;
+        };
+
+        debug!("`{speaker_entity}` speaks in group chat");
+        let Ok((&speaker_transform, ..)) = actors.get(speaker_entity) else {
+            continue;
+        };
+        for &actor_entity in &participants {
+            let Ok((mut transform, mut animation_state, ..)) = actors.get_mut(actor_entity) else {
+                continue;
+            };
+            if actor_entity == speaker_entity {
+                let montage = Montage::new(actor_animations.handle(ActorAnimation::TellSecret));
+                animation_state.play_montage(montage);
+            } else {
+                let target = Vec3::new(
+                    speaker_transform.translation.x,
+                    transform.translation.y,
+                    speaker_transform.translation.z,
+                );
+                transform.look_at(target, Vec3::Y);
+                let montage = Montage::new(actor_animations.handle(ActorAnimation::ThoughtfulNod))
+                    .with_repeat(RepeatAnimation::Forever);
+                animation_state.play_montage(montage);
+            }
+        }
+    }
+}
+
+/// Social need gain for all participants.
+const SOCIAL_GAIN: f32 = 30.0;
+
+/// Passes the word to the next participant when the speaker finishes.
+fn advance(
+    trigger: Trigger<MontageFinished>,
+    actors: Query<&Children>,
+    guest_tasks: Query<&JoinGroupChat, With<ActiveTask>>,
+    mut hosts: Query<(Entity, &Parent, &LinkedTask, &mut ActivityStep), With<GroupChat>>,
+    parents: Query<&Parent>,
+) {
+    let Ok(children) = actors.get(trigger.entity()) else {
+        return;
+    };
+
+    let host_entity = guest_tasks
+        .iter_many(children)
+        .next()
+        .map(|join_group_chat| join_group_chat.host_entity)
+        .unwrap_or(trigger.entity());
+    let Some((task_entity, _, linked_task, mut step)) = hosts
+        .iter_mut()
+        .find(|(_, parent, ..)| ***parent == host_entity)
+    else {
+        return;
+    };
+
+    let speaker_entity = if **step == 0 {
+        Some(host_entity)
+    } else {
+        linked_task
+            .get(**step - 1)
+            .and_then(|&entity| parents.get(entity).ok())
+            .map(|parent| **parent)
+    };
+    if speaker_entity == Some(trigger.entity()) {
+        debug!("advancing group chat `{task_entity}`");
+        **step += 1;
+    }
+}
+
+#[derive(Component, Reflect, Deserialize, Serialize, Clone, Copy)]
+#[reflect(Component)]
+#[require(
+    Name(|| Name::new("Group chat")),
+    Task,
+    LinkedTask,
+    TaskGroups(|| TaskGroups::LEGS),
+    ExpectedDuration(|| ExpectedDuration(Duration::from_secs(30))),
+    SkillTraining(|| SkillTraining(SkillKind::Charisma)),
+)]
+struct GroupChat {
+    target_entity: Entity,
+}
+
+impl MapEntities for GroupChat {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.target_entity = entity_mapper.map_entity(self.target_entity);
+    }
+}
+
+#[derive(Component, Reflect, Deserialize, Serialize, Clone, Copy)]
+#[reflect(Component)]
+#[require(
+    Name(|| Name::new("Join group chat")),
+    Task,
+    TaskGroups(|| TaskGroups::LEGS),
+    ExpectedDuration(|| ExpectedDuration(Duration::from_secs(30))),
+)]
+struct JoinGroupChat {
+    host_entity: Entity,
+}
+
+impl MapEntities for JoinGroupChat {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.host_entity = entity_mapper.map_entity(self.host_entity);
+    }
+}
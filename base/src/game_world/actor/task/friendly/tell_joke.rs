@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use bevy::{animation::RepeatAnimation, ecs::entity::MapEntities, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset::collection::Collection,
+    game_world::{
+        actor::{
+            aging::LifeStage,
+            animation_state::{AnimationState, Montage, MontageFinished},
+            mood::{Mood, MoodEvent},
+            needs::{Fun, Need},
+            skills::{Skill, SkillKind, SkillTraining},
+            task::{
+                linked_task::{JoinRequests, JoinResponse, LinkedTask, QueuedJoin},
+                ActiveTask, AvailableTasks, ExpectedDuration, Task, TaskAppExt, TaskGroups,
+            },
+            Actor, ActorAnimation, Movement, SelectedActor,
+        },
+        navigation::{following::Following, Navigation},
+    },
+};
+
+pub(super) struct TellJokePlugin;
+
+impl Plugin for TellJokePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_mapped_task::<TellJoke>()
+            .add_mapped_task::<ListenJoke>()
+            .add_observer(add_to_list)
+            .add_observer(activate)
+            .add_observer(start_telling)
+            .add_observer(start_listening)
+            .add_observer(finish);
+    }
+}
+
+/// Charisma level required to tell jokes.
+const REQUIRED_LEVEL: u32 = 3;
+
+fn add_to_list(
+    trigger: Trigger<OnAdd, AvailableTasks>,
+    mut commands: Commands,
+    available_tasks: Single<&AvailableTasks>,
+    actors: Query<&LifeStage, With<Actor>>,
+    selected_actor: Single<(&LifeStage, &Children), With<SelectedActor>>,
+    skills: Query<(&SkillKind, &Skill)>,
+) {
+    let (selected_stage, children) = *selected_actor;
+    if !selected_stage.can_talk() {
+        return;
+    }
+
+    let charisma_level = skills
+        .iter_many(children)
+        .find(|(&kind, _)| kind == SkillKind::Charisma)
+        .map(|(_, skill)| skill.level())
+        .unwrap_or_default();
+    if charisma_level < REQUIRED_LEVEL {
+        return;
+    }
+
+    if let Ok(target_stage) = actors.get(available_tasks.interaction_entity) {
+        if !target_stage.can_talk() {
+            return;
+        }
+
+        debug!("listing task");
+        commands.entity(trigger.entity()).with_children(|parent| {
+            parent.spawn(TellJoke {
+                target_entity: available_tasks.interaction_entity,
+            });
+        });
+    }
+}
+
+fn activate(
+    trigger: Trigger<OnAdd, ActiveTask>,
+    mut commands: Commands,
+    mut actors: Query<&mut Navigation>,
+    tasks: Query<(&Parent, &TellJoke)>,
+) {
+    let Ok((parent, tell_joke)) = tasks.get(trigger.entity()) else {
+        return;
+    };
+
+    let mut navigation = actors
+        .get_mut(**parent)
+        .expect("actors should have navigation component");
+    *navigation = Navigation::new(Movement::Walk.speed()).with_offset(0.5);
+
+    commands
+        .entity(**parent)
+        .insert(Following(tell_joke.target_entity));
+}
+
+fn start_telling(
+    trigger: Trigger<OnRemove, Following>,
+    mut commands: Commands,
+    join_requests: JoinRequests,
+    actors: Query<&Children>,
+    tasks: Query<(Entity, &TellJoke), With<ActiveTask>>,
+) {
+    let children = actors.get(trigger.entity()).unwrap();
+    let Some((tell_entity, tell_joke)) = tasks.iter_many(children).next() else {
+        return;
+    };
+
+    let response = join_requests.respond(tell_joke.target_entity, TaskGroups::LEGS);
+    debug!(
+        "`{}` responds with `{response:?}` to `{tell_entity}`",
+        tell_joke.target_entity
+    );
+    if response == JoinResponse::Refuse {
+        commands.entity(tell_entity).despawn();
+        return;
+    }
+
+    // The montage will be started when the listener activates its task.
+    let listen_entity = commands
+        .spawn((
+            LinkedTask(vec![tell_entity]),
+            ListenJoke {
+                teller_entity: trigger.entity(),
+            },
+        ))
+        .set_parent(tell_joke.target_entity)
+        .id();
+    if response == JoinResponse::Queue {
+        commands.entity(listen_entity).insert(QueuedJoin::default());
+    }
+    commands
+        .entity(tell_entity)
+        .insert(LinkedTask(vec![listen_entity]));
+}
+
+fn start_listening(
+    trigger: Trigger<OnAdd, ActiveTask>,
+    actor_animations: Res<Collection<ActorAnimation>>,
+    tasks: Query<(&Parent, &ListenJoke)>,
+    mut actors: Query<(&mut Transform, &mut AnimationState)>,
+) {
+    let Ok((parent, listen_joke)) = tasks.get(trigger.entity()) else {
+        return;
+    };
+
+    let [teller, listener] = actors
+        .get_many_mut([listen_joke.teller_entity, **parent])
+        .expect("teller and listener should have transform and animation");
+    let (teller_transform, mut teller_animation) = teller;
+    let (mut listener_transform, mut listener_animation) = listener;
+
+    listener_transform.look_at(teller_transform.translation, Vec3::Y);
+    let montage = Montage::new(actor_animations.handle(ActorAnimation::ThoughtfulNod))
+        .with_repeat(RepeatAnimation::Forever);
+    listener_animation.play_montage(montage);
+
+    let montage = Montage::new(actor_animations.handle(ActorAnimation::TellSecret));
+    teller_animation.play_montage(montage);
+}
+
+/// Fun need gain for both participants.
+const FUN_GAIN: f32 = 15.0;
+
+fn finish(
+    trigger: Trigger<MontageFinished>,
+    mut commands: Commands,
+    actors: Query<(&Children, &Mood)>,
+    tasks: Query<(Entity, &TellJoke), With<ActiveTask>>,
+    skills: Query<(&SkillKind, &Skill)>,
+    mut needs: Query<&mut Need, With<Fun>>,
+) {
+    let Ok((children, _)) = actors.get(trigger.entity()) else {
+        return;
+    };
+
+    if let Some((task_entity, tell_joke)) = tasks.iter_many(children).next() {
+        let charisma_factor = skills
+            .iter_many(children)
+            .find(|(&kind, _)| kind == SkillKind::Charisma)
+            .map(|(_, skill)| skill.gain_factor())
+            .unwrap_or(1.0);
+
+        for actor_entity in [trigger.entity(), tell_joke.target_entity] {
+            let Ok((children, mood)) = actors.get(actor_entity) else {
+                continue;
+            };
+            // Charisma of the teller, but the mood of each participant.
+            let gain = FUN_GAIN * charisma_factor * mood.performance();
+            let mut iter = needs.iter_many_mut(children);
+            while let Some(mut need) = iter.fetch_next() {
+                need.0 = (need.0 + gain).clamp(0.0, 100.0);
+            }
+            commands.trigger_targets(MoodEvent::Joke, actor_entity);
+        }
+
+        commands.entity(task_entity).despawn();
+    }
+}
+
+#[derive(Component, Reflect, Deserialize, Serialize, Clone, Copy)]
+#[reflect(Component)]
+#[require(
+    Name(|| Name::new("Tell joke")),
+    Task,
+    LinkedTask,
+    TaskGroups(|| TaskGroups::LEGS),
+    ExpectedDuration(|| ExpectedDuration(Duration::from_secs(8))),
+    SkillTraining(|| SkillTraining(SkillKind::Charisma)),
+)]
+struct TellJoke {
+    target_entity: Entity,
+}
+
+impl MapEntities for TellJoke {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.target_entity = entity_mapper.map_entity(self.target_entity);
+    }
+}
+
+#[derive(Component, Reflect, Deserialize, Serialize, Clone, Copy)]
+#[reflect(Component)]
+#[require(
+    Name(|| Name::new("Listen joke")),
+    Task,
+    TaskGroups(|| TaskGroups::LEGS),
+    ExpectedDuration(|| ExpectedDuration(Duration::from_secs(8))),
+)]
+struct ListenJoke {
+    teller_entity: Entity,
+}
+
+impl MapEntities for ListenJoke {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.teller_entity = entity_mapper.map_entity(self.teller_entity);
+    }
+}
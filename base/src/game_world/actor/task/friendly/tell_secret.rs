@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bevy::{animation::RepeatAnimation, ecs::entity::MapEntities, prelude::*};
 use serde::{Deserialize, Serialize};
 
@@ -5,11 +7,17 @@ use crate::{
     asset::collection::Collection,
     game_world::{
         actor::{
+            aging::LifeStage,
             animation_state::{AnimationState, Montage, MontageFinished},
+            mood::{Mood, MoodEvent},
+            needs::{Need, Social},
+            skills::{Skill, SkillKind, SkillTraining},
             task::{
-                linked_task::LinkedTask, ActiveTask, AvailableTasks, Task, TaskAppExt, TaskGroups,
+                linked_task::{JoinRequests, JoinResponse, LinkedTask, QueuedJoin},
+                ActiveTask, AvailableTasks, ExpectedDuration, Task, TaskAppExt, TaskGroups,
             },
-            Actor, ActorAnimation, Movement,
+            traits::TraitEffects,
+            Actor, ActorAnimation, Movement, SelectedActor,
         },
         navigation::{following::Following, Navigation},
     },
@@ -33,9 +41,18 @@ fn add_to_list(
     trigger: Trigger<OnAdd, AvailableTasks>,
     mut commands: Commands,
     available_tasks: Single<&AvailableTasks>,
-    actors: Query<(), With<Actor>>,
+    actors: Query<&LifeStage, With<Actor>>,
+    selected_stage: Single<&LifeStage, With<SelectedActor>>,
 ) {
-    if actors.get(available_tasks.interaction_entity).is_ok() {
+    if !selected_stage.can_talk() {
+        return;
+    }
+
+    if let Ok(target_stage) = actors.get(available_tasks.interaction_entity) {
+        if !target_stage.can_talk() {
+            return;
+        }
+
         debug!("listing task");
         commands.entity(trigger.entity()).with_children(|parent| {
             parent.spawn(TellSecret {
@@ -68,34 +85,41 @@ fn activate(
 fn start_telling(
     trigger: Trigger<OnRemove, Following>,
     mut commands: Commands,
-    actor_animations: Res<Collection<ActorAnimation>>,
-    mut actors: Query<(&Children, &mut AnimationState)>,
-    mut tasks: Query<(Entity, &TellSecret, &mut LinkedTask), With<ActiveTask>>,
+    join_requests: JoinRequests,
+    actors: Query<&Children>,
+    tasks: Query<(Entity, &TellSecret), With<ActiveTask>>,
 ) {
-    let (children, mut animation_state) = actors.get_mut(trigger.entity()).unwrap();
-
-    if let Some((tell_entity, tell_secret, mut linked_task)) =
-        tasks.iter_many_mut(children).fetch_next()
-    {
-        let montage = Montage::new(actor_animations.handle(ActorAnimation::TellSecret));
-        animation_state.play_montage(montage);
-
-        // TODO: Handle cancellation of currently active tasks.
-        commands
-            .entity(tell_secret.target_entity)
-            .with_children(|parent| {
-                let listen_entity = parent
-                    .spawn((
-                        LinkedTask(Some(tell_entity)),
-                        ListenSecret {
-                            teller_entity: trigger.entity(),
-                        },
-                    ))
-                    .id();
-
-                **linked_task = Some(listen_entity)
-            });
+    let children = actors.get(trigger.entity()).unwrap();
+    let Some((tell_entity, tell_secret)) = tasks.iter_many(children).next() else {
+        return;
+    };
+
+    let response = join_requests.respond(tell_secret.target_entity, TaskGroups::LEGS);
+    debug!(
+        "`{}` responds with `{response:?}` to `{tell_entity}`",
+        tell_secret.target_entity
+    );
+    if response == JoinResponse::Refuse {
+        commands.entity(tell_entity).despawn();
+        return;
     }
+
+    // The montage will be started when the listener activates its task.
+    let listen_entity = commands
+        .spawn((
+            LinkedTask(vec![tell_entity]),
+            ListenSecret {
+                teller_entity: trigger.entity(),
+            },
+        ))
+        .set_parent(tell_secret.target_entity)
+        .id();
+    if response == JoinResponse::Queue {
+        commands.entity(listen_entity).insert(QueuedJoin::default());
+    }
+    commands
+        .entity(tell_entity)
+        .insert(LinkedTask(vec![listen_entity]));
 }
 
 fn start_listening(
@@ -108,31 +132,56 @@ fn start_listening(
         return;
     };
 
-    let (&teller_transform, _) = actors
-        .get(listen_secret.teller_entity)
-        .expect("teller should have transform");
-
-    let (mut listener_transform, mut animation_state) = actors
-        .get_mut(**parent)
-        .expect("listener should have transform and animation");
+    let [teller, listener] = actors
+        .get_many_mut([listen_secret.teller_entity, **parent])
+        .expect("teller and listener should have transform and animation");
+    let (teller_transform, mut teller_animation) = teller;
+    let (mut listener_transform, mut listener_animation) = listener;
 
     listener_transform.look_at(teller_transform.translation, Vec3::Y);
     let montage = Montage::new(actor_animations.handle(ActorAnimation::ThoughtfulNod))
         .with_repeat(RepeatAnimation::Forever);
-    animation_state.play_montage(montage);
+    listener_animation.play_montage(montage);
+
+    let montage = Montage::new(actor_animations.handle(ActorAnimation::TellSecret));
+    teller_animation.play_montage(montage);
 }
 
+/// Social need gain for both participants.
+const SOCIAL_GAIN: f32 = 20.0;
+
 fn finish(
     trigger: Trigger<MontageFinished>,
     mut commands: Commands,
-    children: Query<&Children>,
-    tasks: Query<Entity, (With<TellSecret>, With<ActiveTask>)>,
+    actors: Query<(&Children, &TraitEffects, &Mood)>,
+    tasks: Query<(Entity, &TellSecret), With<ActiveTask>>,
+    skills: Query<(&SkillKind, &Skill)>,
+    mut needs: Query<&mut Need, With<Social>>,
 ) {
-    let Ok(children) = children.get(trigger.entity()) else {
+    let Ok((children, ..)) = actors.get(trigger.entity()) else {
         return;
     };
 
-    if let Some(task_entity) = tasks.iter_many(children).next() {
+    if let Some((task_entity, tell_secret)) = tasks.iter_many(children).next() {
+        let charisma_factor = skills
+            .iter_many(children)
+            .find(|(&kind, _)| kind == SkillKind::Charisma)
+            .map(|(_, skill)| skill.gain_factor())
+            .unwrap_or(1.0);
+
+        for actor_entity in [trigger.entity(), tell_secret.target_entity] {
+            let Ok((children, effects, mood)) = actors.get(actor_entity) else {
+                continue;
+            };
+            // Charisma of the teller, but the mood of each participant.
+            let gain = SOCIAL_GAIN * effects.social * charisma_factor * mood.performance();
+            let mut iter = needs.iter_many_mut(children);
+            while let Some(mut need) = iter.fetch_next() {
+                need.0 = (need.0 + gain).clamp(0.0, 100.0);
+            }
+            commands.trigger_targets(MoodEvent::Chat, actor_entity);
+        }
+
         commands.entity(task_entity).despawn();
     }
 }
@@ -144,6 +193,8 @@ fn finish(
     Task,
     LinkedTask,
     TaskGroups(|| TaskGroups::LEGS),
+    ExpectedDuration(|| ExpectedDuration(Duration::from_secs(10))),
+    SkillTraining(|| SkillTraining(SkillKind::Charisma)),
 )]
 struct TellSecret {
     target_entity: Entity,
@@ -161,6 +212,7 @@ impl MapEntities for TellSecret {
     Name(|| Name::new("Listen secret")),
     Task,
     TaskGroups(|| TaskGroups::LEGS),
+    ExpectedDuration(|| ExpectedDuration(Duration::from_secs(10))),
 )]
 struct ListenSecret {
     teller_entity: Entity,
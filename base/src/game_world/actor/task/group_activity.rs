@@ -0,0 +1,139 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+
+use super::{linked_task::LinkedTask, ActiveTask};
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::Movement,
+        navigation::{NavDestination, Navigation},
+    },
+};
+
+pub(super) struct GroupActivityPlugin;
+
+impl Plugin for GroupActivityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (go_to_slots, take_slots, start_sequences)
+                .chain()
+                .run_if(server_or_singleplayer)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+fn go_to_slots(
+    tasks: Query<
+        (&Parent, &FormationSlot),
+        (
+            With<ActiveTask>,
+            Or<(Added<ActiveTask>, Added<FormationSlot>)>,
+        ),
+    >,
+    mut actors: Query<(&mut Navigation, &mut NavDestination)>,
+) {
+    for (parent, slot) in &tasks {
+        debug!("moving `{}` to formation slot", **parent);
+        let (mut navigation, mut dest) = actors
+            .get_mut(**parent)
+            .expect("actors should have navigation component");
+        // Other participants wait in formation, so keep trying to reach the slot.
+        *navigation = Navigation::new(Movement::Walk.speed()).with_waiting();
+        **dest = Some(slot.position);
+    }
+}
+
+fn take_slots(
+    mut commands: Commands,
+    tasks: Query<(Entity, &Parent, &FormationSlot), (With<ActiveTask>, Without<InFormation>)>,
+    mut actors: Query<(&NavDestination, &mut Transform)>,
+) {
+    for (task_entity, parent, slot) in &tasks {
+        let (dest, mut transform) = actors
+            .get_mut(**parent)
+            .expect("actors should have navigation component");
+        if dest.is_none() {
+            debug!("`{}` took formation slot", **parent);
+            let anchor = Vec3::new(slot.anchor.x, transform.translation.y, slot.anchor.z);
+            transform.look_at(anchor, Vec3::Y);
+            commands.entity(task_entity).insert(InFormation);
+        }
+    }
+}
+
+/// Starts the montage sequence when all participants are in formation.
+fn start_sequences(
+    mut commands: Commands,
+    hosts: Query<
+        (Entity, &ActivityRole, &LinkedTask),
+        (With<ActiveTask>, With<InFormation>, Without<ActivityStep>),
+    >,
+    in_formation: Query<(), (With<ActiveTask>, With<InFormation>)>,
+) {
+    for (task_entity, _, linked_task) in hosts
+        .iter()
+        .filter(|(_, &role, _)| role == ActivityRole::Host)
+    {
+        if !linked_task.is_empty()
+            && linked_task
+                .iter()
+                .all(|&entity| in_formation.get(entity).is_ok())
+        {
+            debug!("starting sequence for `{task_entity}`");
+            commands.entity(task_entity).insert(ActivityStep::default());
+        }
+    }
+}
+
+/// Returns positions around the anchor for the specified number of participants.
+pub(super) fn formation_slots(anchor: Vec3, count: usize) -> Vec<Vec3> {
+    let radius = (SLOT_SPACING * count as f32 / TAU).max(MIN_RADIUS);
+    (0..count)
+        .map(|index| {
+            let angle = TAU * index as f32 / count as f32;
+            anchor + Vec3::new(angle.cos(), 0.0, angle.sin()) * radius
+        })
+        .collect()
+}
+
+/// Distance between neighbouring participants.
+const SLOT_SPACING: f32 = 1.2;
+
+/// Minimal distance from participants to the anchor.
+const MIN_RADIUS: f32 = 0.6;
+
+/// Position in a group activity that participant should take.
+///
+/// Tasks with this component navigate actors to the position on activation.
+#[derive(Component, Clone, Copy)]
+pub(super) struct FormationSlot {
+    pub(super) position: Vec3,
+
+    /// Center of the activity, participants face it after arriving.
+    pub(super) anchor: Vec3,
+}
+
+/// Indicates that the actor reached its [`FormationSlot`].
+#[derive(Component)]
+pub(super) struct InFormation;
+
+/// Role of a participant in a group activity.
+///
+/// Host task should be linked to tasks of all guests via [`LinkedTask`].
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub(super) enum ActivityRole {
+    Host,
+    Guest,
+}
+
+/// Current step of the synced montage sequence.
+///
+/// Inserted into the host task when all participants are in formation.
+/// Activities react to its changes to play montages for each participant
+/// and advance it when the step is finished.
+#[derive(Component, Default, Deref, DerefMut)]
+pub(super) struct ActivityStep(usize);
@@ -1,26 +1,290 @@
-use bevy::prelude::*;
+use std::{mem, time::Duration};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_replicon::prelude::*;
+
+use super::{ActiveTask, Task, TaskGroups};
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{job::AtWork, travel::Traveling, ActorTaskGroups},
+        clock::WorldClock,
+    },
+};
 
 pub(super) struct LinkedTaskPlugin;
 
 impl Plugin for LinkedTaskPlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(cleanup);
+        app.add_observer(cleanup).add_systems(
+            Update,
+            expire_queued
+                .run_if(server_or_singleplayer)
+                .run_if(in_state(GameState::InGame)),
+        );
     }
 }
 
+/// Despawns tasks of all other participants.
+///
+/// Guests are linked only to the host task, so links of each despawned task
+/// are followed to reach the whole group.
 fn cleanup(
     trigger: Trigger<OnRemove, LinkedTask>,
     mut commands: Commands,
-    tasks: Query<&LinkedTask>,
+    mut tasks: Query<&mut LinkedTask>,
 ) {
-    let linked_task = tasks.get(trigger.entity()).unwrap();
-    if let Some(mut entity) = linked_task.and_then(|entity| commands.get_entity(entity)) {
-        entity.despawn();
+    let mut linked_task = tasks.get_mut(trigger.entity()).unwrap();
+    let mut pending = mem::take(&mut **linked_task);
+    let mut despawned = vec![trigger.entity()];
+    while let Some(entity) = pending.pop() {
+        if despawned.contains(&entity) {
+            continue;
+        }
+
+        if let Ok(mut linked_task) = tasks.get_mut(entity) {
+            debug!(
+                "cancelling task `{entity}` linked to `{}`",
+                trigger.entity()
+            );
+            // Clear links to avoid despawning other tasks twice.
+            pending.append(&mut linked_task.0);
+            commands.entity(entity).despawn();
+            despawned.push(entity);
+        }
+    }
+}
+
+/// Drops queued linked tasks that weren't activated in time.
+///
+/// The dropped task is unlinked from other participants,
+/// and participants left without links are cancelled too.
+fn expire_queued(
+    mut commands: Commands,
+    time: Res<Time>,
+    clock: Res<WorldClock>,
+    mut queued: Query<(Entity, &mut QueuedJoin, Has<ActiveTask>)>,
+    mut tasks: Query<&mut LinkedTask>,
+) {
+    // Wait in game time to respect pause and speed.
+    let delta = time.delta().mul_f32(clock.speed());
+    for (entity, mut queued_join, active) in &mut queued {
+        if active {
+            commands.entity(entity).remove::<QueuedJoin>();
+            continue;
+        }
+
+        queued_join.tick(delta);
+        if !queued_join.finished() {
+            continue;
+        }
+
+        debug!("dropping queued task `{entity}` after timeout");
+        let links = tasks
+            .get_mut(entity)
+            .map(|mut linked_task| mem::take(&mut **linked_task))
+            .unwrap_or_default();
+        for linked_entity in links {
+            if let Ok(mut linked_task) = tasks.get_mut(linked_entity) {
+                linked_task.retain(|&task_entity| task_entity != entity);
+                if linked_task.is_empty() {
+                    debug!("cancelling task `{linked_entity}` without participants");
+                    commands.entity(linked_entity).despawn();
+                }
+            }
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Decides how actors respond to requests to join a task.
+///
+/// Used by tasks that spawn a [`LinkedTask`] on another actor.
+#[derive(SystemParam)]
+pub(super) struct JoinRequests<'w, 's> {
+    actors: Query<
+        'w,
+        's,
+        (&'static Children, &'static ActorTaskGroups),
+        (Without<AtWork>, Without<Traveling>),
+    >,
+    tasks: Query<'w, 's, (&'static Name, Option<&'static LinkedTask>), With<Task>>,
+}
+
+impl JoinRequests<'_, '_> {
+    /// Returns the response of the target actor to a task that needs the specified groups.
+    ///
+    /// Actors refuse if they are already involved in another linked task
+    /// and queue the request if the groups are occupied by their own tasks.
+    /// Linked tasks for queued requests should have [`QueuedJoin`].
+    pub(super) fn respond(&self, target_entity: Entity, groups: TaskGroups) -> JoinResponse {
+        let Ok((children, actor_groups)) = self.actors.get(target_entity) else {
+            debug!("`{target_entity}` is unavailable");
+            return JoinResponse::Refuse;
+        };
+
+        if let Some((name, _)) = self
+            .tasks
+            .iter_many(children)
+            .find(|(_, linked_task)| linked_task.is_some_and(|task| !task.is_empty()))
+        {
+            debug!("`{target_entity}` is busy with '{name}'");
+            return JoinResponse::Refuse;
+        }
+
+        if actor_groups.intersects(groups) {
+            JoinResponse::Queue
+        } else {
+            JoinResponse::Accept
+        }
     }
 }
 
-/// Stores entity of another tasks and syncs [`TaskState`] between them.
+/// Response of an actor to a request to join a task.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum JoinResponse {
+    /// The linked task will be activated immediately.
+    Accept,
+    /// The linked task will be activated after the conflicting tasks finish.
+    ///
+    /// If it doesn't happen within [`QueuedJoin`] timeout, the linked task will be dropped.
+    Queue,
+    /// The linked task shouldn't be spawned.
+    Refuse,
+}
+
+/// Time limit for a queued linked task to become active.
 ///
-/// If this task will be despawned, the linked task will be despawned as well.
-#[derive(Component, Clone, Copy, Deref, DerefMut, Default)]
-pub(super) struct LinkedTask(pub(super) Option<Entity>);
+/// Ticks only while the world clock runs and scales with its speed.
+#[derive(Component, Deref, DerefMut)]
+pub(super) struct QueuedJoin(Timer);
+
+impl Default for QueuedJoin {
+    fn default() -> Self {
+        Self(Timer::new(Duration::from_secs(30), TimerMode::Once))
+    }
+}
+
+/// Stores entities of tasks of other participants.
+///
+/// If this task will be despawned, the linked tasks will be despawned as well.
+#[derive(Component, Clone, Deref, DerefMut, Default)]
+pub(super) struct LinkedTask(pub(super) Vec<Entity>);
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn join_responses() {
+        let mut world = World::new();
+        let free_actor = spawn_actor(&mut world, TaskGroups::empty());
+        let occupied_actor = spawn_actor(&mut world, TaskGroups::LEGS);
+        let linked_actor = spawn_actor(&mut world, TaskGroups::empty());
+        world.entity_mut(linked_actor).with_children(|parent| {
+            parent.spawn((Task, LinkedTask(vec![Entity::PLACEHOLDER])));
+        });
+        let working_actor = spawn_actor(&mut world, TaskGroups::empty());
+        world.entity_mut(working_actor).insert(AtWork::default());
+
+        assert_eq!(respond(&mut world, free_actor), JoinResponse::Accept);
+        assert_eq!(respond(&mut world, occupied_actor), JoinResponse::Queue);
+        assert_eq!(
+            respond(&mut world, linked_actor),
+            JoinResponse::Refuse,
+            "actors in another linked task should refuse"
+        );
+        assert_eq!(
+            respond(&mut world, working_actor),
+            JoinResponse::Refuse,
+            "unavailable actors should refuse"
+        );
+    }
+
+    #[test]
+    fn queued_expiration() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<WorldClock>();
+
+        let host_task = world.spawn_empty().id();
+        let guest_tasks: Vec<_> = (0..2)
+            .map(|_| world.spawn(LinkedTask(vec![host_task])).id())
+            .collect();
+        world
+            .entity_mut(host_task)
+            .insert(LinkedTask(guest_tasks.clone()));
+
+        world.entity_mut(guest_tasks[0]).insert(expired_join());
+        world.run_system_once(expire_queued).unwrap();
+
+        assert!(world.get_entity(guest_tasks[0]).is_err());
+        let linked_task = world.get::<LinkedTask>(host_task).unwrap();
+        assert_eq!(
+            **linked_task,
+            [guest_tasks[1]],
+            "host should keep other guests"
+        );
+
+        world.entity_mut(guest_tasks[1]).insert(expired_join());
+        world.run_system_once(expire_queued).unwrap();
+
+        assert!(world.get_entity(guest_tasks[1]).is_err());
+        assert!(
+            world.get_entity(host_task).is_err(),
+            "host without guests should be cancelled"
+        );
+    }
+
+    fn spawn_actor(world: &mut World, groups: TaskGroups) -> Entity {
+        world
+            .spawn(ActorTaskGroups(groups))
+            .with_children(|parent| {
+                parent.spawn_empty();
+            })
+            .id()
+    }
+
+    fn respond(world: &mut World, actor_entity: Entity) -> JoinResponse {
+        world
+            .run_system_once(move |join_requests: JoinRequests| {
+                join_requests.respond(actor_entity, TaskGroups::LEGS)
+            })
+            .unwrap()
+    }
+
+    fn expired_join() -> QueuedJoin {
+        let mut queued_join = QueuedJoin::default();
+        let duration = queued_join.duration();
+        queued_join.tick(duration);
+        queued_join
+    }
+
+    #[test]
+    fn guest_cancellation() {
+        let mut app = App::new();
+        app.add_plugins(LinkedTaskPlugin);
+
+        let world = app.world_mut();
+        let host_task = world.spawn_empty().id();
+        let guest_tasks: Vec<_> = (0..3)
+            .map(|_| world.spawn(LinkedTask(vec![host_task])).id())
+            .collect();
+        world
+            .entity_mut(host_task)
+            .insert(LinkedTask(guest_tasks.clone()));
+
+        world.despawn(guest_tasks[1]);
+        world.flush();
+
+        assert!(world.get_entity(host_task).is_err());
+        for entity in guest_tasks {
+            assert!(
+                world.get_entity(entity).is_err(),
+                "task `{entity}` of other guests should also be cancelled"
+            );
+        }
+    }
+}
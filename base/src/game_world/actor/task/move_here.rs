@@ -5,7 +5,11 @@ use super::{ActiveTask, AvailableTasks, Task, TaskAppExt, TaskGroups};
 use crate::{
     core::GameState,
     game_world::{
-        actor::Movement,
+        actor::{
+            aging::LifeStage,
+            skills::{SkillKind, SkillTraining},
+            Movement, SelectedActor,
+        },
         city::Ground,
         navigation::{NavDestination, Navigation},
     },
@@ -27,10 +31,14 @@ fn add_to_list(
     mut commands: Commands,
     available_tasks: Single<&AvailableTasks>,
     grounds: Query<(), With<Ground>>,
+    selected_stage: Single<&LifeStage, With<SelectedActor>>,
 ) {
     if grounds.get(available_tasks.interaction_entity).is_err() {
         return;
     }
+    if !selected_stage.can_walk() {
+        return;
+    }
 
     debug!("listing tasks");
     commands.entity(trigger.entity()).with_children(|parent| {
@@ -53,6 +61,7 @@ fn add_to_list(
 
 fn activate(
     trigger: Trigger<OnAdd, ActiveTask>,
+    mut commands: Commands,
     mut actors: Query<(&mut Navigation, &mut NavDestination)>,
     tasks: Query<(&Parent, &MoveHere)>,
 ) {
@@ -66,6 +75,12 @@ fn activate(
         .expect("actors should have navigation component");
     *navigation = Navigation::new(move_here.movement.speed());
     **dest = Some(move_here.endpoint);
+
+    if let Movement::Run = move_here.movement {
+        commands
+            .entity(trigger.entity())
+            .insert(SkillTraining(SkillKind::Fitness));
+    }
 }
 
 fn finish(
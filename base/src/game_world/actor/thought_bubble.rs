@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_mod_billboard::prelude::*;
+
+use super::Actor;
+use crate::{core::GameState, game_world::navigation::PathFailed};
+
+pub(super) struct ThoughtBubblePlugin;
+
+impl Plugin for ThoughtBubblePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ThoughtFont>().add_systems(
+            Update,
+            (show_path_failures, expire)
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+/// Height of bubbles above actors.
+const BUBBLE_HEIGHT: f32 = 2.2;
+
+/// How long a bubble stays visible.
+const BUBBLE_DURATION: Duration = Duration::from_secs(3);
+
+fn show_path_failures(
+    mut commands: Commands,
+    font: Res<ThoughtFont>,
+    mut failed_events: EventReader<PathFailed>,
+    actors: Query<Option<&Children>, With<Actor>>,
+    mut bubbles: Query<(&mut ThoughtBubble, &mut BillboardText)>,
+) {
+    for event in failed_events.read() {
+        let Ok(children) = actors.get(event.actor_entity) else {
+            continue;
+        };
+
+        let glyph = if event.retrying { "❓" } else { "❌" };
+        if let Some(children) = children {
+            let mut iter = bubbles.iter_many_mut(children);
+            if let Some((mut bubble, mut text)) = iter.fetch_next() {
+                debug!("refreshing thought bubble for `{}`", event.actor_entity);
+                bubble.reset();
+                text.0 = glyph.into();
+                continue;
+            }
+        }
+
+        debug!("showing thought bubble for `{}`", event.actor_entity);
+        commands.entity(event.actor_entity).with_children(|parent| {
+            parent.spawn((
+                ThoughtBubble::default(),
+                BillboardText(glyph.into()),
+                Transform::from_translation(Vec3::Y * BUBBLE_HEIGHT).with_scale(Vec3::splat(0.005)),
+                TextFont {
+                    font: font.0.clone(),
+                    font_size: 80.0,
+                    ..Default::default()
+                },
+                TextColor::WHITE,
+            ));
+        });
+    }
+}
+
+fn expire(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bubbles: Query<(Entity, &mut ThoughtBubble)>,
+) {
+    for (entity, mut bubble) in &mut bubbles {
+        bubble.tick(time.delta());
+        if bubble.finished() {
+            debug!("hiding thought bubble `{entity}`");
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// A short-lived glyph above an actor that shows what it thinks about.
+#[derive(Component, Deref, DerefMut)]
+struct ThoughtBubble(Timer);
+
+impl Default for ThoughtBubble {
+    fn default() -> Self {
+        Self(Timer::new(BUBBLE_DURATION, TimerMode::Once))
+    }
+}
+
+#[derive(Resource)]
+struct ThoughtFont(Handle<Font>);
+
+impl FromWorld for ThoughtFont {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let font_handle = asset_server.load("base/fonts/NotoEmoji-Regular.ttf");
+        Self(font_handle)
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::{asset::AssetPath, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::needs::{NeedKind, NeedRateFactor};
+use crate::{
+    asset::manifest::trait_manifest::TraitManifest, core::GameState,
+    game_world::family::editor::EditorTraits,
+};
+
+pub(super) struct TraitsPlugin;
+
+impl Plugin for TraitsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Traits>()
+            .replicate::<Traits>()
+            .add_systems(
+                Update,
+                apply_effects
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Updates trait modifiers for actors and their needs.
+///
+/// Needs are spawned after actors, so also reacts on children changes.
+fn apply_effects(
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<TraitManifest>>,
+    mut actors: Query<
+        (Entity, &Traits, &Children, &mut TraitEffects),
+        Or<(Changed<Traits>, Changed<Children>)>,
+    >,
+    mut needs: Query<(&NeedKind, &mut NeedRateFactor)>,
+) {
+    for (entity, traits, children, mut effects) in &mut actors {
+        debug!("applying traits for `{entity}`");
+        let trait_manifests: Vec<_> = traits
+            .iter()
+            .filter_map(|path| {
+                let Some(handle) = asset_server.get_handle(path) else {
+                    error!("'{path}' is missing, ignoring");
+                    return None;
+                };
+                manifests.get(&handle)
+            })
+            .collect();
+
+        effects.social = trait_manifests
+            .iter()
+            .map(|manifest| manifest.social)
+            .product();
+
+        let mut iter = needs.iter_many_mut(children);
+        while let Some((kind, mut factor)) = iter.fetch_next() {
+            factor.0 = trait_manifests
+                .iter()
+                .filter_map(|manifest| manifest.need_rates.get(kind))
+                .product();
+        }
+    }
+}
+
+/// Personality traits of an actor.
+///
+/// Stores paths to trait manifests.
+#[derive(Component, Clone, Default, Deref, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+#[require(TraitEffects)]
+pub struct Traits(Vec<AssetPath<'static>>);
+
+impl Traits {
+    /// Maximum number of traits per actor.
+    pub const MAX: usize = 3;
+}
+
+impl From<EditorTraits> for Traits {
+    fn from(value: EditorTraits) -> Self {
+        Self(value.0)
+    }
+}
+
+/// Combined effects of all actor traits.
+///
+/// Calculated from [`TraitManifest`]s on the server.
+#[derive(Component)]
+pub(super) struct TraitEffects {
+    /// Multiplier for need gains from social tasks.
+    pub(super) social: f32,
+}
+
+impl Default for TraitEffects {
+    fn default() -> Self {
+        Self { social: 1.0 }
+    }
+}
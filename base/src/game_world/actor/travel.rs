@@ -0,0 +1,365 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{entity::MapEntities, reflect::ReflectMapEntities, system::SystemParam},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    job::AtWork,
+    task::{ActiveTask, Task},
+    Actor, Movement,
+};
+use crate::{
+    core::GameState,
+    game_world::{
+        city::{road::RoadData, terrain::Heightmap, City, HALF_CITY_SIZE},
+        navigation::{following::Following, NavDestination, Navigation},
+        segment::Segment,
+    },
+};
+
+pub(super) struct TravelPlugin;
+
+impl Plugin for TravelPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Traveling>()
+            .register_type::<TravelTimer>()
+            .replicate_mapped::<Traveling>()
+            .add_mapped_client_event::<TravelRequest>(ChannelKind::Unordered)
+            .add_observer(walk_to_exit)
+            .add_observer(hide)
+            .add_observer(show)
+            .add_systems(
+                PreUpdate,
+                start_departures
+                    .after(ClientSet::Receive)
+                    .run_if(server_or_singleplayer),
+            )
+            .add_systems(
+                Update,
+                (leave, arrive)
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Speed of moving between cities.
+const TRAVEL_SPEED: f32 = 25.0;
+
+/// Minimal travel time.
+const MIN_TRAVEL_TIME: Duration = Duration::from_secs(5);
+
+/// Distance from the city border to exit points.
+const EDGE_MARGIN: f32 = 1.0;
+
+/// Maximum distance between an exit and an actor to consider it reached.
+const EXIT_TOLERANCE: f32 = 1.0;
+
+fn start_departures(
+    mut commands: Commands,
+    mut request_events: EventReader<FromClient<TravelRequest>>,
+    cities: Query<(), With<City>>,
+    actors: Query<&Parent, (With<Actor>, Without<AtWork>, Without<Traveling>)>,
+) {
+    for FromClient { client_id, event } in request_events.read() {
+        if let Err(e) = cities.get(event.city_entity) {
+            error!("unable to travel to `{}`: {e}", event.city_entity);
+            continue;
+        }
+        let Ok(parent) = actors.get(event.actor_entity) else {
+            error!("`{}` can't travel now", event.actor_entity);
+            continue;
+        };
+        if **parent == event.city_entity {
+            error!(
+                "`{}` is already in `{}`",
+                event.actor_entity, event.city_entity
+            );
+            continue;
+        }
+
+        info!(
+            "`{client_id:?}` sends `{}` to `{}`",
+            event.actor_entity, event.city_entity
+        );
+        commands
+            .entity(event.actor_entity)
+            .insert(Departure::new(TravelDestination::City(event.city_entity)));
+    }
+}
+
+/// Cancels tasks of the departing actor and sends it to the nearest exit.
+fn walk_to_exit(
+    trigger: Trigger<OnAdd, Departure>,
+    mut commands: Commands,
+    exits: CityExits,
+    mut actors: Query<(&Parent, &Transform, &Children, &mut Departure)>,
+    followers: Query<(&Following, &Children)>,
+    tasks: Query<Entity, With<Task>>,
+    active_tasks: Query<Entity, (With<Task>, With<ActiveTask>)>,
+) {
+    let (parent, transform, children, mut departure) = actors.get_mut(trigger.entity()).unwrap();
+
+    for task_entity in tasks.iter_many(children) {
+        commands.entity(task_entity).despawn();
+    }
+    // Cancel tasks that target the actor.
+    for (following, follower_children) in &followers {
+        if following.0 == trigger.entity() {
+            for task_entity in active_tasks.iter_many(follower_children) {
+                commands.entity(task_entity).despawn();
+            }
+        }
+    }
+
+    let exit = exits.nearest(**parent, transform.translation);
+    departure.origin = transform.translation;
+    departure.exit = exit;
+
+    // Despawned tasks reset the destination, so assign it after them.
+    commands
+        .entity(trigger.entity())
+        .queue(move |mut entity: EntityWorldMut| {
+            debug!("sending `{}` to exit {exit}", entity.id());
+            *entity.get_mut::<Navigation>().unwrap() = Navigation::new(Movement::Walk.speed());
+            **entity.get_mut::<NavDestination>().unwrap() = Some(exit);
+        });
+}
+
+/// Removes actors that reached their exits from the local simulation.
+fn leave(
+    mut commands: Commands,
+    cities: Query<&GlobalTransform>,
+    actors: Query<
+        (Entity, &Parent, &Transform, &NavDestination, &Departure),
+        Changed<NavDestination>,
+    >,
+) {
+    for (entity, parent, transform, dest, departure) in &actors {
+        if dest.is_some() {
+            continue;
+        }
+
+        commands.entity(entity).remove::<Departure>();
+        if transform.translation.distance(departure.exit) > EXIT_TOLERANCE {
+            debug!("`{entity}` didn't reach the exit, cancelling departure");
+            continue;
+        }
+
+        match departure.destination {
+            TravelDestination::City(city_entity) => {
+                let from = cities.get(**parent).unwrap().translation();
+                let to = cities.get(city_entity).unwrap().translation();
+                let time = Duration::from_secs_f32(from.distance(to) / TRAVEL_SPEED);
+                info!("`{entity}` leaves for `{city_entity}`");
+                commands.entity(entity).insert((
+                    Traveling { city_entity },
+                    TravelTimer(Timer::new(time.max(MIN_TRAVEL_TIME), TimerMode::Once)),
+                ));
+            }
+            TravelDestination::Work => {
+                info!("`{entity}` leaves for work");
+                commands.entity(entity).insert(AtWork {
+                    home: departure.origin,
+                });
+            }
+        }
+    }
+}
+
+/// Moves travelers into their target cities when the travel time passes.
+fn arrive(
+    mut commands: Commands,
+    time: Res<Time>,
+    exits: CityExits,
+    cities: Query<(&GlobalTransform, &Heightmap)>,
+    mut actors: Query<(
+        Entity,
+        &Parent,
+        &Traveling,
+        &mut TravelTimer,
+        &mut Transform,
+        &mut NavDestination,
+    )>,
+) {
+    for (entity, parent, traveling, mut timer, mut transform, mut dest) in &mut actors {
+        timer.tick(time.delta());
+        if !timer.finished() {
+            continue;
+        }
+
+        // Cities could be removed while the actor is traveling.
+        let from = cities.get(**parent).ok();
+        let (city_entity, (to, heightmap)) = match cities.get(traveling.city_entity) {
+            Ok(city) => (traveling.city_entity, city),
+            Err(e) => {
+                error!(
+                    "unable to arrive `{entity}` to `{}`: {e}",
+                    traveling.city_entity
+                );
+                let Some(city) = from else {
+                    error!(
+                        "unable to return `{entity}` to `{}`: city is missing",
+                        **parent
+                    );
+                    commands.entity(entity).remove::<(Traveling, TravelTimer)>();
+                    continue;
+                };
+                info!("`{entity}` returns to `{}`", **parent);
+                (**parent, city)
+            }
+        };
+
+        // Enter from the side that faces the original city.
+        let direction = from
+            .map(|(from, _)| (from.translation() - to.translation()).xz())
+            .unwrap_or_default()
+            .normalize_or(Vec2::X);
+        let outside = direction * HALF_CITY_SIZE * 2.0;
+        let mut entry = exits.nearest(city_entity, Vec3::new(outside.x, 0.0, outside.y));
+        entry.y = heightmap.height_at(entry.xz());
+
+        info!("`{entity}` arrives to `{city_entity}`");
+        transform.translation = entry;
+        **dest = None;
+        commands
+            .entity(entity)
+            .set_parent(city_entity)
+            .remove::<(Traveling, TravelTimer)>();
+    }
+}
+
+fn hide(trigger: Trigger<OnAdd, Traveling>, mut actors: Query<&mut Visibility>) {
+    debug!("hiding traveling `{}`", trigger.entity());
+    let mut visibility = actors.get_mut(trigger.entity()).unwrap();
+    *visibility = Visibility::Hidden;
+}
+
+fn show(trigger: Trigger<OnRemove, Traveling>, mut actors: Query<&mut Visibility>) {
+    debug!("showing `{}` after travel", trigger.entity());
+    if let Ok(mut visibility) = actors.get_mut(trigger.entity()) {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Points where actors can leave a city.
+#[derive(SystemParam)]
+struct CityExits<'w, 's> {
+    cities: Query<'w, 's, &'static Children>,
+    roads: Query<'w, 's, &'static Segment, With<RoadData>>,
+}
+
+impl CityExits<'_, '_> {
+    /// Returns the exit closest to the point.
+    ///
+    /// Road ends near the city border are preferred, otherwise the nearest point on the border is used.
+    fn nearest(&self, city_entity: Entity, point: Vec3) -> Vec3 {
+        let exit_distance = HALF_CITY_SIZE - EDGE_MARGIN;
+        let road_exit = self
+            .cities
+            .get(city_entity)
+            .into_iter()
+            .flat_map(|children| self.roads.iter_many(children))
+            .flat_map(|segment| segment.points())
+            .filter(|point| point.abs().max_element() >= exit_distance - EXIT_TOLERANCE)
+            .map(|exit| exit.clamp(Vec2::splat(-exit_distance), Vec2::splat(exit_distance)))
+            .min_by(|a, b| {
+                a.distance_squared(point.xz())
+                    .total_cmp(&b.distance_squared(point.xz()))
+            });
+        if let Some(exit) = road_exit {
+            return Vec3::new(exit.x, point.y, exit.y);
+        }
+
+        let mut exit = point
+            .xz()
+            .clamp(Vec2::splat(-exit_distance), Vec2::splat(exit_distance));
+        let to_border = exit_distance - exit.abs();
+        if to_border.x < to_border.y {
+            exit.x = exit_distance.copysign(exit.x);
+        } else {
+            exit.y = exit_distance.copysign(exit.y);
+        }
+
+        Vec3::new(exit.x, point.y, exit.y)
+    }
+}
+
+/// Sends an actor to another city.
+///
+/// Emitted by players.
+#[derive(Clone, Deserialize, Event, Serialize)]
+pub struct TravelRequest {
+    pub actor_entity: Entity,
+    pub city_entity: Entity,
+}
+
+impl MapEntities for TravelRequest {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.actor_entity = entity_mapper.map_entity(self.actor_entity);
+        self.city_entity = entity_mapper.map_entity(self.city_entity);
+    }
+}
+
+/// Indicates that the actor walks to a city exit.
+///
+/// Cancels all actor tasks on insertion.
+/// Exists only on server.
+#[derive(Component)]
+pub(super) struct Departure {
+    destination: TravelDestination,
+
+    /// Actor position at the moment of departure.
+    ///
+    /// Assigned on insertion.
+    origin: Vec3,
+
+    /// Assigned on insertion.
+    exit: Vec3,
+}
+
+impl Departure {
+    pub(super) fn new(destination: TravelDestination) -> Self {
+        Self {
+            destination,
+            origin: Vec3::ZERO,
+            exit: Vec3::ZERO,
+        }
+    }
+
+    pub(super) fn destination(&self) -> TravelDestination {
+        self.destination
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum TravelDestination {
+    City(Entity),
+    Work,
+}
+
+/// Indicates that the actor left its city and is on the way to another one.
+///
+/// Such actors are hidden and excluded from navigation.
+#[derive(Component, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+pub struct Traveling {
+    pub city_entity: Entity,
+}
+
+impl MapEntities for Traveling {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.city_entity = entity_mapper.map_entity(self.city_entity);
+    }
+}
+
+/// Remaining travel time.
+///
+/// Exists only on server, but saved with the world.
+#[derive(Component, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+pub(crate) struct TravelTimer(Timer);
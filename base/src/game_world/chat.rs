@@ -0,0 +1,394 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use bevy_replicon_renet::netcode::{NetcodeServerTransport, NETCODE_USER_DATA_BYTES};
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use super::{
+    clock::WorldClock,
+    family::{Family, FamilyControllers},
+    GameSave,
+};
+use crate::{core::GameState, network};
+
+pub(super) struct ChatPlugin;
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatHistory>()
+            .init_resource::<ChatRateLimit>()
+            .init_resource::<ChatAdmin>()
+            .init_resource::<ConnectedAdmins>()
+            .add_client_event::<ChatSend>(ChannelKind::Ordered)
+            .add_server_event::<ChatMessage>(ChannelKind::Ordered)
+            .add_systems(
+                PreUpdate,
+                (
+                    authenticate_admins
+                        .after(ServerSet::Receive)
+                        .run_if(server_running),
+                    reset_admins.run_if(server_just_stopped),
+                    relay
+                        .after(ClientSet::Receive)
+                        .run_if(server_or_singleplayer)
+                        .run_if(in_state(GameState::InGame)),
+                ),
+            )
+            .add_systems(Update, record.run_if(in_state(GameState::InGame)))
+            .add_systems(OnExit(GameState::InGame), cleanup);
+    }
+}
+
+/// Maximum number of characters in a message.
+const MAX_MESSAGE_LEN: usize = 256;
+
+/// Maximum number of messages kept in [`ChatHistory`].
+const MAX_HISTORY_LEN: usize = 100;
+
+/// Checks the password from the connection user data of new clients.
+fn authenticate_admins(
+    admin: Res<ChatAdmin>,
+    transport: Option<Res<NetcodeServerTransport>>,
+    mut connected_admins: ResMut<ConnectedAdmins>,
+    mut server_events: EventReader<ServerEvent>,
+) {
+    for event in server_events.read() {
+        match *event {
+            ServerEvent::ClientConnected { client_id } => {
+                let user_data = transport
+                    .as_ref()
+                    .and_then(|transport| transport.user_data(client_id.get()));
+                if user_data.is_some_and(|user_data| admin.authenticates(&user_data)) {
+                    info!("`{client_id:?}` connected as admin");
+                    connected_admins.insert(client_id);
+                }
+            }
+            ServerEvent::ClientDisconnected { client_id, .. } => {
+                connected_admins.remove(&client_id);
+            }
+        }
+    }
+}
+
+fn reset_admins(mut connected_admins: ResMut<ConnectedAdmins>) {
+    connected_admins.clear();
+}
+
+/// Validates messages from clients and sends them to the channel members.
+///
+/// Messages that start with `/` are executed as [`ChatCommand`] if sent by the [`ChatAdmin`].
+fn relay(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut clock: ResMut<WorldClock>,
+    mut rate_limit: ResMut<ChatRateLimit>,
+    admin: Res<ChatAdmin>,
+    connected_admins: Res<ConnectedAdmins>,
+    controllers: Res<FamilyControllers>,
+    mut send_events: EventReader<FromClient<ChatSend>>,
+    mut message_events: EventWriter<ToClients<ChatMessage>>,
+    families: Query<&Name, With<Family>>,
+) {
+    for FromClient { client_id, event } in send_events.read() {
+        let client_id = *client_id;
+        let text = event.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let mut notify = |text: String| {
+            message_events.send(ToClients {
+                mode: SendMode::Direct(client_id),
+                event: ChatMessage {
+                    sender: None,
+                    channel: event.channel,
+                    text,
+                },
+            });
+        };
+
+        if !rate_limit.allow(client_id, time.elapsed()) {
+            debug!("rejecting chat message from `{client_id:?}` due to rate limit");
+            notify("You are sending messages too fast".to_string());
+            continue;
+        }
+
+        if let Some(command) = text.strip_prefix('/') {
+            if !admin.allows(client_id, &connected_admins) {
+                notify("Only admins can use commands".to_string());
+                continue;
+            }
+
+            info!("executing chat command `/{command}`");
+            match ChatCommand::parse(command) {
+                Ok(command) => notify(command.execute(&mut commands, &mut clock)),
+                Err(e) => notify(format!("{e:#}")),
+            }
+            continue;
+        }
+
+        // Taken from the server state, so clients can't speak for other families.
+        let family = controllers.get(&client_id).and_then(|&family_entity| {
+            families
+                .get(family_entity)
+                .ok()
+                .map(|name| (family_entity, name))
+        });
+        if event.channel == ChatChannel::Family && family.is_none() {
+            notify("Select a family to use the family channel".to_string());
+            continue;
+        }
+
+        let sender = match family {
+            Some((_, name)) => name.to_string(),
+            None if client_id == ClientId::SERVER => "Host".to_string(),
+            None => format!("Player {}", client_id.get()),
+        };
+        debug!(
+            "relaying chat message from '{sender}' to {:?}",
+            event.channel
+        );
+        let message = ChatMessage {
+            sender: Some(sender),
+            channel: event.channel,
+            text: text.chars().take(MAX_MESSAGE_LEN).collect(),
+        };
+        match family {
+            Some((family_entity, _)) if event.channel == ChatChannel::Family => {
+                for client_id in controllers.clients(family_entity) {
+                    message_events.send(ToClients {
+                        mode: SendMode::Direct(client_id),
+                        event: message.clone(),
+                    });
+                }
+            }
+            _ => {
+                message_events.send(ToClients {
+                    mode: SendMode::Broadcast,
+                    event: message,
+                });
+            }
+        }
+    }
+}
+
+/// Stores received messages.
+///
+/// Server sends only messages that are visible to the local player.
+fn record(mut history: ResMut<ChatHistory>, mut message_events: EventReader<ChatMessage>) {
+    for message in message_events.read() {
+        if history.len() == MAX_HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(message.clone());
+    }
+}
+
+fn cleanup(mut history: ResMut<ChatHistory>, mut rate_limit: ResMut<ChatRateLimit>) {
+    history.clear();
+    rate_limit.clear();
+}
+
+/// Messages received during the current session.
+///
+/// Cleared when leaving the game.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ChatHistory(VecDeque<ChatMessage>);
+
+/// Determines who can execute [`ChatCommand`].
+///
+/// Read only on server.
+#[derive(Resource, Default, Clone)]
+pub enum ChatAdmin {
+    /// The player who hosts the game.
+    #[default]
+    Host,
+
+    /// Clients that passed the password when connecting.
+    ///
+    /// Used by dedicated servers since they have no local player.
+    /// Nobody can execute commands if the password is [`None`] or empty.
+    Password(Option<String>),
+}
+
+impl ChatAdmin {
+    fn allows(&self, client_id: ClientId, connected_admins: &ConnectedAdmins) -> bool {
+        match self {
+            Self::Host => client_id == ClientId::SERVER,
+            Self::Password(_) => connected_admins.contains(&client_id),
+        }
+    }
+
+    /// Returns `true` if the connection user data contains the admin password.
+    fn authenticates(&self, user_data: &[u8; NETCODE_USER_DATA_BYTES]) -> bool {
+        let Self::Password(Some(password)) = self else {
+            return false;
+        };
+
+        // Empty password would match clients without user data.
+        !password.is_empty()
+            && network::password_data(password).is_ok_and(|expected| expected == *user_data)
+    }
+}
+
+/// Clients authenticated by [`ChatAdmin::Password`].
+///
+/// Tracked outside of the game state because clients may connect before the world is loaded.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ConnectedAdmins(HashSet<ClientId>);
+
+/// Recent message times for each client.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ChatRateLimit(HashMap<ClientId, VecDeque<Duration>>);
+
+impl ChatRateLimit {
+    /// Maximum number of messages per [`Self::WINDOW`].
+    const MAX_MESSAGES: usize = 5;
+    const WINDOW: Duration = Duration::from_secs(10);
+
+    /// Records the message time and returns `true` if the client hasn't exceeded the limit.
+    fn allow(&mut self, client_id: ClientId, now: Duration) -> bool {
+        let times = self.entry(client_id).or_default();
+        while times
+            .front()
+            .is_some_and(|&time| now.saturating_sub(time) >= Self::WINDOW)
+        {
+            times.pop_front();
+        }
+
+        if times.len() >= Self::MAX_MESSAGES {
+            return false;
+        }
+
+        times.push_back(now);
+        true
+    }
+}
+
+/// Server action requested by the [`ChatAdmin`] from chat.
+enum ChatCommand {
+    Save,
+    Speed(f32),
+}
+
+impl ChatCommand {
+    /// Parses a command without the leading `/`.
+    fn parse(command: &str) -> Result<Self> {
+        let mut args = command.split_whitespace();
+        let command = match args.next() {
+            Some("save") => Self::Save,
+            Some("speed") => {
+                let speed = args.next().context("`/speed` requires a multiplier")?;
+                let speed: f32 = speed
+                    .parse()
+                    .with_context(|| format!("`{speed}` is not a valid speed"))?;
+                if !(0.0..=WorldClock::MAX_SPEED).contains(&speed) {
+                    bail!(
+                        "speed should be between 0 and {}, but got {speed}",
+                        WorldClock::MAX_SPEED
+                    );
+                }
+                Self::Speed(speed)
+            }
+            Some(name) => bail!("unknown command `/{name}`"),
+            None => bail!("missing command name"),
+        };
+
+        if let Some(arg) = args.next() {
+            bail!("unexpected argument `{arg}`");
+        }
+
+        Ok(command)
+    }
+
+    /// Applies the command and returns a message for the admin.
+    fn execute(self, commands: &mut Commands, clock: &mut WorldClock) -> String {
+        match self {
+            Self::Save => {
+                commands.trigger(GameSave);
+                "Saving the world".to_string()
+            }
+            Self::Speed(speed) => {
+                clock.set_speed(speed);
+                format!("Game speed set to {}", clock.speed())
+            }
+        }
+    }
+}
+
+#[derive(
+    Clone, Component, Copy, Debug, Default, Deserialize, EnumIter, Eq, PartialEq, Serialize,
+)]
+pub enum ChatChannel {
+    #[default]
+    All,
+    Family,
+}
+
+impl ChatChannel {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::All => "🌐",
+            Self::Family => "👪",
+        }
+    }
+}
+
+/// A message that client sends to the chat.
+///
+/// The family of the sender is taken from [`FamilyControllers`] and used as the sender name.
+/// It's required for [`ChatChannel::Family`].
+#[derive(Deserialize, Event, Serialize)]
+pub struct ChatSend {
+    pub channel: ChatChannel,
+    pub text: String,
+}
+
+/// A message relayed by the server.
+#[derive(Clone, Deserialize, Event, Serialize)]
+pub struct ChatMessage {
+    /// Sender name or [`None`] for server notices.
+    pub sender: Option<String>,
+    pub channel: ChatChannel,
+    pub text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_world::test_app;
+
+    #[test]
+    fn non_admin_command() {
+        let (mut server_app, mut client_app) = test_app::new_connected_apps();
+        server_app.insert_resource(ChatAdmin::Password(Some("password".to_string())));
+        let speed = server_app.world().resource::<WorldClock>().speed();
+
+        client_app.world_mut().send_event(ChatSend {
+            channel: ChatChannel::All,
+            text: "/speed 5".to_string(),
+        });
+        for _ in 0..3 {
+            test_app::exchange(&mut server_app, &mut client_app);
+        }
+
+        assert_eq!(
+            server_app.world().resource::<WorldClock>().speed(),
+            speed,
+            "command from a client without the password should be ignored"
+        );
+        let history = client_app.world().resource::<ChatHistory>();
+        assert!(
+            history
+                .iter()
+                .any(|message| message.sender.is_none() && message.text.contains("admins")),
+            "client should be notified about the rejection"
+        );
+    }
+}
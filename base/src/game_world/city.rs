@@ -1,9 +1,13 @@
+pub mod daylight;
 pub mod road;
+pub mod terrain;
+pub mod water;
+pub mod weather;
 
 use std::f32::consts::FRAC_PI_2;
 
 use avian3d::prelude::*;
-use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+use bevy::prelude::*;
 use bevy_atmosphere::prelude::*;
 use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -13,26 +17,44 @@ use vleue_navigator::prelude::*;
 use super::{actor::SelectedActor, WorldState};
 use crate::{
     core::GameState,
-    game_world::{actor::ACTOR_RADIUS, player_camera::PlayerCamera, Layer},
+    dynamic_mesh::DynamicMesh,
+    game_world::{actor::ACTOR_RADIUS, navigation::nav_tiles, player_camera::PlayerCamera, Layer},
 };
+use daylight::DaylightPlugin;
 use road::RoadPlugin;
+use terrain::{splat_map::SplatMap, Heightmap, TerrainPlugin};
+use water::WaterPlugin;
+use weather::{Weather, WeatherPlugin, WeatherSeed};
 
 pub(super) struct CityPlugin;
 
 impl Plugin for CityPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(RoadPlugin)
-            .add_sub_state::<CityMode>()
-            .enable_state_scoped_entities::<CityMode>()
-            .register_type::<City>()
-            .replicate_group::<(City, Name)>()
-            .init_resource::<PlacedCities>()
-            .add_observer(init)
-            .add_observer(activate)
-            .add_systems(OnEnter(WorldState::Family), activate_by_actor)
-            .add_systems(OnExit(WorldState::City), deactivate.never_param_warn())
-            .add_systems(OnExit(WorldState::Family), deactivate.never_param_warn())
-            .add_systems(OnExit(GameState::InGame), cleanup);
+        app.add_plugins((
+            DaylightPlugin,
+            RoadPlugin,
+            TerrainPlugin,
+            WaterPlugin,
+            WeatherPlugin,
+        ))
+        .add_sub_state::<CityMode>()
+        .enable_state_scoped_entities::<CityMode>()
+        .register_type::<City>()
+        .replicate_group::<(City, Name)>()
+        .init_resource::<PlacedCities>()
+        .add_observer(init)
+        .add_observer(activate)
+        .add_systems(OnEnter(WorldState::Family), activate_by_actor)
+        .add_systems(OnExit(WorldState::City), deactivate.never_param_warn())
+        .add_systems(OnExit(WorldState::Family), deactivate.never_param_warn())
+        .add_systems(OnExit(GameState::InGame), cleanup)
+        .add_systems(
+            Update,
+            (deactivate, activate_by_actor)
+                .chain()
+                .run_if(in_state(WorldState::Family))
+                .run_if(selected_actor_traveled),
+        );
     }
 }
 
@@ -43,9 +65,8 @@ pub(super) const HALF_CITY_SIZE: f32 = CITY_SIZE / 2.0;
 /// Inserts [`TransformBundle`] and places cities next to each other.
 fn init(
     trigger: Trigger<OnAdd, City>,
-    ground_mesh: Local<GroundMesh>,
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
     mut placed_citites: ResMut<PlacedCities>,
     mut cities: Query<(&mut Transform, &mut CityNavMesh)>,
 ) {
@@ -54,34 +75,31 @@ fn init(
     transform.translation = Vec3::X * CITY_SIZE * **placed_citites as f32;
 
     commands.entity(trigger.entity()).with_children(|parent| {
-        parent.spawn((
-            Ground,
-            Mesh3d(ground_mesh.0.clone()),
-            MeshMaterial3d::<StandardMaterial>(
-                asset_server.load("base/ground/spring_grass/spring_glass.ron"),
-            ),
-        ));
-
-        nav_mesh.0 = parent
-            .spawn((
-                ManagedNavMesh::from_id(**placed_citites as u128),
-                NavMeshSettings {
-                    fixed: Triangulation::from_outer_edges(&[
-                        Vec2::new(-HALF_CITY_SIZE, -HALF_CITY_SIZE),
-                        Vec2::new(HALF_CITY_SIZE, -HALF_CITY_SIZE),
-                        Vec2::new(HALF_CITY_SIZE, HALF_CITY_SIZE),
-                        Vec2::new(-HALF_CITY_SIZE, HALF_CITY_SIZE),
-                    ]),
-                    agent_radius: ACTOR_RADIUS,
-                    merge_steps: 1, // Merge triangles when possible to reduce the number of triangles.
-                    simplify: 0.01, // Remove points that contribute very little to the mesh.
-                    default_search_delta: 0.2, // To avoid agents stuck on namesh edges.
-                    ..Default::default()
-                },
-                Transform::from_rotation(Quat::from_rotation_x(FRAC_PI_2)),
-                NavMeshUpdateMode::Direct,
-            ))
-            .id();
+        // Mesh generated from the heightmap and material assigned from the splat map.
+        parent.spawn((Ground, Mesh3d(meshes.add(DynamicMesh::create_empty()))));
+
+        let tiles_count = nav_tiles::tiles().count();
+        nav_mesh.0 = nav_tiles::tiles()
+            .map(|tile| {
+                let id = **placed_citites * tiles_count + nav_tiles::tile_index(tile);
+                parent
+                    .spawn((
+                        ManagedNavMesh::from_id(id as u128),
+                        NavMeshSettings {
+                            fixed: nav_tiles::tile_triangulation(tile),
+                            agent_radius: ACTOR_RADIUS,
+                            merge_steps: 1, // Merge triangles when possible to reduce the number of triangles.
+                            simplify: 0.01, // Remove points that contribute very little to the mesh.
+                            default_search_delta: 0.2, // To avoid agents stuck on namesh edges.
+                            ..Default::default()
+                        },
+                        Transform::from_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+                        // Rebuilt only when obstacles inside the tile change.
+                        NavMeshUpdateMode::OnDemand(true),
+                    ))
+                    .id()
+            })
+            .collect();
     });
 
     **placed_citites += 1;
@@ -98,10 +116,7 @@ fn activate(
     *visibility = Visibility::Visible;
 
     commands.entity(trigger.entity()).with_children(|parent| {
-        parent.spawn((
-            Sun,
-            Transform::from_xyz(4.0, 7.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-        ));
+        parent.spawn(Sun);
         parent.spawn((PlayerCamera, AtmosphereCamera::default()));
     });
 }
@@ -111,6 +126,18 @@ fn activate_by_actor(mut commands: Commands, actor_parent: Single<&Parent, With<
     commands.entity(***actor_parent).insert(ActiveCity);
 }
 
+/// Returns `true` if the selected actor moved to another city.
+fn selected_actor_traveled(
+    actor_parent: Option<Single<Ref<Parent>, With<SelectedActor>>>,
+    active_city: Option<Single<Entity, With<ActiveCity>>>,
+) -> bool {
+    let (Some(actor_parent), Some(active_city)) = (actor_parent, active_city) else {
+        return false;
+    };
+
+    actor_parent.is_changed() && ***actor_parent != *active_city
+}
+
 fn deactivate(
     mut commands: Commands,
     active_city: Single<(Entity, &mut Visibility), With<ActiveCity>>,
@@ -130,36 +157,14 @@ fn cleanup(mut placed_citites: ResMut<PlacedCities>) {
     placed_citites.0 = 0;
 }
 
-struct GroundMesh(Handle<Mesh>);
-
-impl FromWorld for GroundMesh {
-    fn from_world(world: &mut World) -> Self {
-        let mut mesh = Plane3d::default().mesh().size(CITY_SIZE, CITY_SIZE).build();
-        let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
-        else {
-            panic!("generated plane should have UVs");
-        };
-
-        // Adjust UVs to tile the texture properly.
-        for point in uvs {
-            for value in point {
-                *value *= CITY_SIZE;
-            }
-        }
-
-        let mut meshes = world.resource_mut::<Assets<Mesh>>();
-        let mesh_handle = meshes.add(mesh);
-
-        Self(mesh_handle)
-    }
-}
-
 #[derive(Clone, Component, Copy, Debug, Default, EnumIter, Eq, Hash, PartialEq, SubStates)]
 #[source(WorldState = WorldState::City)]
 pub enum CityMode {
     #[default]
     Objects,
     Roads,
+    Terrain,
+    Water,
 }
 
 impl CityMode {
@@ -167,6 +172,8 @@ impl CityMode {
         match self {
             Self::Objects => "🌳",
             Self::Roads => "🚧",
+            Self::Terrain => "⛰",
+            Self::Water => "🌊",
         }
     }
 }
@@ -178,7 +185,11 @@ impl CityMode {
     Replicated,
     Transform,
     Visibility(|| Visibility::Hidden),
-    CityNavMesh(|| CityNavMesh(Entity::PLACEHOLDER)),
+    Heightmap,
+    SplatMap,
+    Weather,
+    WeatherSeed,
+    CityNavMesh,
     StateScoped<GameState>(|| StateScoped(GameState::InGame)),
 )]
 pub struct City;
@@ -187,9 +198,11 @@ pub struct City;
 #[require(City)]
 pub struct ActiveCity;
 
-/// Points to assigned navmesh for a city.
-#[derive(Component, Deref)]
-pub(super) struct CityNavMesh(Entity);
+/// Points to assigned navmesh tiles for a city.
+///
+/// Tiles are stored in the order of [`nav_tiles::tiles`].
+#[derive(Component, Default, Deref)]
+pub(super) struct CityNavMesh(Vec<Entity>);
 
 /// Number of placed cities.
 ///
@@ -203,7 +216,7 @@ struct PlacedCities(usize);
     Name(|| Name::new("Ground")),
     Mesh3d,
     MeshMaterial3d<StandardMaterial>,
-    Collider(|| Collider::cuboid(CITY_SIZE, 0.0, CITY_SIZE)),
+    Collider,
     CollisionLayers(|| CollisionLayers::new(Layer::Ground, LayerMask::ALL)),
 )]
 pub(super) struct Ground;
@@ -0,0 +1,228 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_atmosphere::prelude::*;
+
+use super::Sun;
+use crate::{
+    core::GameState,
+    game_world::clock::{Season, WorldClock},
+};
+
+pub(super) struct DaylightPlugin;
+
+impl Plugin for DaylightPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_sun
+                .never_param_warn()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+/// Illuminance of the direct sunlight at zenith.
+const DAY_ILLUMINANCE: f32 = light_consts::lux::AMBIENT_DAYLIGHT;
+
+/// Illuminance of the moonlight.
+const NIGHT_ILLUMINANCE: f32 = 50.0;
+
+fn update_sun(
+    clock: Res<WorldClock>,
+    mut atmosphere: AtmosphereMut<Nishita>,
+    sun: Single<(&mut Transform, &mut DirectionalLight), With<Sun>>,
+) {
+    let (mut transform, mut light) = sun.into_inner();
+    let sun_light = SunLight::new(clock.time_of_day(), clock.season());
+
+    *transform = Transform::default().looking_to(sun_light.light_direction(), Vec3::Y);
+    light.color = sun_light.color;
+    light.illuminance = sun_light.illuminance;
+
+    // Sky regeneration is expensive, update it only on noticeable changes.
+    const SKY_THRESHOLD: f32 = 0.001;
+    if atmosphere
+        .sun_position
+        .distance_squared(sun_light.sun_position)
+        > SKY_THRESHOLD
+    {
+        atmosphere.sun_position = sun_light.sun_position;
+    }
+}
+
+/// Sunlight parameters at a specific moment.
+///
+/// At night represents the moonlight.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SunLight {
+    /// Direction from the ground to the sun.
+    sun_position: Vec3,
+    color: Color,
+    illuminance: f32,
+}
+
+impl SunLight {
+    pub(crate) fn new(time_of_day: f32, season: Season) -> Self {
+        let (sunrise, sunset) = daylight_hours(season);
+        let day_length = sunset - sunrise;
+
+        // Rises in the east, passes the south at noon and goes under the horizon through the north.
+        let day_progress = (time_of_day - sunrise).rem_euclid(24.0);
+        let angle = if day_progress <= day_length {
+            PI * day_progress / day_length
+        } else {
+            PI + PI * (day_progress - day_length) / (24.0 - day_length)
+        };
+        let elevation = angle.sin() * max_elevation(season);
+        let sun_position = Vec3::new(
+            angle.cos() * elevation.cos(),
+            elevation.sin(),
+            angle.sin() * elevation.cos(),
+        );
+
+        const NOON_COLOR: Color = Color::linear_rgb(0.913, 0.855, 0.761);
+        const HORIZON_COLOR: Color = Color::linear_rgb(1.0, 0.55, 0.3);
+        const MOON_COLOR: Color = Color::linear_rgb(0.6, 0.7, 1.0);
+
+        let (color, illuminance) = if elevation > 0.0 {
+            let height = elevation.sin();
+            let color = HORIZON_COLOR.mix(&NOON_COLOR, height.sqrt());
+            let illuminance = NIGHT_ILLUMINANCE + (DAY_ILLUMINANCE - NIGHT_ILLUMINANCE) * height;
+            (color, illuminance)
+        } else {
+            (MOON_COLOR, NIGHT_ILLUMINANCE)
+        };
+
+        Self {
+            sun_position,
+            color,
+            illuminance,
+        }
+    }
+
+    pub(crate) fn is_night(&self) -> bool {
+        self.sun_position.y <= 0.0
+    }
+
+    /// Returns the direction in which the light travels.
+    ///
+    /// At night the light comes from the opposite side, where the moon is.
+    fn light_direction(&self) -> Vec3 {
+        if self.is_night() {
+            self.sun_position
+        } else {
+            -self.sun_position
+        }
+    }
+}
+
+/// Returns sunrise and sunset hours.
+fn daylight_hours(season: Season) -> (f32, f32) {
+    match season {
+        Season::Spring | Season::Autumn => (6.0, 20.0),
+        Season::Summer => (5.0, 21.0),
+        Season::Winter => (8.0, 17.0),
+    }
+}
+
+/// Returns the sun elevation at noon in radians.
+fn max_elevation(season: Season) -> f32 {
+    match season {
+        Season::Spring | Season::Autumn => 50_f32.to_radians(),
+        Season::Summer => 65_f32.to_radians(),
+        Season::Winter => 25_f32.to_radians(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn noon() {
+        for season in Season::iter() {
+            let (sunrise, sunset) = daylight_hours(season);
+            let noon = SunLight::new((sunrise + sunset) / 2.0, season);
+            assert!(!noon.is_night(), "sun should be up at noon in {season:?}");
+            assert!(
+                noon.sun_position.z > 0.9 * noon.sun_position.xz().length(),
+                "sun should be in the south at noon in {season:?}"
+            );
+            assert!(
+                noon.light_direction().y < 0.0,
+                "light should go down at noon in {season:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn midnight() {
+        for season in Season::iter() {
+            let midnight = SunLight::new(0.0, season);
+            assert!(
+                midnight.is_night(),
+                "should be night at midnight in {season:?}"
+            );
+            assert_eq!(midnight.illuminance, NIGHT_ILLUMINANCE);
+            assert!(
+                midnight.light_direction().y < 0.0,
+                "moonlight should go down in {season:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn sunrise_and_sunset() {
+        let morning = SunLight::new(7.0, Season::Spring);
+        let evening = SunLight::new(19.0, Season::Spring);
+        assert!(morning.sun_position.x > 0.0, "sun should rise in the east");
+        assert!(evening.sun_position.x < 0.0, "sun should set in the west");
+
+        let noon = SunLight::new(13.0, Season::Spring);
+        assert!(morning.illuminance < noon.illuminance);
+        assert!(evening.illuminance < noon.illuminance);
+    }
+
+    #[test]
+    fn seasons() {
+        let summer_noon = SunLight::new(13.0, Season::Summer);
+        let winter_noon = SunLight::new(12.5, Season::Winter);
+        assert!(summer_noon.sun_position.y > winter_noon.sun_position.y);
+        assert!(summer_noon.illuminance > winter_noon.illuminance);
+
+        assert!(!SunLight::new(20.0, Season::Summer).is_night());
+        assert!(SunLight::new(20.0, Season::Winter).is_night());
+    }
+
+    #[test]
+    fn known_positions() {
+        let sunrise = SunLight::new(6.0, Season::Autumn);
+        assert!(sunrise.sun_position.abs_diff_eq(Vec3::X, 1e-5));
+
+        let noon = SunLight::new(13.0, Season::Autumn);
+        let expected = Vec3::new(0.0, 50_f32.to_radians().sin(), 50_f32.to_radians().cos());
+        assert!(noon.sun_position.abs_diff_eq(expected, 1e-5));
+
+        let sunset = SunLight::new(20.0, Season::Autumn);
+        assert!(sunset.sun_position.abs_diff_eq(Vec3::NEG_X, 1e-5));
+    }
+
+    #[test]
+    fn continuous() {
+        const MINUTES: usize = 24 * 60;
+        for season in Season::iter() {
+            for minute in 0..MINUTES {
+                let current = SunLight::new(minute as f32 / 60.0, season);
+                let next = SunLight::new(((minute + 1) % MINUTES) as f32 / 60.0, season);
+                assert!(current.sun_position.is_normalized());
+                assert!(
+                    current.sun_position.distance(next.sun_position) < 0.01,
+                    "sun should move smoothly at minute {minute} in {season:?}"
+                );
+            }
+        }
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
     core::GameState,
     dynamic_mesh::DynamicMesh,
     game_world::{
-        city::CityMode,
+        city::{terrain::FlatFootprint, CityMode},
         commands_history::{
             CommandConfirmation, CommandId, CommandRequest, ConfirmableCommand, EntityRecorder,
             PendingCommand,
@@ -51,6 +51,7 @@ impl Plugin for RoadPlugin {
 impl RoadPlugin {
     fn init(
         trigger: Trigger<OnAdd, Road>,
+        mut commands: Commands,
         asset_server: Res<AssetServer>,
         mut meshes: ResMut<Assets<Mesh>>,
         manifests: Res<Assets<RoadManifest>>,
@@ -75,6 +76,10 @@ impl RoadPlugin {
             .unwrap_or_else(|| panic!("'{:?}' should be loaded", &**road));
 
         road_data.half_width = manifest.half_width;
+        road_data.sidewalk_width = manifest.sidewalk_width;
+        commands.entity(trigger.entity()).insert(FlatFootprint {
+            half_width: manifest.half_width,
+        });
         **mesh = meshes.add(DynamicMesh::create_empty());
         **material = asset_server.load(manifest.material.clone());
     }
@@ -189,10 +194,11 @@ impl RoadTool {
 struct Road(AssetPath<'static>);
 
 /// Stores road information needed at runtime from [`RoadManifest`].
-#[derive(Component, Reflect, Default)]
+#[derive(Component, Reflect, Default, Clone, Copy)]
 #[reflect(Component)]
-struct RoadData {
-    half_width: f32,
+pub(crate) struct RoadData {
+    pub(crate) half_width: f32,
+    pub(crate) sidewalk_width: f32,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
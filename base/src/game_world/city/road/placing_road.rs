@@ -260,7 +260,7 @@ pub struct SpawnRoadId(pub AssetId<RoadManifest>);
     Collider,
     CollisionLayers(|| CollisionLayers::new(
         Layer::PlacingRoad,
-        [Layer::Wall, Layer::PlacingWall],
+        [Layer::Wall, Layer::PlacingWall, Layer::Water],
     )),
 )]
 enum PlacingRoad {
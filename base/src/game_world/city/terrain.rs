@@ -0,0 +1,555 @@
+pub mod sculpting_brush;
+pub(super) mod splat_map;
+mod terrain_mesh;
+
+use avian3d::prelude::*;
+use bevy::{ecs::entity::MapEntities, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+use vleue_navigator::prelude::*;
+
+use super::{CityMode, CityNavMesh, Ground, CITY_SIZE, HALF_CITY_SIZE};
+use crate::{
+    core::GameState,
+    dynamic_mesh::DynamicMesh,
+    game_world::{
+        navigation::nav_tiles,
+        object::{wall_mount::WallMount, Object},
+        segment::{self, Segment},
+    },
+};
+use sculpting_brush::SculptingBrushPlugin;
+use splat_map::SplatMapPlugin;
+
+pub(super) struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((SculptingBrushPlugin, SplatMapPlugin))
+            .add_sub_state::<TerrainTool>()
+            .enable_state_scoped_entities::<TerrainTool>()
+            .register_type::<Heightmap>()
+            .replicate::<Heightmap>()
+            .add_mapped_client_event::<TerrainEdit>(ChannelKind::Ordered)
+            .add_mapped_server_event::<HeightmapPatch>(ChannelKind::Ordered)
+            .add_systems(First, clear_changes)
+            .add_systems(
+                PreUpdate,
+                (
+                    apply_edits.run_if(server_or_singleplayer),
+                    apply_patches.run_if(client_connected),
+                )
+                    .after(ClientSet::Receive),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    flatten_under_segments.run_if(server_or_singleplayer),
+                    (
+                        update_grounds,
+                        update_navmeshes,
+                        conform_segments.after(segment::update_transform),
+                        conform_objects.run_if(server_or_singleplayer),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Number of height samples along each side of a city.
+const RESOLUTION: usize = 101;
+
+/// Distance between two neighboring height samples.
+const CELL_SIZE: f32 = CITY_SIZE / (RESOLUTION - 1) as f32;
+
+/// Maximum terrain elevation in both directions.
+const MAX_HEIGHT: f32 = 50.0;
+
+/// Maximum slope tangent on which actors can walk.
+const MAX_WALKABLE_SLOPE: f32 = 1.0;
+
+/// Maximum radius of terrain brushes accepted from clients.
+const MAX_BRUSH_RADIUS: f32 = 10.0;
+
+/// Returns the brush radius clamped to [`MAX_BRUSH_RADIUS`]
+/// or [`None`] if it's not positive.
+fn validate_radius(radius: f32) -> Option<f32> {
+    (radius.is_finite() && radius > 0.0).then(|| radius.min(MAX_BRUSH_RADIUS))
+}
+
+fn apply_edits(
+    mut edit_events: EventReader<FromClient<TerrainEdit>>,
+    mut patch_events: EventWriter<ToClients<HeightmapPatch>>,
+    mut cities: Query<(&mut Heightmap, &mut HeightmapChanges)>,
+) {
+    for FromClient { client_id, event } in edit_events.read() {
+        if event.tool == TerrainTool::Paint {
+            error!("`{client_id:?}` sent painting as a terrain edit");
+            continue;
+        }
+
+        let Some(radius) = validate_radius(event.radius) else {
+            error!("`{client_id:?}` sent invalid brush radius {}", event.radius);
+            continue;
+        };
+
+        match cities.get_mut(event.city_entity) {
+            Ok((mut heightmap, mut changes)) => {
+                trace!(
+                    "`{client_id:?}` applies `{:?}` to city `{}`",
+                    event.tool,
+                    event.city_entity
+                );
+                let rect = heightmap.bypass_change_detection().apply_brush(
+                    event.tool,
+                    event.center,
+                    radius,
+                );
+                changes.mark(rect);
+                patch_events.send(ToClients {
+                    mode: SendMode::Broadcast,
+                    event: HeightmapPatch::new(event.city_entity, &heightmap, rect),
+                });
+            }
+            Err(e) => error!("unable to edit terrain `{}`: {e}", event.city_entity),
+        }
+    }
+}
+
+/// Levels terrain under segments that can't be placed on slopes, like walls or roads.
+fn flatten_under_segments(
+    mut patch_events: EventWriter<ToClients<HeightmapPatch>>,
+    mut cities: Query<(&mut Heightmap, &mut HeightmapChanges)>,
+    segments: Query<(&Parent, &Segment, &FlatFootprint), Changed<Segment>>,
+) {
+    for (parent, &segment, footprint) in &segments {
+        if segment.is_zero() {
+            continue;
+        }
+
+        let (mut heightmap, mut changes) = cities
+            .get_mut(**parent)
+            .expect("segments should always be children of a city");
+
+        let height = heightmap.height_at(segment.start);
+        debug!("flattening terrain under segment to {height}");
+        let rect = heightmap.bypass_change_detection().flatten_segment(
+            segment,
+            footprint.half_width,
+            height,
+        );
+        changes.mark(rect);
+        patch_events.send(ToClients {
+            mode: SendMode::Broadcast,
+            event: HeightmapPatch::new(**parent, &heightmap, rect),
+        });
+    }
+}
+
+/// Applies heightmap changes made by the server.
+fn apply_patches(
+    mut patch_events: EventReader<HeightmapPatch>,
+    mut cities: Query<(&mut Heightmap, &mut HeightmapChanges)>,
+) {
+    for patch in patch_events.read() {
+        match cities.get_mut(patch.city_entity) {
+            Ok((mut heightmap, mut changes)) => {
+                trace!("applying heightmap patch to city `{}`", patch.city_entity);
+                heightmap.apply_patch(patch);
+                changes.mark(patch.rect);
+            }
+            Err(e) => error!("unable to patch terrain `{}`: {e}", patch.city_entity),
+        }
+    }
+}
+
+fn clear_changes(mut cities: Query<&mut HeightmapChanges>) {
+    for mut changes in &mut cities {
+        changes.bypass_change_detection().0 = None;
+    }
+}
+
+fn update_grounds(
+    mut meshes: ResMut<Assets<Mesh>>,
+    cities: Query<(&Heightmap, &Children), Changed<HeightmapChanges>>,
+    mut grounds: Query<(&Mesh3d, &mut Collider), With<Ground>>,
+) {
+    for (heightmap, children) in &cities {
+        let mut iter = grounds.iter_many_mut(children);
+        let (mesh_handle, mut collider) = iter
+            .fetch_next()
+            .expect("cities should always have a ground");
+
+        let mesh = meshes
+            .get_mut(mesh_handle)
+            .expect("ground handles should be valid");
+
+        trace!("regenerating ground mesh");
+        let mut dyn_mesh = DynamicMesh::take(mesh);
+        terrain_mesh::generate(&mut dyn_mesh, heightmap);
+        dyn_mesh.apply(mesh);
+
+        *collider = terrain_mesh::generate_collider(heightmap);
+    }
+}
+
+/// Excludes slopes that are too steep to walk from city navmeshes.
+///
+/// Only tiles around the changed region are rebuilt.
+fn update_navmeshes(
+    cities: Query<(&Heightmap, &HeightmapChanges, &CityNavMesh), Changed<HeightmapChanges>>,
+    mut navmeshes: Query<(&mut NavMeshSettings, &mut NavMeshUpdateMode)>,
+) {
+    for (heightmap, changes, tile_entities) in &cities {
+        // Slopes of cells next to the changed samples change too.
+        let area = changes.area();
+        let first = nav_tiles::tile_of(area.min - CELL_SIZE);
+        let last = nav_tiles::tile_of(area.max + CELL_SIZE);
+
+        debug!("updating navmesh triangulations from heightmap for tiles from {first} to {last}");
+        for z in first.y..=last.y {
+            for x in first.x..=last.x {
+                let tile = UVec2::new(x, z);
+                let (mut settings, mut update_mode) = navmeshes
+                    .get_mut(tile_entities[nav_tiles::tile_index(tile)])
+                    .expect("city navmesh tiles should always be valid");
+
+                let mut triangulation = nav_tiles::tile_triangulation(tile);
+                triangulation.add_obstacles(
+                    heightmap.steep_cells(MAX_WALKABLE_SLOPE, nav_tiles::tile_rect(tile)),
+                );
+
+                settings.fixed = triangulation;
+                *update_mode = NavMeshUpdateMode::OnDemand(true);
+            }
+        }
+    }
+}
+
+/// Places segments with [`FlatFootprint`] on top of the terrain.
+fn conform_segments(
+    cities: Query<(&Heightmap, Ref<HeightmapChanges>, &Children)>,
+    mut segments: Query<(Ref<Segment>, &mut Transform), With<FlatFootprint>>,
+) {
+    for (heightmap, changes, children) in &cities {
+        let mut iter = segments.iter_many_mut(children);
+        while let Some((segment, mut transform)) = iter.fetch_next() {
+            if changes.is_changed() || segment.is_changed() {
+                transform.translation.y = heightmap.height_at(segment.start);
+            }
+        }
+    }
+}
+
+/// Keeps objects on the ground when the terrain under them changes.
+///
+/// Wall-mounted objects are positioned by their walls.
+fn conform_objects(
+    cities: Query<(&Heightmap, &Children), Changed<HeightmapChanges>>,
+    mut objects: Query<&mut Transform, (With<Object>, Without<WallMount>)>,
+) {
+    for (heightmap, children) in &cities {
+        let mut iter = objects.iter_many_mut(children);
+        while let Some(mut transform) = iter.fetch_next() {
+            let height = heightmap.height_at(transform.translation.xz());
+            if transform.translation.y != height {
+                transform.translation.y = height;
+            }
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Component,
+    Copy,
+    Debug,
+    Default,
+    EnumIter,
+    Eq,
+    Hash,
+    PartialEq,
+    SubStates,
+    Deserialize,
+    Serialize,
+)]
+#[source(CityMode = CityMode::Terrain)]
+pub enum TerrainTool {
+    #[default]
+    Raise,
+    Lower,
+    Flatten,
+    Smooth,
+    Paint,
+}
+
+impl TerrainTool {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Raise => "⬆",
+            Self::Lower => "⬇",
+            Self::Flatten => "➖",
+            Self::Smooth => "〰",
+            Self::Paint => "🖌",
+        }
+    }
+}
+
+/// Terrain elevation of a city.
+///
+/// Stores a square grid of height samples that covers the whole city.
+///
+/// Replicated only on insertion. Later edits bypass change detection and are sent
+/// as [`HeightmapPatch`] to avoid replicating the whole grid. Use [`HeightmapChanges`]
+/// to react to changes.
+#[derive(Component, Clone, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[require(HeightmapChanges)]
+pub(crate) struct Heightmap(Vec<f32>);
+
+impl Heightmap {
+    fn get(&self, x: usize, z: usize) -> f32 {
+        self.0[z * RESOLUTION + x]
+    }
+
+    fn get_mut(&mut self, x: usize, z: usize) -> &mut f32 {
+        &mut self.0[z * RESOLUTION + x]
+    }
+
+    /// Returns city position of a sample.
+    fn sample_point(x: usize, z: usize) -> Vec2 {
+        Vec2::new(x as f32, z as f32) * CELL_SIZE - HALF_CITY_SIZE
+    }
+
+    /// Returns fractional grid coordinates for a city position.
+    ///
+    /// Positions outside the city are clamped to its border.
+    fn grid_point(point: Vec2) -> Vec2 {
+        ((point + HALF_CITY_SIZE) / CELL_SIZE)
+            .clamp(Vec2::ZERO, Vec2::splat((RESOLUTION - 1) as f32))
+    }
+
+    /// Returns grid rectangle that covers the city rectangle.
+    ///
+    /// Both corners are inclusive.
+    fn grid_rect(min: Vec2, max: Vec2) -> URect {
+        URect::from_corners(
+            Self::grid_point(min).floor().as_uvec2(),
+            Self::grid_point(max).ceil().as_uvec2(),
+        )
+    }
+
+    /// Returns indices of samples inside the grid rectangle.
+    fn rect_samples(rect: URect) -> impl Iterator<Item = (usize, usize)> {
+        (rect.min.y as usize..=rect.max.y as usize)
+            .flat_map(move |z| (rect.min.x as usize..=rect.max.x as usize).map(move |x| (x, z)))
+    }
+
+    /// Returns interpolated height at a city position.
+    pub(crate) fn height_at(&self, point: Vec2) -> f32 {
+        let grid_point = Self::grid_point(point);
+        let x = (grid_point.x as usize).min(RESOLUTION - 2);
+        let z = (grid_point.y as usize).min(RESOLUTION - 2);
+        let fraction = grid_point - Vec2::new(x as f32, z as f32);
+
+        let top = self.get(x, z) + (self.get(x + 1, z) - self.get(x, z)) * fraction.x;
+        let bottom =
+            self.get(x, z + 1) + (self.get(x + 1, z + 1) - self.get(x, z + 1)) * fraction.x;
+
+        top + (bottom - top) * fraction.y
+    }
+
+    /// Returns surface normal at a sample using central differences.
+    fn normal(&self, x: usize, z: usize) -> Vec3 {
+        let left = self.get(x.saturating_sub(1), z);
+        let right = self.get((x + 1).min(RESOLUTION - 1), z);
+        let up = self.get(x, z.saturating_sub(1));
+        let down = self.get(x, (z + 1).min(RESOLUTION - 1));
+
+        Vec3::new(left - right, 2.0 * CELL_SIZE, up - down).normalize()
+    }
+
+    /// Modifies heights around `center` according to the tool.
+    ///
+    /// The effect fades out towards the `radius`.
+    /// Returns the grid rectangle of affected samples.
+    fn apply_brush(&mut self, tool: TerrainTool, center: Vec2, radius: f32) -> URect {
+        const STRENGTH: f32 = 0.5;
+
+        let target = self.height_at(center);
+        let original = self.clone();
+        let rect = Self::grid_rect(center - radius, center + radius);
+        for (x, z) in Self::rect_samples(rect) {
+            let distance = Self::sample_point(x, z).distance(center);
+            if distance > radius {
+                continue;
+            }
+
+            let falloff = (1.0 - (distance / radius).powi(2)).powi(2);
+            let height = self.get_mut(x, z);
+            match tool {
+                TerrainTool::Raise => *height += STRENGTH * falloff,
+                TerrainTool::Lower => *height -= STRENGTH * falloff,
+                TerrainTool::Flatten => *height += (target - *height) * falloff,
+                TerrainTool::Smooth => {
+                    let average = (original.get(x.saturating_sub(1), z)
+                        + original.get((x + 1).min(RESOLUTION - 1), z)
+                        + original.get(x, z.saturating_sub(1))
+                        + original.get(x, (z + 1).min(RESOLUTION - 1)))
+                        / 4.0;
+                    *height += (average - *height) * falloff;
+                }
+                TerrainTool::Paint => unreachable!("painting should be sent as `GroundPaint`"),
+            }
+            *height = height.clamp(-MAX_HEIGHT, MAX_HEIGHT);
+        }
+
+        rect
+    }
+
+    /// Sets all heights under the segment to the given value.
+    ///
+    /// Returns the grid rectangle of affected samples.
+    fn flatten_segment(&mut self, segment: Segment, half_width: f32, height: f32) -> URect {
+        // Include neighbor samples to avoid slopes cutting into the footprint.
+        let reach = half_width + CELL_SIZE;
+        let min = segment.start.min(segment.end) - reach;
+        let max = segment.start.max(segment.end) + reach;
+        let rect = Self::grid_rect(min, max);
+        for (x, z) in Self::rect_samples(rect) {
+            let point = Self::sample_point(x, z);
+            if segment.closest_point(point).distance(point) <= reach {
+                *self.get_mut(x, z) = height;
+            }
+        }
+
+        rect
+    }
+
+    fn apply_patch(&mut self, patch: &HeightmapPatch) {
+        for ((x, z), &height) in Self::rect_samples(patch.rect).zip(&patch.heights) {
+            *self.get_mut(x, z) = height;
+        }
+    }
+
+    /// Returns outlines of cells inside the area whose slope is steeper than `max_slope`.
+    fn steep_cells(&self, max_slope: f32, area: Rect) -> impl Iterator<Item = Vec<Vec2>> + '_ {
+        let rect = Self::grid_rect(area.min, area.max);
+        (rect.min.y as usize..rect.max.y as usize)
+            .flat_map(move |z| (rect.min.x as usize..rect.max.x as usize).map(move |x| (x, z)))
+            .filter(move |&(x, z)| {
+                let heights = [
+                    self.get(x, z),
+                    self.get(x + 1, z),
+                    self.get(x + 1, z + 1),
+                    self.get(x, z + 1),
+                ];
+                let min = heights.into_iter().fold(f32::INFINITY, f32::min);
+                let max = heights.into_iter().fold(f32::NEG_INFINITY, f32::max);
+                (max - min) / CELL_SIZE > max_slope
+            })
+            .map(|(x, z)| {
+                vec![
+                    Self::sample_point(x, z),
+                    Self::sample_point(x + 1, z),
+                    Self::sample_point(x + 1, z + 1),
+                    Self::sample_point(x, z + 1),
+                ]
+            })
+    }
+}
+
+impl Default for Heightmap {
+    fn default() -> Self {
+        Self(vec![0.0; RESOLUTION * RESOLUTION])
+    }
+}
+
+/// Local tracker of [`Heightmap`] changes.
+///
+/// Not replicated, so systems can filter by its changes on both server and clients.
+/// Stores the grid rectangle changed during the current frame, the whole grid on insertion.
+#[derive(Component)]
+pub(crate) struct HeightmapChanges(Option<URect>);
+
+impl HeightmapChanges {
+    const WHOLE_GRID: URect = URect {
+        min: UVec2::ZERO,
+        max: UVec2::splat(RESOLUTION as u32 - 1),
+    };
+
+    fn mark(&mut self, rect: URect) {
+        self.0 = Some(self.0.map_or(rect, |changed| changed.union(rect)));
+    }
+
+    /// Returns the changed city area.
+    ///
+    /// Covers the whole city if nothing was marked during the current frame.
+    fn area(&self) -> Rect {
+        let rect = self.0.unwrap_or(Self::WHOLE_GRID);
+        Rect::from_corners(
+            Heightmap::sample_point(rect.min.x as usize, rect.min.y as usize),
+            Heightmap::sample_point(rect.max.x as usize, rect.max.y as usize),
+        )
+    }
+}
+
+impl Default for HeightmapChanges {
+    fn default() -> Self {
+        Self(Some(Self::WHOLE_GRID))
+    }
+}
+
+/// Flattens the terrain under a segment and places the segment on top of it.
+#[derive(Component, Clone, Copy)]
+pub(crate) struct FlatFootprint {
+    pub(crate) half_width: f32,
+}
+
+/// An event of sculpting the terrain with a brush.
+///
+/// Emitted by players.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub(crate) struct TerrainEdit {
+    pub(crate) city_entity: Entity,
+    pub(crate) tool: TerrainTool,
+    pub(crate) center: Vec2,
+    pub(crate) radius: f32,
+}
+
+impl MapEntities for TerrainEdit {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.city_entity = entity_mapper.map_entity(self.city_entity);
+    }
+}
+
+/// Heights of a changed [`Heightmap`] region.
+///
+/// Emitted by server on terrain edits.
+#[derive(Deserialize, Event, Serialize)]
+struct HeightmapPatch {
+    city_entity: Entity,
+    rect: URect,
+    heights: Vec<f32>,
+}
+
+impl HeightmapPatch {
+    fn new(city_entity: Entity, heightmap: &Heightmap, rect: URect) -> Self {
+        Self {
+            city_entity,
+            rect,
+            heights: Heightmap::rect_samples(rect)
+                .map(|(x, z)| heightmap.get(x, z))
+                .collect(),
+        }
+    }
+}
+
+impl MapEntities for HeightmapPatch {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.city_entity = entity_mapper.map_entity(self.city_entity);
+    }
+}
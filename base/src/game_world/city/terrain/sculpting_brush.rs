@@ -0,0 +1,117 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{color::palettes::css::WHITE, prelude::*};
+use bevy_enhanced_input::prelude::*;
+
+use super::{splat_map::GroundPaint, TerrainEdit, TerrainTool};
+use crate::{
+    asset::manifest::ground_manifest::GroundManifest,
+    game_world::{
+        city::{ActiveCity, CityMode},
+        player_camera::CameraCaster,
+    },
+};
+
+pub(super) struct SculptingBrushPlugin;
+
+impl Plugin for SculptingBrushPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_input_context::<SculptingBrush>()
+            .add_observer(sculpt.never_param_warn())
+            .add_systems(OnEnter(CityMode::Terrain), spawn)
+            .add_systems(
+                Update,
+                draw.never_param_warn().run_if(in_state(CityMode::Terrain)),
+            );
+    }
+}
+
+/// Brush radius in meters.
+const BRUSH_RADIUS: f32 = 3.0;
+
+fn spawn(mut commands: Commands) {
+    debug!("spawning sculpting brush");
+    commands.spawn(SculptingBrush);
+}
+
+fn sculpt(
+    _trigger: Trigger<Fired<Sculpt>>,
+    camera_caster: CameraCaster,
+    asset_server: Res<AssetServer>,
+    tool: Res<State<TerrainTool>>,
+    paint_id: Option<Res<PaintGroundId>>,
+    mut edit_events: EventWriter<TerrainEdit>,
+    mut paint_events: EventWriter<GroundPaint>,
+    city_entity: Single<Entity, With<ActiveCity>>,
+) {
+    let Some(point) = camera_caster.intersect_ground() else {
+        return;
+    };
+
+    if **tool == TerrainTool::Paint {
+        let Some(paint_id) = paint_id else {
+            return;
+        };
+        let ground = asset_server
+            .get_path(paint_id.0)
+            .expect("ground manifests should be loaded from files");
+
+        debug!("painting '{ground}' at {point}");
+        paint_events.send(GroundPaint {
+            city_entity: *city_entity,
+            ground: ground.into_owned(),
+            center: point.xz(),
+            radius: BRUSH_RADIUS,
+        });
+        return;
+    }
+
+    debug!("sculpting terrain with `{:?}` at {point}", **tool);
+    edit_events.send(TerrainEdit {
+        city_entity: *city_entity,
+        tool: **tool,
+        center: point.xz(),
+        radius: BRUSH_RADIUS,
+    });
+}
+
+fn draw(
+    mut gizmos: Gizmos,
+    camera_caster: CameraCaster,
+    city_transform: Single<&GlobalTransform, With<ActiveCity>>,
+) {
+    let Some(point) = camera_caster.intersect_ground() else {
+        return;
+    };
+
+    let isometry = Isometry3d::new(
+        city_transform.transform_point(point),
+        Quat::from_rotation_x(FRAC_PI_2),
+    );
+    gizmos.circle(isometry, BRUSH_RADIUS, WHITE);
+}
+
+/// Ground selected for painting.
+#[derive(Resource)]
+pub struct PaintGroundId(pub AssetId<GroundManifest>);
+
+#[derive(Component)]
+#[require(StateScoped<CityMode>(|| StateScoped(CityMode::Terrain)))]
+struct SculptingBrush;
+
+impl InputContext for SculptingBrush {
+    fn context_instance(_world: &World, _entity: Entity) -> ContextInstance {
+        let mut ctx = ContextInstance::default();
+
+        // Repeat edits while the button is held.
+        ctx.bind::<Sculpt>()
+            .to((MouseButton::Left, GamepadButton::South))
+            .with_conditions(Pulse::new(0.1));
+
+        ctx
+    }
+}
+
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct Sculpt;
@@ -0,0 +1,499 @@
+use bevy::{asset::AssetPath, ecs::entity::MapEntities, pbr::NotShadowCaster, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Heightmap, HeightmapChanges, RESOLUTION};
+use crate::{
+    asset::manifest::ground_manifest::GroundManifest,
+    core::GameState,
+    game_world::city::{weather::Weather, Ground},
+};
+
+pub(super) struct SplatMapPlugin;
+
+impl Plugin for SplatMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SplatMap>()
+            .replicate::<SplatMap>()
+            .add_mapped_client_event::<GroundPaint>(ChannelKind::Ordered)
+            .add_mapped_server_event::<SplatMapPatch>(ChannelKind::Ordered)
+            .add_systems(
+                PreUpdate,
+                (
+                    apply_paints.run_if(server_or_singleplayer),
+                    apply_patches.run_if(client_connected),
+                )
+                    .after(ClientSet::Receive),
+            )
+            .add_systems(
+                PostUpdate,
+                (update_layers.after(super::update_grounds), update_materials)
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Number of weights in a single layer.
+const SAMPLES: usize = RESOLUTION * RESOLUTION;
+
+/// Ground that covers new cities.
+const DEFAULT_GROUND: &str = "base/ground/spring_grass/spring_grass.ground.ron";
+
+fn apply_paints(
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<GroundManifest>>,
+    mut paint_events: EventReader<FromClient<GroundPaint>>,
+    mut patch_events: EventWriter<ToClients<SplatMapPatch>>,
+    mut cities: Query<(&mut SplatMap, &mut SplatMapRevision)>,
+) {
+    for FromClient { client_id, event } in paint_events.read() {
+        let Some(radius) = super::validate_radius(event.radius) else {
+            error!("`{client_id:?}` sent invalid brush radius {}", event.radius);
+            continue;
+        };
+
+        if !asset_server
+            .get_handle::<GroundManifest>(&event.ground)
+            .is_some_and(|handle| manifests.contains(&handle))
+        {
+            error!("`{client_id:?}` sent unknown ground '{}'", event.ground);
+            continue;
+        }
+
+        match cities.get_mut(event.city_entity) {
+            Ok((mut splat_map, mut revision)) => {
+                trace!(
+                    "`{client_id:?}` paints '{}' in city `{}`",
+                    event.ground,
+                    event.city_entity
+                );
+                let Some(rect) = splat_map.bypass_change_detection().paint(
+                    event.ground.clone(),
+                    event.center,
+                    radius,
+                ) else {
+                    continue;
+                };
+                **revision += 1;
+                patch_events.send(ToClients {
+                    mode: SendMode::Broadcast,
+                    event: SplatMapPatch::new(event.city_entity, &splat_map, rect),
+                });
+            }
+            Err(e) => error!("unable to paint ground `{}`: {e}", event.city_entity),
+        }
+    }
+}
+
+/// Applies splat map changes made by the server.
+fn apply_patches(
+    mut patch_events: EventReader<SplatMapPatch>,
+    mut cities: Query<(&mut SplatMap, &mut SplatMapRevision)>,
+) {
+    for patch in patch_events.read() {
+        match cities.get_mut(patch.city_entity) {
+            Ok((mut splat_map, mut revision)) => {
+                trace!("applying splat map patch to city `{}`", patch.city_entity);
+                splat_map.apply_patch(patch);
+                **revision += 1;
+            }
+            Err(e) => error!("unable to patch ground `{}`: {e}", patch.city_entity),
+        }
+    }
+}
+
+/// Assigns the first layer material to the ground and blends other layers on top of it.
+///
+/// Each additional layer is a copy of the ground mesh with weights stored in vertex alpha.
+/// Vertex colors of all layers are tinted by the current [`Weather`].
+fn update_layers(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    manifests: Res<Assets<GroundManifest>>,
+    cities: Query<
+        (&SplatMap, &Weather, &Children),
+        Or<(
+            Changed<SplatMapRevision>,
+            Changed<HeightmapChanges>,
+            Changed<Weather>,
+        )>,
+    >,
+    mut grounds: Query<
+        (
+            Entity,
+            &Mesh3d,
+            &mut MeshMaterial3d<StandardMaterial>,
+            Option<&Children>,
+        ),
+        (With<Ground>, Without<GroundLayer>),
+    >,
+    mut layers: Query<(
+        &mut GroundLayer,
+        &Mesh3d,
+        &mut MeshMaterial3d<StandardMaterial>,
+    )>,
+) {
+    for (splat_map, weather, children) in &cities {
+        let mut iter = grounds.iter_many_mut(children);
+        let (ground_entity, ground_mesh, mut ground_material, ground_children) = iter
+            .fetch_next()
+            .expect("cities should always have a ground");
+
+        if let Some(material) = load_material(&asset_server, &manifests, &splat_map.layers[0]) {
+            if ground_material.0 != material {
+                debug!("changing base ground to '{}'", splat_map.layers[0]);
+                ground_material.0 = material;
+            }
+        }
+
+        let tint = weather.ground_tint();
+        let ground_mesh = meshes
+            .get_mut(ground_mesh)
+            .expect("ground handles should be valid");
+        ground_mesh.insert_attribute(
+            Mesh::ATTRIBUTE_COLOR,
+            vec![tint.to_f32_array(); ground_mesh.count_vertices()],
+        );
+        let ground_mesh = ground_mesh.clone();
+
+        let mut layer_entities = ground_children
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&entity| layers.contains(entity))
+            .collect::<Vec<_>>()
+            .into_iter();
+        for (index, path) in splat_map.layers.iter().enumerate().skip(1) {
+            let mut mesh = ground_mesh.clone();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, splat_map.blend_colors(index, tint));
+
+            let Some(layer_entity) = layer_entities.next() else {
+                debug!("spawning ground layer for '{path}'");
+                let material = load_material(&asset_server, &manifests, path).unwrap_or_default();
+                commands.entity(ground_entity).with_child((
+                    GroundLayer {
+                        index,
+                        ground: path.clone(),
+                    },
+                    Mesh3d(meshes.add(mesh)),
+                    MeshMaterial3d(material),
+                ));
+                continue;
+            };
+
+            let (mut layer, mesh_handle, mut material) = layers.get_mut(layer_entity).unwrap();
+            if layer.ground != *path {
+                debug!("changing ground layer {index} to '{path}'");
+                layer.ground = path.clone();
+                material.0 = load_material(&asset_server, &manifests, path).unwrap_or_default();
+            }
+            layer.index = index;
+
+            *meshes
+                .get_mut(mesh_handle)
+                .expect("ground layer handles should be valid") = mesh;
+        }
+
+        for layer_entity in layer_entities {
+            debug!("removing unused ground layer");
+            commands.entity(layer_entity).despawn();
+        }
+    }
+}
+
+/// Makes layer materials transparent to blend them with the layers below.
+fn update_materials(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut layers: Query<(&GroundLayer, &mut MeshMaterial3d<StandardMaterial>)>,
+) {
+    for (layer, mut material_handle) in &mut layers {
+        let Some(material) = materials.get(&*material_handle) else {
+            continue;
+        };
+
+        // If mode matches, assume that we don't need any update.
+        if material.alpha_mode == AlphaMode::Blend {
+            continue;
+        }
+
+        let mut material = material.clone();
+        material.alpha_mode = AlphaMode::Blend;
+        material.depth_bias = layer.index as f32;
+        *material_handle = materials.add(material).into();
+    }
+}
+
+fn load_material(
+    asset_server: &AssetServer,
+    manifests: &Assets<GroundManifest>,
+    path: &AssetPath<'static>,
+) -> Option<Handle<StandardMaterial>> {
+    let Some(manifest_handle) = asset_server.get_handle(path) else {
+        error!("'{path}' is missing, ignoring");
+        return None;
+    };
+
+    let manifest = manifests
+        .get(&manifest_handle)
+        .unwrap_or_else(|| panic!("'{path:?}' should be loaded"));
+
+    Some(asset_server.load(manifest.material.clone()))
+}
+
+/// Blend weights of ground materials for each [`Heightmap`] sample.
+///
+/// Replicated only on insertion. Later paints bypass change detection and are sent
+/// as [`SplatMapPatch`]. Use [`SplatMapRevision`] to react to changes.
+#[derive(Component, Clone, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[require(SplatMapRevision)]
+pub(crate) struct SplatMap {
+    /// Ground manifest paths.
+    ///
+    /// The first layer is opaque, others are blended on top of it.
+    layers: Vec<AssetPath<'static>>,
+
+    /// Weights of all samples, layer by layer.
+    ///
+    /// Most weights are either 0 or 255, so they are compressed for sending.
+    #[serde(with = "run_length")]
+    weights: Vec<u8>,
+}
+
+impl SplatMap {
+    /// Maximum number of grounds that a single city can use.
+    const MAX_LAYERS: usize = 8;
+
+    fn layer_weights(&self, layer: usize) -> &[u8] {
+        &self.weights[layer * SAMPLES..(layer + 1) * SAMPLES]
+    }
+
+    /// Increases weight of the ground around `center` and reduces weights of other grounds.
+    ///
+    /// The effect fades out towards the `radius`.
+    /// Returns the grid rectangle of affected samples or [`None`] if the ground can't be added.
+    fn paint(&mut self, ground: AssetPath<'static>, center: Vec2, radius: f32) -> Option<URect> {
+        const STRENGTH: f32 = 0.3;
+
+        let layer = match self.layers.iter().position(|path| *path == ground) {
+            Some(layer) => layer,
+            None => {
+                if self.layers.len() >= Self::MAX_LAYERS {
+                    error!(
+                        "unable to paint '{ground}', city can't have more then {} grounds",
+                        Self::MAX_LAYERS
+                    );
+                    return None;
+                }
+
+                self.layers.push(ground);
+                self.weights.resize(self.weights.len() + SAMPLES, 0);
+                self.layers.len() - 1
+            }
+        };
+
+        let rect = Heightmap::grid_rect(center - radius, center + radius);
+        for (x, z) in Heightmap::rect_samples(rect) {
+            let distance = Heightmap::sample_point(x, z).distance(center);
+            if distance > radius {
+                continue;
+            }
+
+            let falloff = (1.0 - (distance / radius).powi(2)).powi(2);
+            let sample = z * RESOLUTION + x;
+            for index in 0..self.layers.len() {
+                let weight = &mut self.weights[index * SAMPLES + sample];
+                let value = *weight as f32;
+                // Round towards the target to let weights reach it.
+                *weight = if index == layer {
+                    (value + (u8::MAX as f32 - value) * STRENGTH * falloff).ceil() as u8
+                } else {
+                    (value - value * STRENGTH * falloff).floor() as u8
+                };
+            }
+        }
+
+        self.remove_unused();
+
+        Some(rect)
+    }
+
+    /// Replaces layers and weights inside the patch rectangle.
+    ///
+    /// Painting may add or remove layers, so weights outside the rectangle are matched by ground.
+    /// Added layers have zero weights outside the rectangle and removed layers had zero weights.
+    fn apply_patch(&mut self, patch: &SplatMapPatch) {
+        let mut weights = vec![0; patch.layers.len() * SAMPLES];
+        for (layer, path) in patch.layers.iter().enumerate() {
+            if let Some(old_layer) = self.layers.iter().position(|old_path| old_path == path) {
+                weights[layer * SAMPLES..(layer + 1) * SAMPLES]
+                    .copy_from_slice(self.layer_weights(old_layer));
+            }
+        }
+
+        let samples_count = Heightmap::rect_samples(patch.rect).count();
+        for (layer, layer_weights) in patch.weights.chunks(samples_count).enumerate() {
+            for ((x, z), &weight) in Heightmap::rect_samples(patch.rect).zip(layer_weights) {
+                weights[layer * SAMPLES + z * RESOLUTION + x] = weight;
+            }
+        }
+
+        self.layers = patch.layers.clone();
+        self.weights = weights;
+    }
+
+    /// Removes layers that are no longer visible.
+    fn remove_unused(&mut self) {
+        let mut layer = 0;
+        while layer < self.layers.len() {
+            if self.layers.len() > 1 && self.layer_weights(layer).iter().all(|&weight| weight == 0)
+            {
+                debug!("removing unused ground '{}'", self.layers[layer]);
+                self.layers.remove(layer);
+                self.weights.drain(layer * SAMPLES..(layer + 1) * SAMPLES);
+            } else {
+                layer += 1;
+            }
+        }
+    }
+
+    /// Returns vertex colors for blending the layer over all previous layers.
+    ///
+    /// Alpha is normalized by the sum of weights up to this layer,
+    /// so drawing layers in order results in a weighted average.
+    /// Color channels are set to the `tint`.
+    fn blend_colors(&self, layer: usize, tint: LinearRgba) -> Vec<[f32; 4]> {
+        (0..SAMPLES)
+            .map(|sample| {
+                let weight = self.weights[layer * SAMPLES + sample] as f32;
+                let total: f32 = (0..=layer)
+                    .map(|index| self.weights[index * SAMPLES + sample] as f32)
+                    .sum();
+                let alpha = if total > 0.0 { weight / total } else { 0.0 };
+
+                [tint.red, tint.green, tint.blue, alpha]
+            })
+            .collect()
+    }
+}
+
+impl Default for SplatMap {
+    fn default() -> Self {
+        Self {
+            layers: vec![DEFAULT_GROUND.into()],
+            weights: vec![u8::MAX; SAMPLES],
+        }
+    }
+}
+
+/// Serializes bytes as pairs of value and its repeat count.
+mod run_length {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{SplatMap, SAMPLES};
+
+    pub(super) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut runs: Vec<(u8, u32)> = Vec::new();
+        for &byte in bytes {
+            match runs.last_mut() {
+                Some((value, count)) if *value == byte => *count += 1,
+                _ => runs.push((byte, 1)),
+            }
+        }
+
+        runs.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let runs = Vec::<(u8, u32)>::deserialize(deserializer)?;
+        let mut bytes = Vec::new();
+        for (value, count) in runs {
+            let len = bytes.len() + count as usize;
+            if len > SplatMap::MAX_LAYERS * SAMPLES {
+                return Err(D::Error::custom(
+                    "weights exceed the maximum number of layers",
+                ));
+            }
+            bytes.resize(len, value);
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// Local counter of [`SplatMap`] changes.
+///
+/// Not replicated, so systems can filter by its changes on both server and clients.
+#[derive(Component, Default, Deref, DerefMut)]
+struct SplatMapRevision(u32);
+
+/// Blended layer of the ground.
+///
+/// Spawned as a child of [`Ground`] for every [`SplatMap`] layer except the first.
+#[derive(Component)]
+#[require(
+    Name(|| Name::new("Ground layer")),
+    Mesh3d,
+    MeshMaterial3d<StandardMaterial>,
+    NotShadowCaster,
+)]
+struct GroundLayer {
+    index: usize,
+    ground: AssetPath<'static>,
+}
+
+/// An event of painting a ground with a brush.
+///
+/// Emitted by players.
+#[derive(Clone, Deserialize, Event, Serialize)]
+pub(crate) struct GroundPaint {
+    pub(crate) city_entity: Entity,
+    pub(crate) ground: AssetPath<'static>,
+    pub(crate) center: Vec2,
+    pub(crate) radius: f32,
+}
+
+impl MapEntities for GroundPaint {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.city_entity = entity_mapper.map_entity(self.city_entity);
+    }
+}
+
+/// Layers and weights of a changed [`SplatMap`] region.
+///
+/// Emitted by server on ground paints.
+#[derive(Deserialize, Event, Serialize)]
+struct SplatMapPatch {
+    city_entity: Entity,
+    layers: Vec<AssetPath<'static>>,
+    rect: URect,
+
+    /// Weights inside the rectangle, layer by layer.
+    weights: Vec<u8>,
+}
+
+impl SplatMapPatch {
+    fn new(city_entity: Entity, splat_map: &SplatMap, rect: URect) -> Self {
+        Self {
+            city_entity,
+            layers: splat_map.layers.clone(),
+            rect,
+            weights: (0..splat_map.layers.len())
+                .flat_map(|layer| {
+                    Heightmap::rect_samples(rect)
+                        .map(move |(x, z)| splat_map.layer_weights(layer)[z * RESOLUTION + x])
+                })
+                .collect(),
+        }
+    }
+}
+
+impl MapEntities for SplatMapPatch {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.city_entity = entity_mapper.map_entity(self.city_entity);
+    }
+}
@@ -0,0 +1,44 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use super::{Heightmap, CITY_SIZE, HALF_CITY_SIZE, RESOLUTION};
+use crate::dynamic_mesh::DynamicMesh;
+
+pub(super) fn generate(mesh: &mut DynamicMesh, heightmap: &Heightmap) {
+    mesh.clear();
+
+    for z in 0..RESOLUTION {
+        for x in 0..RESOLUTION {
+            let point = Heightmap::sample_point(x, z);
+            mesh.positions.push([point.x, heightmap.get(x, z), point.y]);
+            mesh.normals.push(heightmap.normal(x, z).into());
+
+            // Tile the texture every meter.
+            mesh.uvs
+                .push([point.x + HALF_CITY_SIZE, point.y + HALF_CITY_SIZE]);
+        }
+    }
+
+    let row = RESOLUTION as u32;
+    for z in 0..row - 1 {
+        for x in 0..row - 1 {
+            let index = z * row + x;
+            mesh.indices.push(index);
+            mesh.indices.push(index + row);
+            mesh.indices.push(index + 1);
+
+            mesh.indices.push(index + 1);
+            mesh.indices.push(index + row);
+            mesh.indices.push(index + row + 1);
+        }
+    }
+}
+
+pub(super) fn generate_collider(heightmap: &Heightmap) -> Collider {
+    // Heightfield rows go along X axis.
+    let heights = (0..RESOLUTION)
+        .map(|x| (0..RESOLUTION).map(|z| heightmap.get(x, z)).collect())
+        .collect();
+
+    Collider::heightfield(heights, Vec3::new(CITY_SIZE, 1.0, CITY_SIZE))
+}
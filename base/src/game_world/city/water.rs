@@ -0,0 +1,298 @@
+pub mod placing_water;
+mod water_mesh;
+
+use avian3d::prelude::*;
+use bevy::{ecs::entity::MapEntities, pbr::NotShadowCaster, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use super::{
+    terrain::{Heightmap, HeightmapChanges},
+    CityMode,
+};
+use crate::{
+    core::GameState,
+    dynamic_mesh::DynamicMesh,
+    game_world::{
+        commands_history::{
+            CommandConfirmation, CommandId, CommandRequest, ConfirmableCommand, EntityRecorder,
+            PendingCommand,
+        },
+        navigation::Obstacle,
+        Layer,
+    },
+};
+use placing_water::PlacingWaterPlugin;
+
+pub(super) struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(PlacingWaterPlugin)
+            .add_sub_state::<WaterTool>()
+            .enable_state_scoped_entities::<WaterTool>()
+            .init_resource::<WaterMaterial>()
+            .register_type::<Water>()
+            .replicate::<Water>()
+            .add_mapped_client_event::<CommandRequest<WaterCommand>>(ChannelKind::Unordered)
+            .add_observer(init)
+            .add_systems(
+                PostUpdate,
+                (
+                    apply_command
+                        .run_if(server_or_singleplayer)
+                        .before(ServerSet::StoreHierarchy),
+                    update_meshes,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Minimum number of points in a water outline.
+const MIN_POINTS: usize = 3;
+
+fn init(
+    trigger: Trigger<OnAdd, Water>,
+    water_material: Res<WaterMaterial>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut waters: Query<(&mut Mesh3d, &mut MeshMaterial3d<StandardMaterial>)>,
+) {
+    debug!("initializing water `{}`", trigger.entity());
+    let (mut mesh, mut material) = waters.get_mut(trigger.entity()).unwrap();
+    **mesh = meshes.add(DynamicMesh::create_empty());
+    *material = water_material.0.clone();
+}
+
+/// Regenerates water surfaces on outline or terrain changes.
+fn update_meshes(
+    mut meshes: ResMut<Assets<Mesh>>,
+    cities: Query<(&Heightmap, Ref<HeightmapChanges>, &Children)>,
+    mut waters: Query<(&Mesh3d, Ref<Water>, &mut Transform, &mut Collider)>,
+) {
+    for (heightmap, changes, children) in &cities {
+        let mut iter = waters.iter_many_mut(children);
+        while let Some((mesh_handle, water, mut transform, mut collider)) = iter.fetch_next() {
+            if !changes.is_changed() && !water.is_changed() {
+                continue;
+            }
+
+            transform.translation.y = surface_level(&water, heightmap);
+
+            if water.is_changed() {
+                let mesh = meshes
+                    .get_mut(mesh_handle)
+                    .expect("water handles should be valid");
+
+                trace!("regenerating water mesh");
+                let mut dyn_mesh = DynamicMesh::take(mesh);
+                water_mesh::generate(&mut dyn_mesh, &water);
+                dyn_mesh.apply(mesh);
+
+                *collider = water_mesh::generate_collider(&water);
+            }
+        }
+    }
+}
+
+fn apply_command(
+    mut commands: Commands,
+    mut request_events: EventReader<FromClient<CommandRequest<WaterCommand>>>,
+    mut confirm_events: EventWriter<ToClients<CommandConfirmation>>,
+    mut waters: Query<&mut Water>,
+) {
+    for FromClient { client_id, event } in request_events.read().cloned() {
+        // TODO: validate self-intersections.
+        let mut confirmation = CommandConfirmation::new(event.id);
+        match event.command {
+            WaterCommand::Create {
+                city_entity,
+                outline,
+            } => {
+                if outline.len() < MIN_POINTS {
+                    error!("`{client_id:?}` sent water with {} points", outline.len());
+                } else {
+                    info!("`{client_id:?}` creates water");
+                    commands.entity(city_entity).with_children(|parent| {
+                        let entity = parent.spawn(Water(outline)).id();
+                        confirmation.entity = Some(entity);
+                    });
+                }
+            }
+            WaterCommand::EditPoint {
+                entity,
+                index,
+                point,
+            } => match waters.get_mut(entity) {
+                Ok(mut water) => match water.0.get_mut(index) {
+                    Some(water_point) => {
+                        info!("`{client_id:?}` edits point {index} for water `{entity}`");
+                        *water_point = point;
+                    }
+                    None => error!("water `{entity}` doesn't have point {index}"),
+                },
+                Err(e) => error!("unable to edit water `{entity}`: {e}"),
+            },
+            WaterCommand::Delete { entity } => {
+                info!("`{client_id:?}` removes water `{entity}`");
+                commands.entity(entity).despawn();
+            }
+        }
+
+        confirm_events.send(ToClients {
+            mode: SendMode::Direct(client_id),
+            event: confirmation,
+        });
+    }
+}
+
+/// Returns height of the water surface.
+///
+/// Uses the lowest terrain point on the shoreline to avoid water floating above the ground.
+fn surface_level(outline: &[Vec2], heightmap: &Heightmap) -> f32 {
+    outline
+        .iter()
+        .map(|&point| heightmap.height_at(point))
+        .reduce(f32::min)
+        .unwrap_or_default()
+}
+
+#[derive(Resource)]
+struct WaterMaterial(MeshMaterial3d<StandardMaterial>);
+
+impl FromWorld for WaterMaterial {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let material = materials.add(StandardMaterial {
+            base_color: Color::srgba(0.1, 0.3, 0.45, 0.8),
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 0.05,
+            reflectance: 0.6,
+            ..Default::default()
+        });
+
+        Self(material.into())
+    }
+}
+
+#[derive(Clone, Component, Copy, Debug, Default, EnumIter, Eq, Hash, PartialEq, SubStates)]
+#[source(CityMode = CityMode::Water)]
+pub enum WaterTool {
+    #[default]
+    Create,
+    Move,
+}
+
+impl WaterTool {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Create => "✏",
+            Self::Move => "↔",
+        }
+    }
+}
+
+/// Closed outline of a pond, lake or river in city coordinates.
+///
+/// The whole area up to the shoreline is excluded from navigation and placement.
+#[derive(Component, Clone, Default, Deref, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+#[require(
+    Name(|| Name::new("Water")),
+    ParentSync,
+    Replicated,
+    Obstacle,
+    Mesh3d,
+    MeshMaterial3d::<StandardMaterial>,
+    NotShadowCaster,
+    Collider,
+    CollisionLayers(|| CollisionLayers::new(
+        Layer::Water,
+        [Layer::PlacingObject, Layer::PlacingWall, Layer::PlacingRoad],
+    )),
+)]
+pub(crate) struct Water(Vec<Vec2>);
+
+#[derive(Serialize, Deserialize, Clone)]
+enum WaterCommand {
+    Create {
+        city_entity: Entity,
+        outline: Vec<Vec2>,
+    },
+    EditPoint {
+        entity: Entity,
+        index: usize,
+        point: Vec2,
+    },
+    Delete {
+        entity: Entity,
+    },
+}
+
+impl PendingCommand for WaterCommand {
+    fn apply(
+        self: Box<Self>,
+        id: CommandId,
+        mut recorder: EntityRecorder,
+        world: &mut World,
+    ) -> Box<dyn ConfirmableCommand> {
+        let reverse_command = match *self {
+            Self::Create { .. } => Self::Delete {
+                // Correct entity will be set after the server confirmation.
+                entity: Entity::PLACEHOLDER,
+            },
+            Self::EditPoint { entity, index, .. } => {
+                let water = world.get::<Water>(entity).unwrap();
+                Self::EditPoint {
+                    entity,
+                    index,
+                    point: water[index],
+                }
+            }
+            Self::Delete { entity } => {
+                recorder.record(entity);
+                let entity = world.entity(entity);
+                let water = entity.get::<Water>().unwrap();
+                let city_entity = **entity.get::<Parent>().unwrap();
+                Self::Create {
+                    city_entity,
+                    outline: water.0.clone(),
+                }
+            }
+        };
+
+        world.send_event(CommandRequest { id, command: *self });
+
+        Box::new(reverse_command)
+    }
+}
+
+impl ConfirmableCommand for WaterCommand {
+    fn confirm(
+        mut self: Box<Self>,
+        mut recorder: EntityRecorder,
+        confirmation: CommandConfirmation,
+    ) -> Box<dyn PendingCommand> {
+        if let Self::Delete { entity } = &mut *self {
+            *entity = confirmation
+                .entity
+                .expect("confirmation for water creation should contain an entity");
+            recorder.record(*entity);
+        }
+
+        self
+    }
+}
+
+impl MapEntities for WaterCommand {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        match self {
+            Self::Create { city_entity, .. } => {
+                *city_entity = entity_mapper.map_entity(*city_entity)
+            }
+            Self::EditPoint { entity, .. } => *entity = entity_mapper.map_entity(*entity),
+            Self::Delete { entity } => *entity = entity_mapper.map_entity(*entity),
+        };
+    }
+}
@@ -0,0 +1,281 @@
+use bevy::{pbr::NotShadowCaster, prelude::*, render::view::NoFrustumCulling};
+use bevy_enhanced_input::prelude::*;
+
+use super::{Water, WaterCommand, WaterMaterial, WaterTool, MIN_POINTS};
+use crate::{
+    dynamic_mesh::DynamicMesh,
+    game_world::{
+        city::{terrain::Heightmap, ActiveCity, CityMode},
+        commands_history::{CommandsHistory, PendingDespawn},
+        player_camera::CameraCaster,
+    },
+    ghost::Ghost,
+    settings::Settings,
+};
+
+pub(super) struct PlacingWaterPlugin;
+
+impl Plugin for PlacingWaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_input_context::<PlacingWater>()
+            .add_observer(pick.never_param_warn())
+            .add_observer(spawn.never_param_warn())
+            .add_observer(delete.never_param_warn())
+            .add_observer(cancel)
+            .add_observer(confirm.never_param_warn())
+            .add_systems(
+                Update,
+                update_position
+                    .never_param_warn()
+                    .run_if(in_state(CityMode::Water)),
+            )
+            .add_systems(
+                PostUpdate,
+                update_mesh
+                    .never_param_warn()
+                    .run_if(in_state(CityMode::Water)),
+            );
+    }
+}
+
+/// Distance within which points are picked or snapped to the first point.
+const SNAP_DELTA: f32 = 0.5;
+
+fn pick(
+    mut trigger: Trigger<Pointer<Click>>,
+    water_tool: Res<State<WaterTool>>,
+    mut commands: Commands,
+    camera_caster: CameraCaster,
+    water_material: Res<WaterMaterial>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    waters: Query<(Entity, &Parent, &Water)>,
+    placing_waters: Query<(), With<PlacingWater>>,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+    if *water_tool != WaterTool::Move {
+        return;
+    }
+    if !placing_waters.is_empty() {
+        return;
+    }
+    let Ok((entity, parent, water)) = waters.get(trigger.entity()) else {
+        return;
+    };
+    let Some(point) = camera_caster.intersect_ground() else {
+        return;
+    };
+    let Some(index) = water
+        .iter()
+        .position(|water_point| water_point.distance(point.xz()) < SNAP_DELTA)
+    else {
+        return;
+    };
+    trigger.propagate(false);
+
+    info!("picking point {index} for `{entity}`");
+    commands.entity(**parent).with_children(|parent| {
+        parent.spawn((
+            Ghost::new(entity),
+            PlacingWater {
+                kind: PlacingWaterKind::EditPoint { entity, index },
+                outline: water.to_vec(),
+            },
+            Mesh3d(meshes.add(DynamicMesh::create_empty())),
+            water_material.0.clone(),
+        ));
+    });
+}
+
+fn spawn(
+    mut trigger: Trigger<Pointer<Click>>,
+    water_tool: Res<State<WaterTool>>,
+    mut commands: Commands,
+    camera_caster: CameraCaster,
+    water_material: Res<WaterMaterial>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    city_entity: Single<Entity, With<ActiveCity>>,
+    placing_waters: Query<(), With<PlacingWater>>,
+) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+    if *water_tool != WaterTool::Create {
+        return;
+    }
+    if !placing_waters.is_empty() {
+        return;
+    }
+    let Some(point) = camera_caster.intersect_ground() else {
+        return;
+    };
+
+    trigger.propagate(false);
+
+    info!("spawning new water");
+    commands.entity(*city_entity).with_children(|parent| {
+        parent.spawn((
+            PlacingWater {
+                kind: PlacingWaterKind::Spawning,
+                // The last point follows the cursor.
+                outline: vec![point.xz(); 2],
+            },
+            Mesh3d(meshes.add(DynamicMesh::create_empty())),
+            water_material.0.clone(),
+        ));
+    });
+}
+
+fn update_position(camera_caster: CameraCaster, mut placing_water: Single<&mut PlacingWater>) {
+    let Some(point) = camera_caster.intersect_ground().map(|point| point.xz()) else {
+        return;
+    };
+
+    let index = match placing_water.kind {
+        PlacingWaterKind::Spawning => placing_water.outline.len() - 1,
+        PlacingWaterKind::EditPoint { index, .. } => index,
+    };
+
+    if placing_water.outline[index] != point {
+        trace!("updating point {index} to `{point:?}`");
+        placing_water.outline[index] = point;
+    }
+}
+
+fn update_mesh(
+    mut meshes: ResMut<Assets<Mesh>>,
+    cities: Query<&Heightmap>,
+    placing_water: Single<(&Mesh3d, &Parent, &PlacingWater, &mut Transform), Changed<PlacingWater>>,
+) {
+    let (mesh_handle, parent, placing_water, mut transform) = placing_water.into_inner();
+    let heightmap = cities
+        .get(**parent)
+        .expect("placing water should be a child of a city");
+    transform.translation.y = super::surface_level(&placing_water.outline, heightmap);
+
+    let mesh = meshes
+        .get_mut(mesh_handle)
+        .expect("placing water handle should be valid");
+
+    let mut dyn_mesh = DynamicMesh::take(mesh);
+    super::water_mesh::generate(&mut dyn_mesh, &placing_water.outline);
+    dyn_mesh.apply(mesh);
+}
+
+fn delete(
+    trigger: Trigger<Completed<DeleteWater>>,
+    mut commands: Commands,
+    mut history: CommandsHistory,
+    placing_water: Single<&PlacingWater>,
+) {
+    info!("deleting water");
+    if let PlacingWaterKind::EditPoint { entity, .. } = placing_water.kind {
+        let command_id = history.push_pending(WaterCommand::Delete { entity });
+        commands
+            .entity(trigger.entity())
+            .insert(PendingDespawn { command_id })
+            .remove::<PlacingWater>();
+    } else {
+        commands.entity(trigger.entity()).despawn_recursive();
+    }
+}
+
+fn cancel(trigger: Trigger<Completed<CancelWater>>, mut commands: Commands) {
+    debug!("cancelling water placing");
+    commands.entity(trigger.entity()).despawn_recursive();
+}
+
+fn confirm(
+    trigger: Trigger<Completed<ConfirmWater>>,
+    mut commands: Commands,
+    mut history: CommandsHistory,
+    placing_water: Single<(&Parent, &mut PlacingWater)>,
+) {
+    let (parent, mut placing_water) = placing_water.into_inner();
+
+    let command_id = match placing_water.kind {
+        PlacingWaterKind::Spawning => {
+            let &last = placing_water.outline.last().unwrap();
+            let &first = placing_water.outline.first().unwrap();
+            let closing = last.distance(first) < SNAP_DELTA;
+            if !closing || placing_water.outline.len() <= MIN_POINTS {
+                debug!("adding water point");
+                placing_water.outline.push(last);
+                return;
+            }
+
+            info!("confirming water creation");
+            let mut outline = placing_water.outline.clone();
+            outline.pop();
+            history.push_pending(WaterCommand::Create {
+                city_entity: **parent,
+                outline,
+            })
+        }
+        PlacingWaterKind::EditPoint { entity, index } => {
+            info!("confirming point {index} for water `{entity}`");
+            history.push_pending(WaterCommand::EditPoint {
+                entity,
+                index,
+                point: placing_water.outline[index],
+            })
+        }
+    };
+
+    commands
+        .entity(trigger.entity())
+        .insert(PendingDespawn { command_id })
+        .remove::<PlacingWater>();
+}
+
+#[derive(Component)]
+#[require(
+    Name(|| Name::new("Placing water")),
+    // Looks like AABB is not recalculated when we edit the mesh.
+    // But we don't need to cull currently placed water anyway.
+    NoFrustumCulling,
+    NotShadowCaster,
+    Mesh3d,
+    MeshMaterial3d::<StandardMaterial>,
+)]
+struct PlacingWater {
+    kind: PlacingWaterKind,
+    outline: Vec<Vec2>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum PlacingWaterKind {
+    Spawning,
+    EditPoint { entity: Entity, index: usize },
+}
+
+impl InputContext for PlacingWater {
+    const PRIORITY: isize = 1;
+
+    fn context_instance(world: &World, _entity: Entity) -> ContextInstance {
+        let mut ctx = ContextInstance::default();
+        let settings = world.resource::<Settings>();
+
+        ctx.bind::<DeleteWater>()
+            .to((&settings.keyboard.delete, GamepadButton::North));
+        ctx.bind::<CancelWater>()
+            .to((KeyCode::Escape, GamepadButton::East));
+        ctx.bind::<ConfirmWater>()
+            .to((MouseButton::Left, GamepadButton::South));
+
+        ctx
+    }
+}
+
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct DeleteWater;
+
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct CancelWater;
+
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+struct ConfirmWater;
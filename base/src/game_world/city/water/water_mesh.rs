@@ -0,0 +1,84 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use earcut::Earcut;
+
+use crate::dynamic_mesh::DynamicMesh;
+
+/// How deep the collider goes under the surface.
+const DEPTH: f32 = 2.0;
+
+/// How high the collider goes above the surface.
+///
+/// Makes the shoreline block placement on slopes.
+const HEIGHT: f32 = 0.5;
+
+pub(super) fn generate(mesh: &mut DynamicMesh, outline: &[Vec2]) {
+    mesh.clear();
+
+    if outline.len() < super::MIN_POINTS {
+        return;
+    }
+
+    for &point in outline {
+        mesh.positions.push([point.x, 0.0, point.y]);
+        mesh.normals.push([0.0, 1.0, 0.0]);
+        mesh.uvs.push(point.into());
+    }
+
+    triangulate(&mut mesh.indices, outline);
+}
+
+pub(super) fn generate_collider(outline: &[Vec2]) -> Collider {
+    if outline.len() < super::MIN_POINTS {
+        return Default::default();
+    }
+
+    let mut vertices = Vec::with_capacity(outline.len() * 2);
+    vertices.extend(
+        outline
+            .iter()
+            .map(|point| Vec3::new(point.x, HEIGHT, point.y)),
+    );
+    vertices.extend(
+        outline
+            .iter()
+            .map(|point| Vec3::new(point.x, -DEPTH, point.y)),
+    );
+
+    let mut cap_indices = Vec::new();
+    triangulate(&mut cap_indices, outline);
+
+    let mut indices = Vec::new();
+    let len: u32 = outline.len().try_into().expect("points should fit u32");
+    for triangle in cap_indices.chunks_exact(3) {
+        // Top
+        indices.push([triangle[0], triangle[1], triangle[2]]);
+        // Bottom
+        indices.push([triangle[2] + len, triangle[1] + len, triangle[0] + len]);
+    }
+
+    // Sides
+    for index in 0..len {
+        let next = (index + 1) % len;
+        indices.push([index, index + len, next]);
+        indices.push([next, index + len, next + len]);
+    }
+
+    Collider::trimesh(vertices, indices)
+}
+
+/// Triangulates the outline with triangles facing up.
+fn triangulate(indices: &mut Vec<u32>, outline: &[Vec2]) {
+    let mut earcut = Earcut::new();
+    earcut.earcut(outline.iter().map(|point| [point.x, point.y]), &[], indices);
+
+    for triangle in indices.chunks_exact_mut(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| {
+            let point = outline[index as usize];
+            Vec3::new(point.x, 0.0, point.y)
+        });
+        if (b - a).cross(c - a).y < 0.0 {
+            triangle.swap(0, 2);
+        }
+    }
+}
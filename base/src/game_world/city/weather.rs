@@ -0,0 +1,325 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use crate::{
+    core::GameState,
+    game_world::{
+        actor::{
+            needs::{Fun, Hygiene, Need},
+            Actor,
+        },
+        clock::{Season, WorldClock},
+        family::building::wall::Wall,
+        segment::Segment,
+    },
+    settings::Settings,
+};
+
+pub(super) struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Weather>()
+            .register_type::<WeatherSeed>()
+            .replicate::<Weather>()
+            .replicate::<WeatherSeed>()
+            .add_systems(
+                Update,
+                (
+                    update,
+                    affect_needs.run_if(on_timer(Duration::from_secs(1))),
+                )
+                    .chain()
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Number of hours between weather rolls.
+const PERIOD_HOURS: f32 = 6.0;
+
+fn update(
+    clock: Res<WorldClock>,
+    settings: Res<Settings>,
+    mut cities: Query<(Entity, &WeatherSeed, &mut Weather)>,
+) {
+    let period =
+        clock.day() * (24.0 / PERIOD_HOURS) as u32 + (clock.time_of_day() / PERIOD_HOURS) as u32;
+    for (entity, seed, mut weather) in &mut cities {
+        let new_weather = settings
+            .developer
+            .forced_weather
+            .unwrap_or_else(|| Weather::roll(seed.0, period, clock.season()));
+
+        if weather.set_if_neq(new_weather) {
+            debug!("changing weather in city `{entity}` to `{new_weather:?}`");
+        }
+    }
+}
+
+/// Applies weather effects to needs of actors that are not inside a building.
+fn affect_needs(
+    cities: Query<(Entity, &Weather, &Children)>,
+    walls: Query<(&Parent, &Segment), With<Wall>>,
+    actors: Query<(&Transform, &Children), With<Actor>>,
+    mut hygiene_needs: Query<&mut Need, (With<Hygiene>, Without<Fun>)>,
+    mut fun_needs: Query<&mut Need, (With<Fun>, Without<Hygiene>)>,
+) {
+    for (city_entity, &weather, children) in &cities {
+        let (hygiene_rate, fun_rate) = weather.need_rates();
+        if hygiene_rate == 0.0 && fun_rate == 0.0 {
+            continue;
+        }
+
+        let city_walls: Vec<_> = walls
+            .iter()
+            .filter(|(parent, _)| ***parent == city_entity)
+            .map(|(_, &segment)| segment)
+            .collect();
+
+        for (transform, actor_children) in actors.iter_many(children) {
+            if is_indoors(transform.translation.xz(), &city_walls) {
+                continue;
+            }
+
+            let mut iter = hygiene_needs.iter_many_mut(actor_children);
+            while let Some(mut need) = iter.fetch_next() {
+                need.0 = (need.0 + hygiene_rate).clamp(0.0, 100.0);
+            }
+            let mut iter = fun_needs.iter_many_mut(actor_children);
+            while let Some(mut need) = iter.fetch_next() {
+                need.0 = (need.0 + fun_rate).clamp(0.0, 100.0);
+            }
+        }
+    }
+}
+
+/// Returns `true` if the point is enclosed by walls.
+///
+/// Counts wall crossings of a ray casted from the point along X, an odd number means that the point is inside.
+/// Each wall includes only its lower end by Y, so a ray through a vertex shared by two walls is counted once.
+pub(crate) fn is_indoors(point: Vec2, walls: &[Segment]) -> bool {
+    let crossings = walls
+        .iter()
+        .filter(|wall| {
+            if (wall.start.y > point.y) == (wall.end.y > point.y) {
+                return false;
+            }
+
+            let t = (point.y - wall.start.y) / (wall.end.y - wall.start.y);
+            let x = wall.start.x + (wall.end.x - wall.start.x) * t;
+            x > point.x
+        })
+        .count();
+    crossings % 2 == 1
+}
+
+/// Current weather in a city.
+#[derive(
+    Component, Clone, Copy, Debug, Default, Deserialize, EnumIter, Eq, PartialEq, Reflect, Serialize,
+)]
+#[reflect(Component)]
+pub enum Weather {
+    #[default]
+    Sunny,
+    Cloudy,
+    Rain,
+    Snow,
+}
+
+impl Weather {
+    /// Picks weather for the given period.
+    ///
+    /// Returns the same result for the same arguments.
+    fn roll(seed: u64, period: u32, season: Season) -> Self {
+        let hash = splitmix64(seed ^ splitmix64(period.into()));
+        // Use the upper 24 bits to get an evenly distributed value in `[0, 1)`.
+        let value = (hash >> 40) as f32 / (1 << 24) as f32;
+
+        let mut threshold = 0.0;
+        for (weather, chance) in Self::chances(season) {
+            threshold += chance;
+            if value < threshold {
+                return weather;
+            }
+        }
+
+        Self::Sunny
+    }
+
+    /// Returns probabilities of each weather in a season.
+    fn chances(season: Season) -> [(Self, f32); 4] {
+        let [sunny, cloudy, rain, snow] = match season {
+            Season::Spring => [0.45, 0.3, 0.25, 0.0],
+            Season::Summer => [0.65, 0.2, 0.15, 0.0],
+            Season::Autumn => [0.3, 0.35, 0.3, 0.05],
+            Season::Winter => [0.25, 0.3, 0.05, 0.4],
+        };
+
+        [
+            (Self::Sunny, sunny),
+            (Self::Cloudy, cloudy),
+            (Self::Rain, rain),
+            (Self::Snow, snow),
+        ]
+    }
+
+    /// Returns movement speed multiplier for actors.
+    pub(crate) fn speed_factor(self) -> f32 {
+        match self {
+            Self::Sunny | Self::Cloudy => 1.0,
+            Self::Rain => 0.85,
+            Self::Snow => 0.7,
+        }
+    }
+
+    /// Returns color that multiplies the ground materials.
+    pub(crate) fn ground_tint(self) -> LinearRgba {
+        match self {
+            Self::Sunny => LinearRgba::WHITE,
+            Self::Cloudy => LinearRgba::rgb(0.85, 0.85, 0.85),
+            Self::Rain => LinearRgba::rgb(0.6, 0.62, 0.65),
+            Self::Snow => LinearRgba::rgb(0.9, 0.95, 1.0),
+        }
+    }
+
+    /// Returns changes of [`Hygiene`] and [`Fun`] per second for actors outdoors.
+    fn need_rates(self) -> (f32, f32) {
+        match self {
+            Self::Sunny => (0.0, 0.05),
+            Self::Cloudy => (0.0, 0.0),
+            Self::Rain => (-0.3, -0.1),
+            Self::Snow => (-0.1, 0.05),
+        }
+    }
+
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Sunny => "☀",
+            Self::Cloudy => "☁",
+            Self::Rain => "🌧",
+            Self::Snow => "🌨",
+        }
+    }
+}
+
+/// Seed for weather rolls in a city.
+///
+/// Generated randomly for new cities and saved with them.
+#[derive(Component, Clone, Copy, Deref, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub(crate) struct WeatherSeed(u64);
+
+impl Default for WeatherSeed {
+    fn default() -> Self {
+        Self(RandomState::new().build_hasher().finish())
+    }
+}
+
+/// Mixes bits of the value into a well-distributed hash.
+fn splitmix64(mut value: u64) -> u64 {
+    value = value.wrapping_add(0x9E3779B97F4A7C15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D049BB133111EB);
+    value ^ (value >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn fixed_seed() {
+        let rolls: Vec<_> = (0..8)
+            .map(|period| Weather::roll(42, period, Season::Winter))
+            .collect();
+        assert_eq!(
+            rolls,
+            [
+                Weather::Cloudy,
+                Weather::Cloudy,
+                Weather::Sunny,
+                Weather::Cloudy,
+                Weather::Rain,
+                Weather::Snow,
+                Weather::Sunny,
+                Weather::Cloudy,
+            ]
+        );
+    }
+
+    #[test]
+    fn seeds_differ() {
+        let rolls = |seed| {
+            (0..100)
+                .map(|period| Weather::roll(seed, period, Season::Autumn))
+                .collect::<Vec<_>>()
+        };
+        assert_ne!(rolls(1), rolls(2));
+    }
+
+    #[test]
+    fn seasons() {
+        for period in 0..1000 {
+            for season in [Season::Spring, Season::Summer] {
+                assert_ne!(Weather::roll(7, period, season), Weather::Snow);
+            }
+        }
+
+        let has_snow =
+            (0..1000).any(|period| Weather::roll(7, period, Season::Winter) == Weather::Snow);
+        assert!(has_snow, "winter should have snow");
+    }
+
+    #[test]
+    fn chances() {
+        for season in Season::iter() {
+            let total: f32 = Weather::chances(season)
+                .iter()
+                .map(|(_, chance)| chance)
+                .sum();
+            assert!(
+                (total - 1.0).abs() < 1e-5,
+                "chances in {season:?} should sum to 1"
+            );
+        }
+    }
+
+    #[test]
+    fn indoors() {
+        let walls = [
+            Segment::new(Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0)),
+            Segment::new(Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0)),
+            Segment::new(Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)),
+            Segment::new(Vec2::new(-1.0, 1.0), Vec2::new(-1.0, -1.0)),
+        ];
+
+        assert!(is_indoors(Vec2::ZERO, &walls));
+        assert!(!is_indoors(Vec2::new(2.0, 0.0), &walls));
+        assert!(!is_indoors(Vec2::new(-2.0, 0.0), &walls));
+    }
+
+    #[test]
+    fn indoors_through_vertex() {
+        let walls = [
+            Segment::new(Vec2::new(0.0, -1.0), Vec2::new(1.0, 0.0)),
+            Segment::new(Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0)),
+            Segment::new(Vec2::new(0.0, 1.0), Vec2::new(-1.0, 0.0)),
+            Segment::new(Vec2::new(-1.0, 0.0), Vec2::new(0.0, -1.0)),
+        ];
+
+        assert!(is_indoors(Vec2::ZERO, &walls));
+        assert!(!is_indoors(Vec2::new(-2.0, 0.0), &walls));
+    }
+}
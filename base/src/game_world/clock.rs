@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use crate::core::GameState;
+
+pub(super) struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<WorldClock>()
+            .add_server_event::<ClockSync>(ChannelKind::Unordered)
+            .add_systems(OnEnter(GameState::InGame), init)
+            .add_systems(
+                PreUpdate,
+                sync.after(ClientSet::Receive)
+                    .run_if(client_connected)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (
+                    advance,
+                    send_sync
+                        .run_if(on_timer(Duration::from_secs(1)))
+                        .run_if(server_running),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(GameState::InGame), cleanup);
+    }
+}
+
+/// Game seconds that pass in one real second at normal speed.
+const TIME_SCALE: f64 = 60.0;
+
+const SECONDS_PER_HOUR: f64 = 60.0 * 60.0;
+const SECONDS_PER_DAY: f64 = 24.0 * SECONDS_PER_HOUR;
+const DAYS_PER_SEASON: u32 = 7;
+
+/// Inserts the clock for new worlds.
+///
+/// For loaded worlds the clock is inserted from the save file.
+fn init(mut commands: Commands) {
+    commands.init_resource::<WorldClock>();
+}
+
+fn advance(time: Res<Time>, mut clock: ResMut<WorldClock>) {
+    clock.elapsed += time.delta_secs_f64() * TIME_SCALE * clock.speed as f64;
+}
+
+/// Corrects drift between server and client clocks.
+fn send_sync(mut sync_events: EventWriter<ToClients<ClockSync>>, clock: Res<WorldClock>) {
+    sync_events.send(ToClients {
+        mode: SendMode::Broadcast,
+        event: ClockSync(*clock),
+    });
+}
+
+fn sync(mut commands: Commands, mut sync_events: EventReader<ClockSync>) {
+    if let Some(&ClockSync(clock)) = sync_events.read().last() {
+        trace!("syncing clock to {:?}", clock);
+        commands.insert_resource(clock);
+    }
+}
+
+fn cleanup(mut commands: Commands) {
+    commands.remove_resource::<WorldClock>();
+}
+
+/// In-game date and time.
+///
+/// Advanced on both server and clients, but periodically synced from the server.
+/// Saved with the world.
+#[derive(Resource, Clone, Copy, Debug, Deserialize, Reflect, Serialize)]
+#[reflect(Resource)]
+pub struct WorldClock {
+    /// Game seconds since the world creation.
+    elapsed: f64,
+
+    /// Time multiplier.
+    speed: f32,
+}
+
+impl WorldClock {
+    /// Maximum time multiplier.
+    ///
+    /// Higher values make simulation steps too large.
+    pub const MAX_SPEED: f32 = 10.0;
+
+    /// Returns game seconds since the world creation.
+    pub fn elapsed_secs(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Returns game time passed since the specified [`Self::elapsed_secs`] converted to normal speed.
+    ///
+    /// Useful to compare with durations that are defined for normal speed.
+    pub fn normal_time_since(&self, elapsed_secs: f64) -> Duration {
+        Duration::from_secs_f64(((self.elapsed - elapsed_secs) / TIME_SCALE).max(0.0))
+    }
+
+    /// Returns hours since midnight in range `[0, 24)`.
+    pub fn time_of_day(&self) -> f32 {
+        (self.elapsed.rem_euclid(SECONDS_PER_DAY) / SECONDS_PER_HOUR) as f32
+    }
+
+    /// Returns the number of passed game minutes.
+    pub fn minutes(&self) -> u64 {
+        (self.elapsed / 60.0) as u64
+    }
+
+    /// Returns the number of passed days.
+    pub fn day(&self) -> u32 {
+        (self.elapsed / SECONDS_PER_DAY) as u32
+    }
+
+    pub fn season(&self) -> Season {
+        Season::from_day(self.day())
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets the time multiplier clamped to [`Self::MAX_SPEED`].
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.clamp(0.0, Self::MAX_SPEED);
+    }
+}
+
+impl Default for WorldClock {
+    fn default() -> Self {
+        Self {
+            // Start in the morning.
+            elapsed: 8.0 * SECONDS_PER_HOUR,
+            speed: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, EnumIter, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    fn from_day(day: u32) -> Self {
+        match (day / DAYS_PER_SEASON) % 4 {
+            0 => Self::Spring,
+            1 => Self::Summer,
+            2 => Self::Autumn,
+            _ => Self::Winter,
+        }
+    }
+
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Spring => "🌱",
+            Self::Summer => "☀",
+            Self::Autumn => "🍂",
+            Self::Winter => "❄",
+        }
+    }
+}
+
+/// Server event with the current clock state.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+struct ClockSync(WorldClock);
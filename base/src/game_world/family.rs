@@ -1,7 +1,8 @@
+pub mod bills;
 pub mod building;
 pub mod editor;
 
-use std::io::Cursor;
+use std::{collections::HashMap, io::Cursor};
 
 use bevy::{
     ecs::{
@@ -20,10 +21,11 @@ use serde::{de::DeserializeSeed, Deserialize, Serialize};
 use strum::EnumIter;
 
 use super::{
-    actor::{Actor, SelectedActor},
+    actor::{aging::AgingSettings, Actor, SelectedActor},
     WorldState,
 };
 use crate::core::GameState;
+use bills::{BillsPlugin, PropertyValue};
 use building::BuildingPlugin;
 use editor::{EditorPlugin, FamilyScene, ReflectActorBundle};
 
@@ -31,7 +33,7 @@ pub(super) struct FamilyPlugin;
 
 impl Plugin for FamilyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((EditorPlugin, BuildingPlugin))
+        app.add_plugins((BillsPlugin, EditorPlugin, BuildingPlugin))
             .add_sub_state::<FamilyMode>()
             .enable_state_scoped_entities::<FamilyMode>()
             .register_type::<Family>()
@@ -44,6 +46,8 @@ impl Plugin for FamilyPlugin {
                 deserialize_family_spawn,
             )
             .add_mapped_client_event::<FamilyDelete>(ChannelKind::Unordered)
+            .add_mapped_client_event::<FamilySelect>(ChannelKind::Ordered)
+            .init_resource::<FamilyControllers>()
             .add_mapped_server_event::<SelectedFamilyCreated>(ChannelKind::Unordered)
             .add_observer(record_new_members)
             .add_observer(update_members)
@@ -51,11 +55,12 @@ impl Plugin for FamilyPlugin {
             .add_systems(OnExit(WorldState::Family), deselect.never_param_warn())
             .add_systems(
                 PreUpdate,
-                (create, delete)
+                (create, delete, update_controllers, remove_disconnected)
                     .run_if(server_or_singleplayer)
                     .after(ClientSet::Receive)
                     .run_if(in_state(GameState::InGame)),
-            );
+            )
+            .add_systems(OnExit(GameState::InGame), cleanup);
     }
 }
 
@@ -116,18 +121,66 @@ fn delete(
     }
 }
 
-pub fn select(mut commands: Commands, selected_actor: Single<&Actor, With<SelectedActor>>) {
+/// Tracks which families clients control to validate their requests.
+fn update_controllers(
+    mut controllers: ResMut<FamilyControllers>,
+    mut select_events: EventReader<FromClient<FamilySelect>>,
+    families: Query<(), With<Family>>,
+) {
+    for FromClient { client_id, event } in select_events.read() {
+        match event.0 {
+            Some(family_entity) if families.contains(family_entity) => {
+                debug!("`{client_id:?}` controls family `{family_entity}`");
+                controllers.insert(*client_id, family_entity);
+            }
+            Some(family_entity) => {
+                error!("`{client_id:?}` tried to control invalid family `{family_entity}`")
+            }
+            None => {
+                debug!("`{client_id:?}` stopped controlling a family");
+                controllers.remove(client_id);
+            }
+        }
+    }
+}
+
+fn remove_disconnected(
+    mut controllers: ResMut<FamilyControllers>,
+    mut server_events: EventReader<ServerEvent>,
+) {
+    for event in server_events.read() {
+        if let ServerEvent::ClientDisconnected { client_id, .. } = event {
+            controllers.remove(client_id);
+        }
+    }
+}
+
+fn cleanup(mut controllers: ResMut<FamilyControllers>) {
+    controllers.clear();
+}
+
+pub fn select(
+    mut commands: Commands,
+    mut select_events: EventWriter<FamilySelect>,
+    selected_actor: Single<&Actor, With<SelectedActor>>,
+) {
     info!("selecting `{}`", selected_actor.family_entity);
     commands
         .entity(selected_actor.family_entity)
         .insert(SelectedFamily);
+    select_events.send(FamilySelect(Some(selected_actor.family_entity)));
 }
 
-fn deselect(mut commands: Commands, selected_actor: Single<&Actor, With<SelectedActor>>) {
+fn deselect(
+    mut commands: Commands,
+    mut select_events: EventWriter<FamilySelect>,
+    selected_actor: Single<&Actor, With<SelectedActor>>,
+) {
     info!("deselecting `{}`", selected_actor.family_entity);
     commands
         .entity(selected_actor.family_entity)
         .remove::<SelectedFamily>();
+    select_events.send(FamilySelect(None));
 }
 
 fn serialize_family_spawn(
@@ -214,6 +267,8 @@ impl FamilyMode {
 #[require(
     Name,
     Budget,
+    PropertyValue,
+    AgingSettings,
     Replicated,
     FamilyMembers,
     StateScoped<GameState>(|| StateScoped(GameState::InGame))
@@ -224,6 +279,19 @@ pub struct Family;
 #[reflect(Component)]
 pub struct Budget(u32);
 
+impl Budget {
+    pub(crate) fn earn(&mut self, amount: u32) {
+        self.0 = self.0.saturating_add(amount);
+    }
+
+    /// Deducts the amount and returns the part that couldn't be paid.
+    pub(crate) fn spend(&mut self, amount: u32) -> u32 {
+        let paid = amount.min(self.0);
+        self.0 -= paid;
+        amount - paid
+    }
+}
+
 impl Default for Budget {
     fn default() -> Self {
         Self(20_000)
@@ -271,6 +339,37 @@ impl MapEntities for FamilyDelete {
     }
 }
 
+/// Family that the client switched to or [`None`] if it left the family mode.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub(super) struct FamilySelect(Option<Entity>);
+
+impl MapEntities for FamilySelect {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.0 = self.0.map(|entity| entity_mapper.map_entity(entity));
+    }
+}
+
+/// Families controlled by connected clients.
+///
+/// Updated only on server. Unlike [`SelectedFamily`], it can be used to check
+/// on which behalf a client sends requests.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(crate) struct FamilyControllers(HashMap<ClientId, Entity>);
+
+impl FamilyControllers {
+    /// Returns `true` if the client controls the family.
+    pub(crate) fn controls(&self, client_id: ClientId, family_entity: Entity) -> bool {
+        self.get(&client_id) == Some(&family_entity)
+    }
+
+    /// Returns all clients that control the family.
+    pub(crate) fn clients(&self, family_entity: Entity) -> impl Iterator<Item = ClientId> + '_ {
+        self.iter()
+            .filter(move |(_, &entity)| entity == family_entity)
+            .map(|(&client_id, _)| client_id)
+    }
+}
+
 /// An event from server which indicates spawn confirmation for the selected family.
 #[derive(Deserialize, Event, Serialize)]
 pub(super) struct SelectedFamilyCreated(pub(super) Entity);
@@ -280,3 +379,24 @@ impl MapEntities for SelectedFamilyCreated {
         self.0 = entity_mapper.map_entity(self.0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_world::test_app::{self, GameTestAppExt};
+
+    #[test]
+    fn replication() {
+        let (mut server_app, mut client_app) = test_app::new_connected_apps();
+        let city_entity = server_app.spawn_city();
+        server_app.spawn_family(city_entity, &["First", "Second"]);
+        test_app::exchange(&mut server_app, &mut client_app);
+
+        let mut families = client_app
+            .world_mut()
+            .query_filtered::<(&Name, &FamilyMembers), With<Family>>();
+        let (name, members) = families.single(client_app.world());
+        assert_eq!(name.as_str(), "Test");
+        assert_eq!(members.len(), 2);
+    }
+}
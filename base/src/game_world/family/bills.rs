@@ -0,0 +1,299 @@
+use std::cmp::Reverse;
+
+use bevy::{
+    ecs::{entity::MapEntities, system::SystemParam},
+    prelude::*,
+};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    building::wall::{wall_mesh, Wall},
+    Budget, FamilyMembers,
+};
+use crate::{
+    asset::manifest::object_manifest::ObjectManifest,
+    core::GameState,
+    game_world::{
+        city::weather,
+        clock::WorldClock,
+        object::{self, Object},
+        segment::Segment,
+    },
+};
+
+pub(super) struct BillsPlugin;
+
+impl Plugin for BillsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PropertyValue>()
+            .register_type::<Owner>()
+            .replicate::<PropertyValue>()
+            .replicate_mapped::<Owner>()
+            .add_observer(pay)
+            .add_systems(
+                Update,
+                (update_property_values.run_if(property_changed), charge)
+                    .chain()
+                    .run_if(server_or_singleplayer)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// Daily bill for each family member.
+const BILL_PER_MEMBER: u32 = 50;
+
+/// Part of the property value charged daily.
+const PROPERTY_TAX: f32 = 0.01;
+
+/// Price for a square meter of a wall side.
+const WALL_PRICE: f32 = 10.0;
+
+/// Price for a square meter of a floor.
+const FLOOR_PRICE: f32 = 40.0;
+
+/// Size of a grid cell for floor area estimation.
+const FLOOR_CELL: f32 = 0.5;
+
+/// Returns `true` if an owned object or wall was added, changed or removed.
+fn property_changed(
+    mut removed_owners: RemovedComponents<Owner>,
+    changed_property: Query<(), (With<Owner>, Or<(Changed<Owner>, Changed<Segment>)>)>,
+) -> bool {
+    // Read all events to avoid triggering on the same removals again.
+    let removed = removed_owners.read().count() > 0;
+    removed || !changed_property.is_empty()
+}
+
+fn update_property_values(
+    property: FamilyProperty,
+    mut families: Query<(Entity, &mut PropertyValue)>,
+) {
+    for (entity, mut property_value) in &mut families {
+        let objects_value: u32 = property.objects(entity).map(|(_, price)| price).sum();
+        let walls = property.walls(entity);
+        let wall_area: f32 = walls
+            .iter()
+            .map(|wall| wall.len() * wall_mesh::HEIGHT)
+            .sum();
+        let buildings_value = wall_area * WALL_PRICE + floor_area(&walls) * FLOOR_PRICE;
+
+        let value = objects_value + buildings_value.round() as u32;
+        if property_value.0 != value {
+            debug!("updating property value of `{entity}` to {value}");
+            property_value.0 = value;
+        }
+    }
+}
+
+/// Estimates the area enclosed by walls by sampling a grid.
+fn floor_area(walls: &[Segment]) -> f32 {
+    let mut points = walls.iter().flat_map(|wall| wall.points());
+    let Some(first) = points.next() else {
+        return 0.0;
+    };
+    let (min, max) = points.fold((first, first), |(min, max), point| {
+        (min.min(point), max.max(point))
+    });
+
+    let cells = ((max - min) / FLOOR_CELL).ceil().as_uvec2();
+    let mut count = 0;
+    for x in 0..cells.x {
+        for y in 0..cells.y {
+            let point = min + (UVec2::new(x, y).as_vec2() + 0.5) * FLOOR_CELL;
+            if weather::is_indoors(point, walls) {
+                count += 1;
+            }
+        }
+    }
+
+    count as f32 * FLOOR_CELL * FLOOR_CELL
+}
+
+/// Issues bills when a new day starts.
+fn charge(
+    mut commands: Commands,
+    mut last_day: Local<Option<u32>>,
+    clock: Res<WorldClock>,
+    families: Query<(Entity, &FamilyMembers, &PropertyValue)>,
+) {
+    let day = clock.day();
+    let Some(last_day) = last_day.replace(day) else {
+        return;
+    };
+    if day <= last_day {
+        return;
+    }
+
+    let passed_days = day - last_day;
+    for (entity, members, property_value) in &families {
+        let tax = (property_value.0 as f32 * PROPERTY_TAX).round() as u32;
+        let amount = (BILL_PER_MEMBER * members.len() as u32 + tax) * passed_days;
+        commands.trigger_targets(Bill(amount), entity);
+    }
+}
+
+/// Pays the bill, repossessing objects if the budget is not enough.
+fn pay(
+    trigger: Trigger<Bill>,
+    mut commands: Commands,
+    property: FamilyProperty,
+    mut budgets: Query<&mut Budget>,
+) {
+    let mut budget = budgets.get_mut(trigger.entity()).unwrap();
+    info!(
+        "charging {} for bills from `{}`",
+        **trigger,
+        trigger.entity()
+    );
+    let mut debt = budget.spend(**trigger);
+    if debt == 0 {
+        return;
+    }
+
+    // Repossessed objects pay off the debt with their price.
+    // Selling doesn't refund anything, so the remainder is not returned to the family.
+    warn!("`{}` couldn't pay {debt} for bills", trigger.entity());
+    let mut objects: Vec<_> = property.objects(trigger.entity()).collect();
+    objects.sort_by_key(|&(_, price)| Reverse(price));
+    for (entity, price) in objects {
+        if debt == 0 {
+            break;
+        }
+
+        info!("repossessing object `{entity}` for {price}");
+        object::sell(&mut commands, entity);
+        debt = debt.saturating_sub(price);
+    }
+
+    if debt > 0 {
+        warn!(
+            "`{}` still owes {debt} after repossession",
+            trigger.entity()
+        );
+    }
+}
+
+/// Objects and walls that belong to a family.
+#[derive(SystemParam)]
+struct FamilyProperty<'w, 's> {
+    asset_server: Res<'w, AssetServer>,
+    manifests: Res<'w, Assets<ObjectManifest>>,
+    objects: Query<'w, 's, (Entity, &'static Object, &'static Owner)>,
+    walls: Query<'w, 's, (&'static Segment, &'static Owner), With<Wall>>,
+}
+
+impl FamilyProperty<'_, '_> {
+    /// Returns entities of owned objects with their prices.
+    fn objects(&self, family_entity: Entity) -> impl Iterator<Item = (Entity, u32)> + '_ {
+        self.objects
+            .iter()
+            .filter(move |(.., owner)| owner.family_entity == family_entity)
+            .filter_map(|(entity, object, _)| {
+                let handle = self.asset_server.get_handle(&**object)?;
+                let manifest = self.manifests.get(&handle)?;
+                Some((entity, manifest.price))
+            })
+    }
+
+    fn walls(&self, family_entity: Entity) -> Vec<Segment> {
+        self.walls
+            .iter()
+            .filter(|(_, owner)| owner.family_entity == family_entity)
+            .map(|(&segment, _)| segment)
+            .collect()
+    }
+}
+
+/// Family that bought an object or built a wall.
+///
+/// Objects without it belong to the city.
+#[derive(Clone, Component, Copy, Deserialize, Reflect, Serialize)]
+#[reflect(Component, MapEntities)]
+pub(crate) struct Owner {
+    pub(crate) family_entity: Entity,
+}
+
+impl MapEntities for Owner {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.family_entity = entity_mapper.map_entity(self.family_entity);
+    }
+}
+
+/// Total price of objects and buildings owned by a family.
+///
+/// Calculated on the server.
+#[derive(Clone, Component, Copy, Debug, Default, Deref, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+pub struct PropertyValue(u32);
+
+/// Triggered on a family entity to charge the specified amount.
+#[derive(Event, Deref)]
+struct Bill(u32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_world::test_app::{self, GameTestAppExt};
+
+    #[test]
+    fn separate_families() {
+        let mut app = test_app::new_app();
+        app.start_game();
+        let city_entity = app.spawn_city();
+        let (first_family, _) = app.spawn_family(city_entity, &["First"]);
+        let (second_family, _) = app.spawn_family(city_entity, &["Second"]);
+
+        let stone_entity = app
+            .world_mut()
+            .spawn((
+                Object("base/objects/rocks/small_stone/small_stone.object.ron".into()),
+                Owner {
+                    family_entity: first_family,
+                },
+            ))
+            .set_parent(city_entity)
+            .id();
+        let bench_entity = app
+            .world_mut()
+            .spawn((
+                Object(
+                    "base/objects/outdoor_furniture/simple_bench/simple_bench.object.ron".into(),
+                ),
+                Owner {
+                    family_entity: second_family,
+                },
+            ))
+            .set_parent(city_entity)
+            .id();
+        app.update();
+
+        assert_eq!(
+            **app.world().get::<PropertyValue>(first_family).unwrap(),
+            20
+        );
+        assert_eq!(
+            **app.world().get::<PropertyValue>(second_family).unwrap(),
+            150
+        );
+
+        app.world_mut().get_mut::<Budget>(first_family).unwrap().0 = 0;
+        app.world_mut().trigger_targets(Bill(100), first_family);
+        app.update();
+
+        assert!(
+            app.world().get_entity(stone_entity).is_err(),
+            "object of the indebted family should be repossessed"
+        );
+        assert!(
+            app.world().get_entity(bench_entity).is_ok(),
+            "objects of other families should be kept"
+        );
+        assert_eq!(**app.world().get::<PropertyValue>(first_family).unwrap(), 0);
+        assert_eq!(
+            **app.world().get::<PropertyValue>(second_family).unwrap(),
+            150
+        );
+    }
+}
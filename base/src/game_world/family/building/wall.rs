@@ -8,11 +8,15 @@ use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
-use super::BuildingMode;
+use super::{
+    super::{bills::Owner, FamilyControllers},
+    BuildingMode,
+};
 use crate::{
     core::GameState,
     dynamic_mesh::DynamicMesh,
     game_world::{
+        city::terrain::FlatFootprint,
         commands_history::{
             CommandConfirmation, CommandId, CommandRequest, ConfirmableCommand, EntityRecorder,
             PendingCommand,
@@ -104,6 +108,7 @@ fn apply_command(
     mut commands: Commands,
     mut request_events: EventReader<FromClient<CommandRequest<WallCommand>>>,
     mut confirm_events: EventWriter<ToClients<CommandConfirmation>>,
+    controllers: Res<FamilyControllers>,
     mut walls: Query<&mut Segment, With<Wall>>,
 ) {
     for FromClient { client_id, event } in request_events.read().copied() {
@@ -112,12 +117,23 @@ fn apply_command(
         match event.command {
             WallCommand::Create {
                 city_entity,
+                family_entity,
                 segment,
             } => {
+                if let Some(family_entity) = family_entity {
+                    if !controllers.controls(client_id, family_entity) {
+                        error!("`{client_id:?}` tried to create wall for another family");
+                        continue;
+                    }
+                }
+
                 info!("`{client_id:?}` creates wall");
                 commands.entity(city_entity).with_children(|parent| {
-                    let entity = parent.spawn((Wall, segment)).id();
-                    confirmation.entity = Some(entity);
+                    let mut entity = parent.spawn((Wall, segment));
+                    if let Some(family_entity) = family_entity {
+                        entity.insert(Owner { family_entity });
+                    }
+                    confirmation.entity = Some(entity.id());
                 });
             }
             WallCommand::EditPoint {
@@ -176,6 +192,7 @@ impl WallTool {
 #[require(
     Name(|| Name::new("Wall")),
     Segment,
+    FlatFootprint(|| FlatFootprint { half_width: wall_mesh::HALF_WIDTH }),
     Apertures,
     ParentSync,
     Replicated,
@@ -261,6 +278,10 @@ pub(crate) struct Aperture {
 enum WallCommand {
     Create {
         city_entity: Entity,
+        /// Family that will own the wall.
+        ///
+        /// [`None`] for walls without an owner.
+        family_entity: Option<Entity>,
         segment: Segment,
     },
     EditPoint {
@@ -299,8 +320,10 @@ impl PendingCommand for WallCommand {
                 let entity = world.entity(entity);
                 let segment = *entity.get::<Segment>().unwrap();
                 let city_entity = **entity.get::<Parent>().unwrap();
+                let owner = entity.get::<Owner>();
                 Self::Create {
                     city_entity,
+                    family_entity: owner.map(|owner| owner.family_entity),
                     segment,
                 }
             }
@@ -332,7 +355,14 @@ impl ConfirmableCommand for WallCommand {
 impl MapEntities for WallCommand {
     fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
         match self {
-            Self::Create { .. } => (),
+            Self::Create {
+                city_entity,
+                family_entity,
+                ..
+            } => {
+                *city_entity = entity_mapper.map_entity(*city_entity);
+                *family_entity = family_entity.map(|entity| entity_mapper.map_entity(entity));
+            }
             Self::EditPoint { entity, .. } => *entity = entity_mapper.map_entity(*entity),
             Self::Delete { entity } => *entity = entity_mapper.map_entity(*entity),
         };
@@ -14,7 +14,10 @@ use crate::{
     game_world::{
         city::ActiveCity,
         commands_history::{CommandsHistory, PendingDespawn},
-        family::building::{wall::Apertures, BuildingMode},
+        family::{
+            building::{wall::Apertures, BuildingMode},
+            SelectedFamily,
+        },
         segment::{
             placing_segment::{ConfirmSegment, DeleteSegment, PlacingSegment},
             ruler::Ruler,
@@ -188,6 +191,7 @@ fn confirm(
     mut commands: Commands,
     mut history: CommandsHistory,
     placing_wall: Single<(&Parent, &PlacingWall, &Segment, &PlacingSegment)>,
+    selected_family: Single<Entity, With<SelectedFamily>>,
 ) {
     let (parent, &placing_wall, &segment, placing_segment) = *placing_wall;
 
@@ -195,6 +199,7 @@ fn confirm(
     let command_id = match placing_wall {
         PlacingWall::Spawning => history.push_pending(WallCommand::Create {
             city_entity: **parent,
+            family_entity: Some(*selected_family),
             segment,
         }),
         PlacingWall::EditingPoint { entity } => {
@@ -232,6 +237,7 @@ fn confirm(
             Layer::PlacingObject,
             Layer::Road,
             Layer::PlacingRoad,
+            Layer::Water,
         ],
     ))
 )]
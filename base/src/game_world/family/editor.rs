@@ -1,9 +1,9 @@
 use std::fmt::Write;
 
-use bevy::prelude::*;
+use bevy::{asset::AssetPath, prelude::*};
 
 use crate::game_world::{
-    actor::{human::EditorHuman, SelectedActor},
+    actor::{appearance::Appearance, human::EditorHuman, SelectedActor},
     family::{FamilyMembers, SelectedFamilyCreated},
     player_camera::PlayerCamera,
     WorldState,
@@ -111,7 +111,15 @@ pub struct EditorFamily;
 
 /// Component for a actor inside the editor.
 #[derive(Component, Default)]
-#[require(EditorFirstName, EditorLastName, EditorSex, SceneRoot, EditorHuman)] // TODO: Select race.
+#[require(
+    EditorFirstName,
+    EditorLastName,
+    EditorSex,
+    EditorTraits,
+    EditorAppearance,
+    SceneRoot,
+    EditorHuman
+)] // TODO: Select race.
 pub struct EditorActor;
 
 #[derive(Component, Default, Deref, DerefMut, Clone)]
@@ -127,6 +135,19 @@ pub enum EditorSex {
     Female,
 }
 
+/// Paths to selected trait manifests.
+#[derive(Component, Default, Deref, DerefMut, Clone)]
+pub struct EditorTraits(pub Vec<AssetPath<'static>>);
+
+#[derive(Component, Default, Deref, DerefMut, Clone)]
+pub struct EditorAppearance(pub Appearance);
+
+impl AsRef<Appearance> for EditorAppearance {
+    fn as_ref(&self) -> &Appearance {
+        &self.0
+    }
+}
+
 /// Event that resets currently editing family.
 #[derive(Event)]
 pub struct EditorFamilyReset;
@@ -1,72 +1,128 @@
+mod avoidance;
 pub(super) mod following;
+pub(crate) mod nav_area;
+pub mod nav_tiles;
 pub(super) mod path_debug;
 
-use bevy::prelude::*;
+use std::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::{ecs::entity::MapEntities, prelude::*};
 use bevy_replicon::prelude::*;
 use path_debug::PathDebugPlugin;
 use serde::{Deserialize, Serialize};
 use vleue_navigator::prelude::*;
 
-use crate::game_world::city::CityNavMesh;
-use following::FollowingPlugin;
+use crate::game_world::{
+    actor::{aging::LifeStage, job::AtWork, travel::Traveling, ACTOR_RADIUS},
+    city::{terrain::Heightmap, weather::Weather},
+    object::door::LockedDoors,
+};
+use avoidance::Agent;
+use following::{Following, FollowingPlugin};
+use nav_area::{NavAreaPlugin, NavAreas};
+use nav_tiles::{CityTiles, NavTilesPlugin, ObstacleTiles};
 
 pub(super) struct NavigationPlugin;
 
 impl Plugin for NavigationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((FollowingPlugin, PathDebugPlugin))
-            .register_type::<Navigation>()
-            .register_type::<NavDestination>()
-            .replicate::<Navigation>()
-            .replicate::<NavDestination>()
-            .replicate::<NavPath>()
-            .add_systems(
-                PreUpdate,
-                (update_paths, generate_paths)
-                    .chain()
-                    .after(ClientSet::Receive)
-                    .run_if(server_or_singleplayer),
-            )
-            .add_systems(Update, navigate.run_if(server_or_singleplayer));
+        app.add_plugins((
+            FollowingPlugin,
+            NavAreaPlugin,
+            NavTilesPlugin,
+            PathDebugPlugin,
+        ))
+        .register_type::<Navigation>()
+        .register_type::<NavDestination>()
+        .register_type::<Obstacle>()
+        .replicate::<Navigation>()
+        .replicate::<NavDestination>()
+        .replicate::<NavPath>()
+        .add_mapped_server_event::<PathFailed>(ChannelKind::Unordered)
+        .add_observer(propagate_obstacle)
+        .add_systems(
+            PreUpdate,
+            (retry_paths, update_paths, generate_paths)
+                .chain()
+                .after(ClientSet::Receive)
+                .run_if(server_or_singleplayer),
+        )
+        .add_systems(Update, navigate.run_if(server_or_singleplayer));
     }
 }
 
 /// Updates path on navmesh changes.
+///
+/// Navmesh changes when walls or obstacle objects are placed, so agents replan around them.
 fn update_paths(
-    mut navmeshes: ResMut<Assets<NavMesh>>,
-    city_navmeshes: Query<(&ManagedNavMesh, &Parent, &NavMeshStatus), Changed<NavMeshStatus>>,
-    children: Query<&Children>,
-    mut agents: Query<(
-        Entity,
-        &Transform,
-        &mut NavDestination,
-        &mut NavPath,
-        &mut NavPathIndex,
-    )>,
+    mut failed_events: EventWriter<ToClients<PathFailed>>,
+    city_tiles: CityTiles,
+    tiles: Query<(&Parent, &NavMeshStatus), Changed<NavMeshStatus>>,
+    cities: Query<(&Children, &Heightmap)>,
+    areas: NavAreas,
+    locked_doors: LockedDoors,
+    mut agents: Query<
+        (
+            Entity,
+            &Transform,
+            &Navigation,
+            &mut NavDestination,
+            &mut NavPath,
+            &mut NavPathIndex,
+            &mut PathRetry,
+        ),
+        (Without<AtWork>, Without<Traveling>),
+    >,
 ) {
-    for (navmesh_handle, parent, status) in &city_navmeshes {
-        if !matches!(status, NavMeshStatus::Built) {
-            continue;
-        }
+    let mut changed_cities: Vec<_> = tiles
+        .iter()
+        .filter(|(_, status)| matches!(status, NavMeshStatus::Built))
+        .map(|(parent, _)| **parent)
+        .collect();
+    changed_cities.sort();
+    changed_cities.dedup();
 
-        let Some(navmesh) = navmeshes.get_mut(navmesh_handle) else {
+    for city_entity in changed_cities {
+        let navmesh = city_tiles.get(city_entity);
+        if !navmesh.is_ready() {
             continue;
-        };
+        }
 
-        let children = children.get(**parent).unwrap();
+        let (children, heightmap) = cities.get(city_entity).unwrap();
+        let area_map = areas.map(city_entity);
         let mut iter = agents.iter_many_mut(children);
-        while let Some((entity, transform, mut dest, mut path, mut path_index)) = iter.fetch_next()
+        while let Some((
+            entity,
+            transform,
+            navigation,
+            mut dest,
+            mut path,
+            mut path_index,
+            mut retry,
+        )) = iter.fetch_next()
         {
             let Some(endpoint) = **dest else {
                 continue;
             };
 
-            if let Some(transformed) = navmesh.transformed_path(transform.translation, endpoint) {
+            path.0.clear();
+            path_index.0 = 0;
+
+            let blocked = locked_doors.blocked_for(entity);
+            if let Some(points) = nav_area::find_path(
+                &navmesh,
+                &area_map,
+                &blocked,
+                transform.translation,
+                endpoint,
+            ) {
                 debug!("recalculating path for `{entity}`");
                 path.0.push(transform.translation);
-                path.0.extend(transformed.path);
-                path_index.0 = 0;
-            } else {
+                path.0.extend(points);
+                conform_path(&mut path.0, heightmap);
+                retry.reset();
+            } else if !retry.fail(entity, *navigation, &mut failed_events) {
                 debug!("cancelling destination for `{entity}`");
                 **dest = None;
             }
@@ -75,110 +131,258 @@ fn update_paths(
 }
 
 fn generate_paths(
-    mut navmeshes: ResMut<Assets<NavMesh>>,
-    cities: Query<&CityNavMesh>,
-    city_navmeshes: Query<&ManagedNavMesh>,
+    mut failed_events: EventWriter<ToClients<PathFailed>>,
+    city_tiles: CityTiles,
+    cities: Query<&Heightmap>,
+    areas: NavAreas,
+    locked_doors: LockedDoors,
     mut agents: Query<
         (
             Entity,
             &Parent,
             &Transform,
+            &Navigation,
             &mut NavDestination,
             &mut NavPath,
             &mut NavPathIndex,
+            &mut PathRetry,
         ),
-        Changed<NavDestination>,
+        (Changed<NavDestination>, Without<AtWork>, Without<Traveling>),
     >,
 ) {
-    for (entity, parent, transform, mut dest, mut path, mut path_index) in &mut agents {
+    for (entity, parent, transform, navigation, mut dest, mut path, mut path_index, mut retry) in
+        &mut agents
+    {
         path.0.clear();
         path_index.0 = 0;
 
         let Some(endpoint) = **dest else {
+            retry.reset();
             continue;
         };
 
-        let navmesh_entity = cities
+        let heightmap = cities
             .get(**parent)
             .expect("all agents should have city as parents");
-        let navmesh_handle = city_navmeshes
-            .get(**navmesh_entity)
-            .expect("city navmesh should always be valid");
-
-        let Some(navmesh) = navmeshes.get_mut(navmesh_handle) else {
+        let navmesh = city_tiles.get(**parent);
+        if !navmesh.is_ready() {
+            // The path will be calculated after building.
             continue;
-        };
+        }
 
-        if let Some(transformed) = navmesh.transformed_path(transform.translation, endpoint) {
+        let area_map = areas.map(**parent);
+        let blocked = locked_doors.blocked_for(entity);
+        if let Some(points) = nav_area::find_path(
+            &navmesh,
+            &area_map,
+            &blocked,
+            transform.translation,
+            endpoint,
+        ) {
             debug!("calculating path for `{entity}`");
             path.0.push(transform.translation);
-            path.0.extend(transformed.path);
-        } else {
+            path.0.extend(points);
+            conform_path(&mut path.0, heightmap);
+            retry.reset();
+        } else if !retry.fail(entity, *navigation, &mut failed_events) {
             debug!("refusing destination for `{entity}`");
             **dest = None;
         }
     }
 }
 
+/// Repeats path search for agents whose retry delay passed.
+fn retry_paths(time: Res<Time>, mut agents: Query<(Entity, &mut PathRetry, &mut NavDestination)>) {
+    for (entity, mut retry, mut dest) in &mut agents {
+        let Some(timer) = &mut retry.timer else {
+            continue;
+        };
+
+        timer.tick(time.delta());
+        if timer.finished() {
+            debug!(
+                "retrying path for `{entity}` after {} failed attempts",
+                retry.attempts
+            );
+            retry.timer = None;
+            dest.set_changed();
+        }
+    }
+}
+
+/// Marks scene colliders of obstacle objects as obstacles.
+///
+/// Objects can't be obstacles themselves since colliders are generated for their scene meshes.
+fn propagate_obstacle(
+    trigger: Trigger<OnAdd, Collider>,
+    mut commands: Commands,
+    parents: Query<&Parent>,
+    obstacles: Query<(), With<Obstacle>>,
+) {
+    if obstacles.get(trigger.entity()).is_ok() {
+        return;
+    }
+
+    if parents
+        .iter_ancestors(trigger.entity())
+        .any(|entity| obstacles.get(entity).is_ok())
+    {
+        debug!("marking collider `{}` as obstacle", trigger.entity());
+        commands.entity(trigger.entity()).insert(Obstacle);
+    }
+}
+
+/// Places path points on the terrain.
+fn conform_path(path: &mut [Vec3], heightmap: &Heightmap) {
+    for point in path {
+        point.y = heightmap.height_at(point.xz());
+    }
+}
+
+/// Moves agents along their paths.
+///
+/// Agents steer around nearby agents in the same city, but never leave the navmesh.
+/// The followed entity is not avoided to let the agent reach it.
 fn navigate(
     time: Res<Time>,
-    mut agents: Query<(
-        Entity,
-        &Navigation,
-        &NavPath,
-        &mut NavPathIndex,
-        &mut NavDestination,
-        &mut Transform,
-    )>,
+    city_tiles: CityTiles,
+    cities: Query<(&Heightmap, &Weather)>,
+    mut agents: Query<
+        (
+            Entity,
+            &Parent,
+            &Navigation,
+            &NavPath,
+            &mut NavPathIndex,
+            &mut NavDestination,
+            &mut NavVelocity,
+            &mut Transform,
+            Option<&LifeStage>,
+            Option<&Following>,
+        ),
+        (Without<AtWork>, Without<Traveling>),
+    >,
 ) {
-    for (entity, &navigation, path, mut path_index, mut dest, mut transform) in &mut agents {
+    let snapshot: Vec<_> = agents
+        .iter()
+        .map(|(entity, parent, .., velocity, transform, _, _)| {
+            let agent = Agent {
+                position: transform.translation.xz(),
+                velocity: **velocity,
+                radius: ACTOR_RADIUS,
+            };
+            (entity, **parent, agent)
+        })
+        .collect();
+
+    for (
+        entity,
+        parent,
+        &navigation,
+        path,
+        mut path_index,
+        mut dest,
+        mut velocity,
+        mut transform,
+        stage,
+        following,
+    ) in &mut agents
+    {
         if dest.is_none() || path.is_empty() {
+            velocity.set_if_neq(NavVelocity::default());
             continue;
         }
 
+        let (heightmap, weather) = cities
+            .get(**parent)
+            .expect("all agents should have city as parents");
+        let mut navigation = navigation;
+        navigation.speed *= weather.speed_factor();
+        if let Some(stage) = stage {
+            navigation.speed *= stage.speed_factor();
+        }
+
         let target_index = **path_index + 1;
-        if let Some(passed_points) = move_agent(
-            &mut transform,
+        let delta = time.delta_secs();
+        let Some((passed_points, target_point)) = next_point(
+            transform.translation,
             navigation,
             &path[target_index..],
-            time.delta_secs(),
-        ) {
-            if passed_points != 0 {
-                **path_index += passed_points;
-                debug!(
-                    "advancing path index to {}/{} for `{entity}`",
-                    **path_index,
-                    path.len() - 1,
-                );
-            }
-        } else {
+            delta,
+        ) else {
             debug!("`{entity}` finished navigation");
             **dest = None;
+            velocity.set_if_neq(NavVelocity::default());
+            continue;
+        };
+
+        let preferred = (target_point - transform.translation)
+            .xz()
+            .normalize_or_zero()
+            * navigation.speed;
+        let neighbours: Vec<_> = snapshot
+            .iter()
+            .filter(|&&(neighbour_entity, city_entity, _)| {
+                neighbour_entity != entity
+                    && city_entity == **parent
+                    && following.is_none_or(|following| following.0 != neighbour_entity)
+            })
+            .map(|&(.., agent)| agent)
+            .collect();
+        let agent = Agent {
+            position: transform.translation.xz(),
+            velocity: **velocity,
+            radius: ACTOR_RADIUS,
+        };
+        let mut steered = avoidance::steer(agent, preferred, &neighbours);
+        if steered != preferred {
+            let position = transform.translation + to_3d(steered) * delta;
+            if !city_tiles.get(**parent).is_in_mesh(position) {
+                trace!("ignoring steering for `{entity}` to stay on navmesh");
+                steered = preferred;
+            }
+        }
+
+        move_agent(&mut transform, steered, delta);
+        transform.translation.y = heightmap.height_at(transform.translation.xz());
+        velocity.set_if_neq(NavVelocity(steered));
+
+        if passed_points != 0 {
+            **path_index += passed_points;
+            debug!(
+                "advancing path index to {}/{} for `{entity}`",
+                **path_index,
+                path.len() - 1,
+            );
         }
     }
 }
 
 /// Marks an entity with [`Collider`] as a navigation mesh affector.
-#[derive(Component, Default)]
+///
+/// Can be specified in object manifests, in this case it's propagated to the scene colliders.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+#[require(ObstacleTiles)]
 pub struct Obstacle;
 
-/// Moves the agent along a path.
+/// Returns the next point from the path to move to.
 ///
 /// The path should contain only the remaining points to reach.
 ///
 /// Skips points that actor have projected past to prevent jitter
 /// when multiple points are near each other.
 ///
-/// Returns the number of points passed.
+/// Returns the number of points passed with the target point.
 /// If the path is completed, returns [`None`].
-fn move_agent(
-    transform: &mut Transform,
+fn next_point(
+    translation: Vec3,
     navigation: Navigation,
     path: &[Vec3],
     delta: f32,
-) -> Option<usize> {
+) -> Option<(usize, Vec3)> {
     let movement_step = navigation.speed * delta;
-    let (passed_points, &target_point) = path.iter().enumerate().find(|&(index, &point)| {
+    path.iter().copied().enumerate().find(|&(index, point)| {
         const EPSILON: f32 = 0.1;
         let tolerance = if index == path.len() - 1 {
             // Apply the desired offset for the last point.
@@ -187,30 +391,42 @@ fn move_agent(
             EPSILON
         };
 
-        transform.translation.distance(point) - movement_step > tolerance
-    })?;
+        translation.distance(point) - movement_step > tolerance
+    })
+}
 
-    let disp = target_point - transform.translation;
-    let target_rotation = transform.looking_to(disp, Vec3::Y).rotation;
-    const ROTATION_SPEED: f32 = 10.0;
-    transform.translation += disp.normalize() * movement_step;
-    transform.rotation = transform
-        .rotation
-        .slerp(target_rotation, ROTATION_SPEED * delta);
+/// Moves the agent with the specified ground velocity and turns it towards the movement direction.
+///
+/// Elevation is ignored to keep the agent upright on slopes.
+fn move_agent(transform: &mut Transform, velocity: Vec2, delta: f32) {
+    transform.translation += to_3d(velocity) * delta;
+    if velocity != Vec2::ZERO {
+        let target_rotation = transform.looking_to(to_3d(velocity), Vec3::Y).rotation;
+        const ROTATION_SPEED: f32 = 10.0;
+        transform.rotation = transform
+            .rotation
+            .slerp(target_rotation, ROTATION_SPEED * delta);
+    }
+}
 
-    Some(passed_points)
+/// Converts a vector on the ground plane into 3D.
+fn to_3d(value: Vec2) -> Vec3 {
+    Vec3::new(value.x, 0.0, value.y)
 }
 
 /// Navigation parameters.
 #[derive(Component, Clone, Copy, Default, Reflect, Serialize, Deserialize)]
 #[reflect(Component)]
-#[require(NavDestination, NavPath)]
+#[require(NavDestination, NavPath, NavVelocity, PathRetry)]
 pub(super) struct Navigation {
     /// Movement speed.
     speed: f32,
 
     /// Offset for the target point.
     offset: Option<f32>,
+
+    /// Keep retrying path search after [`PathRetry::MAX_ATTEMPTS`] instead of cancelling the destination.
+    wait_on_failure: bool,
 }
 
 impl Navigation {
@@ -218,6 +434,7 @@ impl Navigation {
         Self {
             speed,
             offset: None,
+            wait_on_failure: false,
         }
     }
 
@@ -226,6 +443,14 @@ impl Navigation {
         self
     }
 
+    /// Makes the agent wait until the destination becomes reachable.
+    ///
+    /// Used by tasks that can't be completed without reaching the destination.
+    pub(super) fn with_waiting(mut self) -> Self {
+        self.wait_on_failure = true;
+        self
+    }
+
     pub(super) fn speed(&self) -> f32 {
         self.speed
     }
@@ -254,3 +479,192 @@ pub(super) struct NavPath(Vec<Vec3>);
 /// Resets to 0 each time [`NavPath`] changes.
 #[derive(Component, Default, Serialize, Deserialize, Deref, DerefMut)]
 struct NavPathIndex(usize);
+
+/// Current velocity of an agent on the ground plane.
+///
+/// Used by nearby agents for collision avoidance.
+/// Zero when the agent is not moving. Updated only on server.
+#[derive(Component, Clone, Copy, Default, Deref, PartialEq)]
+struct NavVelocity(Vec2);
+
+/// Failed path search attempts for the current destination.
+///
+/// Exists only on server.
+#[derive(Component, Default)]
+struct PathRetry {
+    attempts: u8,
+
+    /// Delay before the next attempt.
+    timer: Option<Timer>,
+}
+
+impl PathRetry {
+    const MAX_ATTEMPTS: u8 = 3;
+
+    /// Delay after the first failure, doubled after each next one.
+    const DELAY: Duration = Duration::from_secs(2);
+    const MAX_DELAY: Duration = Duration::from_secs(30);
+
+    /// Registers a failed attempt and schedules the next one.
+    ///
+    /// Clients are notified only on the first failure and when the destination is cancelled.
+    /// Returns `true` if the search will be retried.
+    fn fail(
+        &mut self,
+        entity: Entity,
+        navigation: Navigation,
+        failed_events: &mut EventWriter<ToClients<PathFailed>>,
+    ) -> bool {
+        self.attempts = self.attempts.saturating_add(1);
+        let retrying = navigation.wait_on_failure || self.attempts < Self::MAX_ATTEMPTS;
+        debug!(
+            "path for `{entity}` not found after {} attempts, retrying: {retrying}",
+            self.attempts
+        );
+        if self.attempts == 1 || !retrying {
+            failed_events.send(ToClients {
+                mode: SendMode::Broadcast,
+                event: PathFailed {
+                    actor_entity: entity,
+                    retrying,
+                },
+            });
+        }
+
+        if retrying {
+            let delay = Self::DELAY
+                .saturating_mul(1 << (self.attempts - 1).min(31))
+                .min(Self::MAX_DELAY);
+            self.timer = Some(Timer::new(delay, TimerMode::Once));
+        } else {
+            self.reset();
+        }
+
+        retrying
+    }
+
+    fn reset(&mut self) {
+        self.attempts = 0;
+        self.timer = None;
+    }
+}
+
+/// An event from server which indicates that a path to the actor destination can't be found.
+#[derive(Clone, Copy, Deserialize, Event, Serialize)]
+pub struct PathFailed {
+    pub actor_entity: Entity,
+
+    /// Whether the search will be repeated later.
+    ///
+    /// If `false`, the destination was cancelled.
+    pub retrying: bool,
+}
+
+impl MapEntities for PathFailed {
+    fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
+        self.actor_entity = entity_mapper.map_entity(self.actor_entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_world::test_app::{self, GameTestAppExt};
+
+    #[test]
+    fn walking() {
+        let mut app = test_app::new_app();
+        app.start_game();
+        let city_entity = app.spawn_city();
+        let (_, actors) = app.spawn_family(city_entity, &["Walker"]);
+        let actor_entity = actors[0];
+
+        let destination = Vec3::new(5.0, 0.0, 5.0);
+        app.walk_to(actor_entity, destination);
+        app.update_until("walking", |world| {
+            world.get::<NavDestination>(actor_entity).unwrap().is_none()
+        });
+
+        let transform = app.world().get::<Transform>(actor_entity).unwrap();
+        assert!(transform.translation.xz().distance(destination.xz()) < 0.1);
+    }
+
+    #[test]
+    fn walking_around_obstacle() {
+        let mut app = test_app::new_app();
+        app.start_game();
+        let city_entity = app.spawn_city();
+        let (_, actors) = app.spawn_family(city_entity, &["Walker"]);
+        let actor_entity = actors[0];
+
+        let center = Vec3::new(3.0, 1.0, 0.0);
+        app.world_mut()
+            .spawn((
+                Obstacle,
+                Collider::cuboid(2.0, 2.0, 2.0),
+                Transform::from_translation(center),
+            ))
+            .set_parent(city_entity);
+        app.wait_for_navmesh(city_entity);
+
+        let destination = Vec3::new(6.0, 0.0, 0.0);
+        app.walk_to(actor_entity, destination);
+        app.update_until("walking", |world| {
+            let translation = world.get::<Transform>(actor_entity).unwrap().translation;
+            let offset = (translation - center).xz().abs();
+            assert!(
+                offset.x > 1.0 || offset.y > 1.0,
+                "actor shouldn't walk through the obstacle"
+            );
+            world.get::<NavDestination>(actor_entity).unwrap().is_none()
+        });
+
+        let transform = app.world().get::<Transform>(actor_entity).unwrap();
+        assert!(transform.translation.xz().distance(destination.xz()) < 0.1);
+    }
+
+    #[test]
+    fn walking_around_tile_border() {
+        let mut app = test_app::new_app();
+        app.start_game();
+        let city_entity = app.spawn_city();
+        let (_, actors) = app.spawn_family(city_entity, &["Walker"]);
+        let actor_entity = actors[0];
+
+        // Covers the whole border between two tiles, so the path has to go through a third one.
+        let border = nav_tiles::tile_rect(nav_tiles::tile_of(Vec2::ONE));
+        let half_length = nav_tiles::TILE_SIZE / 2.0 + 1.0;
+        app.world_mut()
+            .spawn((
+                Obstacle,
+                Collider::cuboid(0.2, 2.0, half_length * 2.0),
+                Transform::from_xyz(border.min.x, 1.0, border.center().y),
+            ))
+            .set_parent(city_entity);
+        app.wait_for_navmesh(city_entity);
+
+        let start = Vec3::new(border.min.x - 3.0, 0.0, border.center().y);
+        app.world_mut()
+            .get_mut::<Transform>(actor_entity)
+            .unwrap()
+            .translation = start;
+
+        let destination = Vec3::new(border.min.x + 3.0, 0.0, border.center().y);
+        app.walk_to(actor_entity, destination);
+        let mut previous = start;
+        app.update_until("walking", |world| {
+            let translation = world.get::<Transform>(actor_entity).unwrap().translation;
+            if (previous.x < border.min.x) != (translation.x < border.min.x) {
+                assert!(
+                    (translation.z - border.center().y).abs() > half_length,
+                    "actor shouldn't walk through the wall"
+                );
+            }
+            previous = translation;
+            world.get::<NavDestination>(actor_entity).unwrap().is_none()
+        });
+
+        let transform = app.world().get::<Transform>(actor_entity).unwrap();
+        assert!(transform.translation.xz().distance(destination.xz()) < 0.1);
+    }
+}
@@ -0,0 +1,231 @@
+//! Reciprocal velocity obstacles for local collision avoidance between agents.
+//!
+//! Each agent picks a velocity close to the preferred one that doesn't collide
+//! with neighbours in [`TIME_HORIZON`] assuming that moving neighbours take half of the responsibility.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+/// How far in time collisions are considered.
+const TIME_HORIZON: f32 = 2.0;
+
+/// Weight of the time-to-collision penalty relative to the deviation from the preferred velocity.
+const COLLISION_WEIGHT: f32 = 1.5;
+
+/// Number of sampled directions on each side of the preferred velocity.
+const SIDE_SAMPLES: usize = 6;
+
+/// Maximum distance at which agents are considered neighbours.
+pub(super) const NEIGHBOUR_DISTANCE: f32 = 3.0;
+
+/// State of an agent on the ground plane.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Agent {
+    pub(super) position: Vec2,
+    pub(super) velocity: Vec2,
+    pub(super) radius: f32,
+}
+
+/// Returns a velocity close to the preferred one that avoids collisions with neighbours.
+///
+/// Candidates are sampled around the preferred velocity, clockwise samples go first,
+/// which makes agents that move towards each other pass on the same side.
+/// The result is deterministic for the same input.
+pub(super) fn steer(agent: Agent, preferred: Vec2, neighbours: &[Agent]) -> Vec2 {
+    let nearby: Vec<_> = neighbours
+        .iter()
+        .filter(|neighbour| {
+            neighbour.position.distance(agent.position)
+                < NEIGHBOUR_DISTANCE + agent.radius + neighbour.radius
+        })
+        .collect();
+    if nearby.is_empty() || preferred == Vec2::ZERO {
+        return preferred;
+    }
+
+    let mut best_velocity = preferred;
+    let mut best_penalty = f32::INFINITY;
+    for candidate in candidates(preferred) {
+        let min_time = nearby
+            .iter()
+            .filter_map(|neighbour| {
+                let relative_velocity = if neighbour.velocity == Vec2::ZERO {
+                    // Standing agents don't avoid, take the full responsibility.
+                    candidate
+                } else {
+                    // Reciprocal velocity obstacle: each agent takes half of the avoidance.
+                    2.0 * candidate - agent.velocity - neighbour.velocity
+                };
+                time_to_collision(
+                    neighbour.position - agent.position,
+                    relative_velocity,
+                    agent.radius + neighbour.radius,
+                )
+            })
+            .fold(f32::INFINITY, f32::min);
+
+        let collision_penalty = if min_time < TIME_HORIZON {
+            COLLISION_WEIGHT / min_time.max(f32::EPSILON)
+        } else {
+            0.0
+        };
+        let penalty = collision_penalty + candidate.distance(preferred);
+        if penalty < best_penalty {
+            best_penalty = penalty;
+            best_velocity = candidate;
+        }
+    }
+
+    best_velocity
+}
+
+/// Returns velocities to consider in order of preference.
+fn candidates(preferred: Vec2) -> impl Iterator<Item = Vec2> {
+    let angle_step = FRAC_PI_2 / SIDE_SAMPLES as f32;
+    [1.0, 0.5]
+        .into_iter()
+        .flat_map(move |scale| {
+            let velocity = preferred * scale;
+            (0..=SIDE_SAMPLES).flat_map(move |index| {
+                let angle = angle_step * index as f32;
+                let clockwise = Vec2::from_angle(-angle).rotate(velocity);
+                let counterclockwise = Vec2::from_angle(angle).rotate(velocity);
+                [clockwise, counterclockwise]
+                    .into_iter()
+                    .take(if index == 0 { 1 } else { 2 })
+            })
+        })
+        .chain([Vec2::ZERO])
+}
+
+/// Returns time until a circle of the specified radius at the relative position
+/// will be reached when moving with the relative velocity.
+///
+/// Returns 0 if the circle is already reached and [`None`] if it won't be reached.
+fn time_to_collision(position: Vec2, velocity: Vec2, radius: f32) -> Option<f32> {
+    let c = position.length_squared() - radius * radius;
+    if c < 0.0 {
+        return Some(0.0);
+    }
+
+    let a = velocity.length_squared();
+    let b = position.dot(velocity);
+    if a == 0.0 || b <= 0.0 {
+        // Not moving or moving away.
+        return None;
+    }
+
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    Some((b - discriminant.sqrt()) / a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIUS: f32 = 0.4;
+
+    #[test]
+    fn collision_time() {
+        let time = time_to_collision(Vec2::new(2.0, 0.0), Vec2::X, 1.0).unwrap();
+        assert!((time - 1.0).abs() < 1e-5);
+
+        assert_eq!(time_to_collision(Vec2::ZERO, Vec2::X, 1.0), Some(0.0));
+        assert_eq!(time_to_collision(Vec2::new(2.0, 0.0), -Vec2::X, 1.0), None);
+        assert_eq!(time_to_collision(Vec2::new(2.0, 0.0), Vec2::Y, 1.0), None);
+        assert_eq!(
+            time_to_collision(Vec2::new(2.0, 0.0), Vec2::ZERO, 1.0),
+            None
+        );
+    }
+
+    #[test]
+    fn no_neighbours() {
+        let agent = agent(Vec2::ZERO, Vec2::X);
+        assert_eq!(steer(agent, Vec2::X, &[]), Vec2::X);
+
+        let distant = self::agent(Vec2::new(10.0, 0.0), -Vec2::X);
+        assert_eq!(steer(agent, Vec2::X, &[distant]), Vec2::X);
+    }
+
+    #[test]
+    fn neighbour_behind() {
+        let agent = agent(Vec2::ZERO, Vec2::X);
+        let neighbour = self::agent(Vec2::new(-1.5, 0.0), Vec2::ZERO);
+        assert_eq!(steer(agent, Vec2::X, &[neighbour]), Vec2::X);
+    }
+
+    #[test]
+    fn head_on() {
+        let first = agent(Vec2::ZERO, Vec2::X);
+        let second = agent(Vec2::new(2.0, 0.0), -Vec2::X);
+
+        let first_velocity = steer(first, first.velocity, &[second]);
+        let second_velocity = steer(second, second.velocity, &[first]);
+
+        assert!(first_velocity.x > 0.0, "should keep moving forward");
+        assert!(second_velocity.x < 0.0, "should keep moving forward");
+        assert!(
+            first_velocity.y * second_velocity.y < 0.0,
+            "agents should pass each other on the same side: {first_velocity}, {second_velocity}"
+        );
+    }
+
+    #[test]
+    fn static_obstacle() {
+        let agent = agent(Vec2::ZERO, Vec2::X);
+        let neighbour = self::agent(Vec2::new(1.5, 0.0), Vec2::ZERO);
+
+        let velocity = steer(agent, Vec2::X, &[neighbour]);
+        assert_ne!(velocity, Vec2::X, "should deviate");
+        let collision = time_to_collision(
+            neighbour.position - agent.position,
+            velocity - neighbour.velocity,
+            2.0 * RADIUS,
+        );
+        assert!(
+            collision.is_none_or(|time| time >= TIME_HORIZON),
+            "shouldn't collide with {velocity}"
+        );
+    }
+
+    #[test]
+    fn simulation() {
+        const DELTA: f32 = 0.1;
+
+        let mut first = agent(Vec2::ZERO, Vec2::X);
+        let mut second = agent(Vec2::new(6.0, 0.1), -Vec2::X);
+        for _ in 0..100 {
+            let first_velocity = steer(first, Vec2::X, &[second]);
+            let second_velocity = steer(second, -Vec2::X, &[first]);
+
+            first.velocity = first_velocity;
+            second.velocity = second_velocity;
+            first.position += first_velocity * DELTA;
+            second.position += second_velocity * DELTA;
+
+            assert!(
+                first.position.distance(second.position) >= first.radius + second.radius,
+                "agents shouldn't overlap at {} and {}",
+                first.position,
+                second.position
+            );
+        }
+
+        assert!(first.position.x > 6.0, "first agent should pass");
+        assert!(second.position.x < 0.0, "second agent should pass");
+    }
+
+    fn agent(position: Vec2, velocity: Vec2) -> Agent {
+        Agent {
+            position,
+            velocity,
+            radius: RADIUS,
+        }
+    }
+}
@@ -0,0 +1,609 @@
+//! Navigation areas with different walking costs.
+//!
+//! Areas are rasterized per city and re-rasterized only where roads, walls or footprints change.
+//! Navmesh can't store costs, so if the direct navmesh path crosses costlier areas,
+//! the path is searched on a grid over the area raster.
+//! Points where the grid path turns become waypoints that are connected with navmesh paths.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    iter,
+};
+
+use bevy::{
+    color::palettes::css::{DARK_GRAY, GREEN, LIGHT_GRAY, SANDY_BROWN},
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use itertools::Itertools;
+
+use super::nav_tiles::TiledNavMesh;
+use crate::game_world::{
+    city::{road::RoadData, weather, City, HALF_CITY_SIZE},
+    family::building::wall::Wall,
+    segment::Segment,
+};
+
+pub(super) struct NavAreaPlugin;
+
+impl Plugin for NavAreaPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<NavArea>()
+            .register_type::<AreaFootprint>()
+            .register_required_components::<RoadData, AreaBounds>()
+            .register_required_components::<Wall, AreaBounds>()
+            .add_observer(init_raster)
+            .add_observer(mark_removed)
+            .add_systems(
+                PostUpdate,
+                (mark_roads, mark_walls, mark_footprints, rasterize).chain(),
+            );
+    }
+}
+
+/// Size of a cell for the area raster and the weighted search.
+const CELL_SIZE: f32 = 1.0;
+
+/// Number of raster cells along each city side.
+const CELLS_PER_SIDE: u32 = (HALF_CITY_SIZE * 2.0 / CELL_SIZE) as u32;
+
+/// Distance around the direct path in which detours are searched.
+const SEARCH_MARGIN: f32 = 10.0;
+
+/// Maximum number of grid cells for the weighted search.
+///
+/// The direct navmesh path is used for larger areas.
+const MAX_CELLS: usize = 100_000;
+
+/// Distance between points at which areas are sampled along a path.
+const SAMPLE_STEP: f32 = 0.5;
+
+/// Kind of surface that affects how actors prefer to walk.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Reflect)]
+pub enum NavArea {
+    #[default]
+    Grass,
+    Sidewalk,
+    Carriageway,
+    Floor,
+}
+
+impl NavArea {
+    /// Cost of the cheapest area.
+    const MIN_COST: f32 = 1.0;
+
+    /// Multiplier for the distance walked through the area.
+    pub fn cost(self) -> f32 {
+        match self {
+            NavArea::Sidewalk | NavArea::Floor => 1.0,
+            NavArea::Grass => 1.5,
+            NavArea::Carriageway => 4.0,
+        }
+    }
+
+    pub(super) fn color(self) -> Srgba {
+        match self {
+            NavArea::Grass => GREEN,
+            NavArea::Sidewalk => LIGHT_GRAY,
+            NavArea::Carriageway => DARK_GRAY,
+            NavArea::Floor => SANDY_BROWN,
+        }
+    }
+}
+
+/// Tags a rectangle around an object with a navigation area.
+///
+/// Can be specified in object manifests.
+#[derive(Component, Clone, Copy, Default, Reflect)]
+#[reflect(Component, Default)]
+#[require(AreaBounds)]
+pub(crate) struct AreaFootprint {
+    area: NavArea,
+    half_extents: Vec2,
+}
+
+impl AreaFootprint {
+    /// Returns corners of the footprint placed with the transform.
+    fn corners(self, transform: &Transform) -> [Vec2; 4] {
+        [
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ]
+        .map(|sign| {
+            let local = (sign * self.half_extents).extend(0.0).xzy();
+            transform.transform_point(local).xz()
+        })
+    }
+
+    fn contains(self, center: Vec2, inverse: Quat, point: Vec2) -> bool {
+        let local = inverse * (point - center).extend(0.0).xzy();
+        local.x.abs() <= self.half_extents.x && local.z.abs() <= self.half_extents.y
+    }
+}
+
+/// Rectangle in the city space that the entity rasterized into [`AreaRaster`] last time.
+#[derive(Component, Default, Deref, DerefMut)]
+pub(super) struct AreaBounds(Option<Rect>);
+
+/// Areas of a city stored per cell of [`CELL_SIZE`].
+#[derive(Component)]
+pub(super) struct AreaRaster {
+    cells: Vec<NavArea>,
+
+    /// Rectangle that needs to be rasterized again.
+    dirty: Option<Rect>,
+
+    /// Whether walls changed, which may turn any enclosed space into a floor or back.
+    walls_changed: bool,
+}
+
+impl AreaRaster {
+    fn mark_dirty(&mut self, rect: Rect) {
+        self.dirty = Some(self.dirty.map_or(rect, |dirty| dirty.union(rect)));
+    }
+}
+
+impl Default for AreaRaster {
+    fn default() -> Self {
+        Self {
+            cells: vec![NavArea::default(); (CELLS_PER_SIDE * CELLS_PER_SIDE) as usize],
+            dirty: None,
+            walls_changed: false,
+        }
+    }
+}
+
+fn init_raster(trigger: Trigger<OnAdd, City>, mut commands: Commands) {
+    commands
+        .entity(trigger.entity())
+        .insert(AreaRaster::default());
+}
+
+fn mark_roads(
+    mut cities: Query<&mut AreaRaster>,
+    mut roads: Query<
+        (&Parent, &Segment, &RoadData, &mut AreaBounds),
+        Or<(Changed<Segment>, Changed<RoadData>)>,
+    >,
+) {
+    for (parent, segment, road_data, mut bounds) in &mut roads {
+        let rect = Rect::from_corners(segment.start, segment.end).inflate(road_data.half_width);
+        mark_changed(&mut cities, **parent, &mut bounds, rect);
+    }
+}
+
+fn mark_walls(
+    mut cities: Query<&mut AreaRaster>,
+    mut walls: Query<(&Parent, &Segment, &mut AreaBounds), (With<Wall>, Changed<Segment>)>,
+) {
+    for (parent, segment, mut bounds) in &mut walls {
+        let rect = Rect::from_corners(segment.start, segment.end);
+        if let Some(mut raster) = mark_changed(&mut cities, **parent, &mut bounds, rect) {
+            raster.walls_changed = true;
+        }
+    }
+}
+
+fn mark_footprints(
+    mut cities: Query<&mut AreaRaster>,
+    mut footprints: Query<
+        (&Parent, &Transform, &AreaFootprint, &mut AreaBounds),
+        Or<(Changed<Transform>, Changed<AreaFootprint>)>,
+    >,
+) {
+    for (parent, transform, footprint, mut bounds) in &mut footprints {
+        let [a, b, c, d] = footprint.corners(transform);
+        let rect = Rect::from_corners(a, c).union_point(b).union_point(d);
+        mark_changed(&mut cities, **parent, &mut bounds, rect);
+    }
+}
+
+/// Marks both the previous and the new rectangle of the entity as dirty.
+fn mark_changed<'a>(
+    cities: &'a mut Query<&mut AreaRaster>,
+    city_entity: Entity,
+    bounds: &mut AreaBounds,
+    rect: Rect,
+) -> Option<Mut<'a, AreaRaster>> {
+    let mut raster = cities.get_mut(city_entity).ok()?;
+    raster.mark_dirty(bounds.map_or(rect, |old_rect| old_rect.union(rect)));
+    **bounds = Some(rect);
+    Some(raster)
+}
+
+fn mark_removed(
+    trigger: Trigger<OnRemove, AreaBounds>,
+    mut cities: Query<&mut AreaRaster>,
+    entities: Query<(&Parent, &AreaBounds, Has<Wall>)>,
+) {
+    let Ok((parent, bounds, is_wall)) = entities.get(trigger.entity()) else {
+        return;
+    };
+    let (Some(rect), Ok(mut raster)) = (**bounds, cities.get_mut(**parent)) else {
+        return;
+    };
+
+    trace!("marking areas of removed `{}` as dirty", trigger.entity());
+    raster.mark_dirty(rect);
+    raster.walls_changed |= is_wall;
+}
+
+/// Rasterizes dirty rectangles of cities.
+fn rasterize(
+    mut cities: Query<(Entity, Option<&Children>, &mut AreaRaster)>,
+    roads: Query<(&Segment, &RoadData)>,
+    walls: Query<&Segment, With<Wall>>,
+    footprints: Query<(&Transform, &AreaFootprint)>,
+) {
+    for (city_entity, children, mut raster) in &mut cities {
+        let Some(mut dirty) = raster.dirty.take() else {
+            continue;
+        };
+
+        // The last child could be removed.
+        let children = children.map(|children| &**children).unwrap_or_default();
+        let city_walls: Vec<_> = walls.iter_many(children).copied().collect();
+        if raster.walls_changed {
+            // Any enclosed space lies within the walls that enclose it.
+            for wall in &city_walls {
+                dirty = dirty.union(Rect::from_corners(wall.start, wall.end));
+            }
+            raster.walls_changed = false;
+        }
+
+        let (first, last) = (cell_of(dirty.min), cell_of(dirty.max));
+        let city_roads: Vec<_> = roads
+            .iter_many(children)
+            .filter(|(segment, road_data)| {
+                !Rect::from_corners(segment.start, segment.end)
+                    .inflate(road_data.half_width)
+                    .intersect(dirty)
+                    .is_empty()
+            })
+            .collect();
+        let city_footprints: Vec<_> = footprints
+            .iter_many(children)
+            .map(|(transform, &footprint)| {
+                let inverse = transform.rotation.inverse();
+                (transform.translation.xz(), inverse, footprint)
+            })
+            .collect();
+
+        debug!(
+            "rasterizing areas of `{city_entity}` from {first} to {last} for {} walls",
+            city_walls.len()
+        );
+        for y in first.y..=last.y {
+            // Only walls that span the row affect whether its points are indoors.
+            let row_y = cell_center(UVec2::new(0, y)).y;
+            let row_walls: Vec<_> = city_walls
+                .iter()
+                .filter(|wall| (wall.start.y > row_y) != (wall.end.y > row_y))
+                .copied()
+                .collect();
+
+            for x in first.x..=last.x {
+                let cell = UVec2::new(x, y);
+                let point = cell_center(cell);
+                raster.cells[cell_index(cell)] =
+                    area_at(point, &city_footprints, &row_walls, &city_roads);
+            }
+        }
+    }
+}
+
+/// Returns area at the point.
+///
+/// Object footprints have the highest priority, then floors and roads.
+/// Everything else is grass.
+fn area_at(
+    point: Vec2,
+    footprints: &[(Vec2, Quat, AreaFootprint)],
+    walls: &[Segment],
+    roads: &[(&Segment, &RoadData)],
+) -> NavArea {
+    for &(center, inverse, footprint) in footprints {
+        if footprint.contains(center, inverse, point) {
+            return footprint.area;
+        }
+    }
+
+    if weather::is_indoors(point, walls) {
+        return NavArea::Floor;
+    }
+
+    for (segment, road_data) in roads {
+        let distance = segment.closest_point(point).distance(point);
+        if distance <= road_data.half_width {
+            if distance > road_data.half_width - road_data.sidewalk_width {
+                return NavArea::Sidewalk;
+            }
+            return NavArea::Carriageway;
+        }
+    }
+
+    NavArea::Grass
+}
+
+/// Returns raster cell that contains the point in the city space.
+///
+/// Points outside the city are clamped to the border cells.
+fn cell_of(point: Vec2) -> UVec2 {
+    ((point + HALF_CITY_SIZE) / CELL_SIZE)
+        .floor()
+        .clamp(Vec2::ZERO, Vec2::splat((CELLS_PER_SIDE - 1) as f32))
+        .as_uvec2()
+}
+
+fn cell_center(cell: UVec2) -> Vec2 {
+    (cell.as_vec2() + 0.5) * CELL_SIZE - HALF_CITY_SIZE
+}
+
+fn cell_index(cell: UVec2) -> usize {
+    (cell.y * CELLS_PER_SIDE + cell.x) as usize
+}
+
+/// Provides rasterized areas of cities.
+#[derive(SystemParam)]
+pub(super) struct NavAreas<'w, 's> {
+    cities: Query<'w, 's, &'static AreaRaster>,
+}
+
+impl NavAreas<'_, '_> {
+    pub(super) fn map(&self, city_entity: Entity) -> AreaMap<'_> {
+        let cells = self
+            .cities
+            .get(city_entity)
+            .map(|raster| &*raster.cells)
+            .unwrap_or_default();
+
+        AreaMap { cells }
+    }
+}
+
+/// Areas of a city in local coordinates.
+pub(super) struct AreaMap<'a> {
+    cells: &'a [NavArea],
+}
+
+impl AreaMap<'_> {
+    /// Returns area at the point.
+    ///
+    /// Everything outside the city is grass.
+    pub(super) fn area_at(&self, point: Vec2) -> NavArea {
+        if point.abs().cmpgt(Vec2::splat(HALF_CITY_SIZE)).any() {
+            return NavArea::Grass;
+        }
+
+        self.cells
+            .get(cell_index(cell_of(point)))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the weighted length of the path.
+    fn path_cost(&self, start: Vec3, path: &[Vec3]) -> f32 {
+        path_samples(start, path)
+            .map(|(start, end)| start.distance(end) * self.area_at(start.midpoint(end)).cost())
+            .sum()
+    }
+
+    /// Returns `true` if the path passes through any area costlier than the cheapest one.
+    fn is_costlier(&self, start: Vec3, path: &[Vec3]) -> bool {
+        path_samples(start, path)
+            .any(|(start, end)| self.area_at(start.midpoint(end)).cost() > NavArea::MIN_COST)
+    }
+}
+
+/// Splits the path into samples on the ground plane.
+fn path_samples(start: Vec3, path: &[Vec3]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    iter::once(start)
+        .chain(path.iter().copied())
+        .tuple_windows()
+        .flat_map(|(a, b)| samples(a, b))
+        .map(|(start, end)| (start.xz(), end.xz()))
+}
+
+/// Splits the segment into pieces of [`SAMPLE_STEP`] length on the ground plane.
+pub(super) fn samples(start: Vec3, end: Vec3) -> impl Iterator<Item = (Vec3, Vec3)> {
+    let count = (start.xz().distance(end.xz()) / SAMPLE_STEP)
+        .ceil()
+        .max(1.0) as usize;
+    (0..count).map(move |index| {
+        let a = start.lerp(end, index as f32 / count as f32);
+        let b = start.lerp(end, (index + 1) as f32 / count as f32);
+        (a, b)
+    })
+}
+
+/// Finds a path that prefers cheaper areas and doesn't cross blocked segments.
+///
+/// Returns intermediate points and the destination like [`TiledNavMesh::path`].
+pub(super) fn find_path(
+    navmesh: &TiledNavMesh,
+    areas: &AreaMap,
+    blocked: &[Segment],
+    start: Vec3,
+    end: Vec3,
+) -> Option<Vec<Vec3>> {
+    let direct = navmesh.path(start, end)?;
+    let direct_crosses = crosses(blocked, start, &direct);
+    if !direct_crosses && !areas.is_costlier(start, &direct) {
+        // The shortest path through the cheapest areas can't be improved.
+        return Some(direct);
+    }
+
+    let (min, max) = direct
+        .iter()
+        .fold((start.xz(), start.xz()), |(min, max), point| {
+            (min.min(point.xz()), max.max(point.xz()))
+        });
+
+    let weighted = Grid::new(min - SEARCH_MARGIN, max + SEARCH_MARGIN)
+        .and_then(|grid| search_waypoints(navmesh, areas, blocked, &grid, start, end))
+        .and_then(|waypoints| {
+            let mut path = Vec::new();
+            let mut from = start;
+            for point in waypoints.into_iter().chain([end]) {
+                path.extend(navmesh.path(from, point)?);
+                from = point;
+            }
+            Some(path)
+        })
+        .filter(|path| !crosses(blocked, start, path));
+    let direct = (!direct_crosses).then_some(direct);
+
+    match (direct, weighted) {
+        (Some(direct), Some(weighted)) => {
+            if areas.path_cost(start, &weighted) < areas.path_cost(start, &direct) {
+                Some(weighted)
+            } else {
+                Some(direct)
+            }
+        }
+        (direct, weighted) => direct.or(weighted),
+    }
+}
+
+/// Returns `true` if the path intersects any of the blocked segments.
+fn crosses(blocked: &[Segment], start: Vec3, path: &[Vec3]) -> bool {
+    iter::once(start)
+        .chain(path.iter().copied())
+        .tuple_windows()
+        .map(|(a, b)| Segment::new(a.xz(), b.xz()))
+        .any(|segment| blocked.iter().any(|&other| segment.intersects(other)))
+}
+
+/// Searches a weighted grid path and returns its turning points without start and end.
+fn search_waypoints(
+    navmesh: &TiledNavMesh,
+    areas: &AreaMap,
+    blocked: &[Segment],
+    grid: &Grid,
+    start: Vec3,
+    end: Vec3,
+) -> Option<Vec<Vec3>> {
+    // Extend blocked segments to prevent diagonal moves around their ends.
+    let blocked: Vec<_> = blocked
+        .iter()
+        .map(|segment| {
+            let [a, b] = segment.points();
+            let extension = (b - a).normalize_or_zero() * CELL_SIZE;
+            Segment::new(a - extension, b + extension)
+        })
+        .collect();
+
+    let start_cell = grid.cell(start.xz());
+    let end_cell = grid.cell(end.xz());
+    let mut cell_areas: HashMap<UVec2, Option<NavArea>> = HashMap::new();
+    let mut cell_area = |cell: UVec2| {
+        *cell_areas.entry(cell).or_insert_with(|| {
+            let point = grid.center(cell);
+            let walkable = cell == start_cell
+                || cell == end_cell
+                || navmesh.is_in_mesh(Vec3::new(point.x, start.y, point.y));
+            walkable.then(|| areas.area_at(point))
+        })
+    };
+
+    let mut costs = HashMap::from([(start_cell, 0.0)]);
+    let mut came_from = HashMap::new();
+    // Non-negative floats have the same order as their bits.
+    let mut queue = BinaryHeap::from([Reverse((0.0f32.to_bits(), start_cell.to_array()))]);
+    while let Some(Reverse((_, cell))) = queue.pop() {
+        let cell = UVec2::from_array(cell);
+        if cell == end_cell {
+            break;
+        }
+
+        let cost = costs[&cell];
+        let area = cell_area(cell)?;
+        for neighbour in grid.neighbours(cell) {
+            let Some(neighbour_area) = cell_area(neighbour) else {
+                continue;
+            };
+            let movement = Segment::new(grid.center(cell), grid.center(neighbour));
+            if blocked.iter().any(|&segment| movement.intersects(segment)) {
+                continue;
+            }
+            let distance = grid.center(cell).distance(grid.center(neighbour));
+            let new_cost = cost + distance * (area.cost() + neighbour_area.cost()) / 2.0;
+            if costs
+                .get(&neighbour)
+                .is_none_or(|&old_cost| new_cost < old_cost)
+            {
+                costs.insert(neighbour, new_cost);
+                came_from.insert(neighbour, cell);
+                // Admissible because the cheapest area cost is 1.
+                let priority = new_cost + grid.center(neighbour).distance(end.xz());
+                queue.push(Reverse((priority.to_bits(), neighbour.to_array())));
+            }
+        }
+    }
+
+    let mut cells = vec![end_cell];
+    let mut cell = end_cell;
+    while cell != start_cell {
+        cell = *came_from.get(&cell)?;
+        cells.push(cell);
+    }
+    cells.reverse();
+
+    let waypoints = cells
+        .windows(3)
+        .filter(|cells| {
+            cells[1].as_ivec2() - cells[0].as_ivec2() != cells[2].as_ivec2() - cells[1].as_ivec2()
+        })
+        .map(|cells| {
+            let point = grid.center(cells[1]);
+            Vec3::new(point.x, start.y, point.y)
+        })
+        .collect();
+
+    Some(waypoints)
+}
+
+/// Square grid over a rectangle.
+struct Grid {
+    origin: Vec2,
+    size: UVec2,
+}
+
+impl Grid {
+    /// Creates a grid for the rectangle.
+    ///
+    /// Returns [`None`] if the grid exceeds [`MAX_CELLS`].
+    fn new(min: Vec2, max: Vec2) -> Option<Self> {
+        let size = ((max - min) / CELL_SIZE).ceil().as_uvec2().max(UVec2::ONE);
+        if size.element_product() as usize > MAX_CELLS {
+            return None;
+        }
+
+        Some(Self { origin: min, size })
+    }
+
+    fn cell(&self, point: Vec2) -> UVec2 {
+        ((point - self.origin) / CELL_SIZE)
+            .floor()
+            .as_uvec2()
+            .min(self.size - 1)
+    }
+
+    fn center(&self, cell: UVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + 0.5) * CELL_SIZE
+    }
+
+    fn neighbours(&self, cell: UVec2) -> impl Iterator<Item = UVec2> + '_ {
+        (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |y| IVec2::new(x, y)))
+            .filter(|&offset| offset != IVec2::ZERO)
+            .map(move |offset| cell.as_ivec2() + offset)
+            .filter(|neighbour| {
+                neighbour.cmpge(IVec2::ZERO).all() && neighbour.cmplt(self.size.as_ivec2()).all()
+            })
+            .map(|neighbour| neighbour.as_uvec2())
+    }
+}
@@ -0,0 +1,398 @@
+//! City navmesh split into square tiles.
+//!
+//! Each tile is a separate navmesh that rebuilds only when obstacles inside it change.
+//! Paths that cross tile borders are searched over portals between tiles
+//! and stitched from per-tile paths.
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap},
+};
+
+use avian3d::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*, transform::TransformSystem};
+use vleue_navigator::prelude::*;
+
+use super::Obstacle;
+use crate::game_world::{
+    actor::ACTOR_RADIUS,
+    city::{CityNavMesh, HALF_CITY_SIZE},
+};
+
+pub(super) struct NavTilesPlugin;
+
+impl Plugin for NavTilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(rebuild_after_removal).add_systems(
+            PostUpdate,
+            rebuild_changed.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Side size of a navmesh tile.
+pub const TILE_SIZE: f32 = 50.0;
+
+/// Number of tiles along each city side.
+pub const TILES_PER_SIDE: u32 = (HALF_CITY_SIZE * 2.0 / TILE_SIZE) as u32;
+
+/// Distance between points checked when searching walkable crossings between tiles.
+const PORTAL_STEP: f32 = 0.5;
+
+/// Maximum length of a walkable border piece represented by a single portal.
+///
+/// Longer pieces are split, so paths don't detour to the middle of a wide crossing.
+const MAX_PORTAL_WIDTH: f32 = 5.0;
+
+/// Offset from a tile border to place stitched points inside tiles.
+const BORDER_OFFSET: f32 = 0.01;
+
+/// Requests rebuilding of tiles that changed obstacles cover now or covered before.
+fn rebuild_changed(
+    cities: Query<(&GlobalTransform, &CityNavMesh)>,
+    mut tiles: Query<&mut NavMeshUpdateMode>,
+    mut obstacles: Query<
+        (Entity, &GlobalTransform, &Collider, &mut ObstacleTiles),
+        Or<(Added<Obstacle>, Changed<GlobalTransform>, Changed<Collider>)>,
+    >,
+) {
+    for (entity, transform, collider, mut obstacle_tiles) in &mut obstacles {
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let aabb = collider.aabb(translation, rotation);
+        let min = aabb.min.xz() - ACTOR_RADIUS;
+        let max = aabb.max.xz() + ACTOR_RADIUS;
+
+        let mut covered = Vec::new();
+        for (city_transform, tile_entities) in &cities {
+            let center = city_transform.translation().xz();
+            let (local_min, local_max) = (min - center, max - center);
+            if local_max.cmplt(Vec2::splat(-HALF_CITY_SIZE)).any()
+                || local_min.cmpgt(Vec2::splat(HALF_CITY_SIZE)).any()
+            {
+                continue;
+            }
+
+            let (first, last) = (tile_of(local_min), tile_of(local_max));
+            for y in first.y..=last.y {
+                for x in first.x..=last.x {
+                    covered.push(tile_entities[tile_index(UVec2::new(x, y))]);
+                }
+            }
+        }
+
+        let mut iter = tiles.iter_many_mut(obstacle_tiles.iter().chain(&covered));
+        while let Some(mut update_mode) = iter.fetch_next() {
+            *update_mode = NavMeshUpdateMode::OnDemand(true);
+        }
+
+        trace!("obstacle `{entity}` covers {} navmesh tiles", covered.len());
+        **obstacle_tiles = covered;
+    }
+}
+
+fn rebuild_after_removal(
+    trigger: Trigger<OnRemove, Obstacle>,
+    obstacles: Query<&ObstacleTiles>,
+    mut tiles: Query<&mut NavMeshUpdateMode>,
+) {
+    let Ok(obstacle_tiles) = obstacles.get(trigger.entity()) else {
+        return;
+    };
+
+    trace!(
+        "rebuilding {} navmesh tiles after `{}` removal",
+        obstacle_tiles.len(),
+        trigger.entity()
+    );
+    let mut iter = tiles.iter_many_mut(&**obstacle_tiles);
+    while let Some(mut update_mode) = iter.fetch_next() {
+        *update_mode = NavMeshUpdateMode::OnDemand(true);
+    }
+}
+
+/// Returns all tiles in the order in which they are stored in [`CityNavMesh`].
+pub fn tiles() -> impl Iterator<Item = UVec2> {
+    (0..TILES_PER_SIDE).flat_map(|y| (0..TILES_PER_SIDE).map(move |x| UVec2::new(x, y)))
+}
+
+/// Returns index of the tile in [`CityNavMesh`].
+pub fn tile_index(tile: UVec2) -> usize {
+    (tile.y * TILES_PER_SIDE + tile.x) as usize
+}
+
+/// Returns tile that contains the point in the city space.
+///
+/// Points outside the city are clamped to the border tiles.
+pub fn tile_of(point: Vec2) -> UVec2 {
+    ((point + HALF_CITY_SIZE) / TILE_SIZE)
+        .floor()
+        .clamp(Vec2::ZERO, Vec2::splat((TILES_PER_SIDE - 1) as f32))
+        .as_uvec2()
+}
+
+/// Returns the tile square in the city space.
+pub fn tile_rect(tile: UVec2) -> Rect {
+    let min = tile.as_vec2() * TILE_SIZE - HALF_CITY_SIZE;
+    Rect::from_corners(min, min + TILE_SIZE)
+}
+
+/// Returns triangulation that covers the tile square.
+///
+/// Used as a base for tile navmesh generation.
+pub fn tile_triangulation(tile: UVec2) -> Triangulation {
+    let rect = tile_rect(tile);
+    Triangulation::from_outer_edges(&[
+        rect.min,
+        Vec2::new(rect.max.x, rect.min.y),
+        rect.max,
+        Vec2::new(rect.min.x, rect.max.y),
+    ])
+}
+
+/// Tiles that were marked for rebuilding by the obstacle last time.
+#[derive(Component, Default, Deref, DerefMut)]
+pub(super) struct ObstacleTiles(Vec<Entity>);
+
+/// Built tile navmeshes of cities.
+#[derive(SystemParam)]
+pub(super) struct CityTiles<'w, 's> {
+    navmeshes: Res<'w, Assets<NavMesh>>,
+    cities: Query<'w, 's, &'static CityNavMesh>,
+    tiles: Query<'w, 's, &'static ManagedNavMesh>,
+}
+
+impl CityTiles<'_, '_> {
+    pub(super) fn get(&self, city_entity: Entity) -> TiledNavMesh<'_> {
+        let tiles = self
+            .cities
+            .get(city_entity)
+            .map(|tile_entities| {
+                tile_entities
+                    .iter()
+                    .map(|&entity| {
+                        self.tiles
+                            .get(entity)
+                            .ok()
+                            .and_then(|handle| self.navmeshes.get(handle))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        TiledNavMesh { tiles }
+    }
+}
+
+/// Navmeshes of city tiles.
+///
+/// Missing tiles are not built yet and considered unwalkable.
+pub(super) struct TiledNavMesh<'a> {
+    tiles: Vec<Option<&'a NavMesh>>,
+}
+
+impl TiledNavMesh<'_> {
+    /// Returns `true` if all tiles were built at least once.
+    pub(super) fn is_ready(&self) -> bool {
+        !self.tiles.is_empty() && self.tiles.iter().all(Option::is_some)
+    }
+
+    fn tile(&self, tile: UVec2) -> Option<&NavMesh> {
+        self.tiles.get(tile_index(tile)).copied().flatten()
+    }
+
+    pub(super) fn is_in_mesh(&self, point: Vec3) -> bool {
+        self.tile(tile_of(point.xz()))
+            .is_some_and(|navmesh| navmesh.transformed_is_in_mesh(point))
+    }
+
+    /// Finds a path like [`NavMesh::transformed_path`], but across tiles.
+    ///
+    /// Tiles are connected by portals, walkable pieces of their shared borders.
+    /// A* searches over portals with navmesh path lengths inside tiles as costs,
+    /// so detours through other tiles are found.
+    pub(super) fn path(&self, start: Vec3, end: Vec3) -> Option<Vec<Vec3>> {
+        let start_tile = tile_of(start.xz());
+        let end_tile = tile_of(end.xz());
+        if start_tile == end_tile {
+            // The end could still be reachable through other tiles.
+            if let Some(path) = self.tile(start_tile)?.transformed_path(start, end) {
+                return Some(path.path);
+            }
+        }
+
+        let mut nodes = vec![PortalNode {
+            tile: start_tile,
+            entry: start,
+            cost: 0.0,
+            parent: None,
+            leg: Vec::new(),
+        }];
+        let mut node_indices = HashMap::new();
+        let mut portals = HashMap::new();
+        let mut best: Option<(f32, usize, Vec<Vec3>)> = None;
+        // Non-negative floats have the same order as their bits.
+        let mut queue = BinaryHeap::from([Reverse((start.distance(end).to_bits(), 0))]);
+        while let Some(Reverse((priority, index))) = queue.pop() {
+            if best
+                .as_ref()
+                .is_some_and(|&(cost, ..)| f32::from_bits(priority) >= cost)
+            {
+                break;
+            }
+
+            let PortalNode {
+                tile, entry, cost, ..
+            } = nodes[index];
+            let Some(navmesh) = self.tile(tile) else {
+                continue;
+            };
+
+            if tile == end_tile {
+                if let Some(path) = navmesh.transformed_path(entry, end) {
+                    let total = cost + path.length;
+                    if best.as_ref().is_none_or(|&(cost, ..)| total < cost) {
+                        best = Some((total, index, path.path));
+                    }
+                }
+            }
+
+            for neighbour in neighbours(tile) {
+                let tile_portals = portals
+                    .entry((tile, neighbour))
+                    .or_insert_with(|| self.portals(tile, neighbour, start.y));
+                for &(exit, next_entry) in &*tile_portals {
+                    let Some(path) = navmesh.transformed_path(entry, exit) else {
+                        continue;
+                    };
+
+                    let new_cost = cost + path.length + exit.distance(next_entry);
+                    let mut leg = path.path;
+                    leg.push(next_entry);
+                    let node = PortalNode {
+                        tile: neighbour,
+                        entry: next_entry,
+                        cost: new_cost,
+                        parent: Some(index),
+                        leg,
+                    };
+
+                    let key = (neighbour, next_entry.xz().to_array().map(f32::to_bits));
+                    let next_index = match node_indices.entry(key) {
+                        Entry::Occupied(entry) => {
+                            let next_index = *entry.get();
+                            if nodes[next_index].cost <= new_cost {
+                                continue;
+                            }
+                            nodes[next_index] = node;
+                            next_index
+                        }
+                        Entry::Vacant(entry) => {
+                            nodes.push(node);
+                            *entry.insert(nodes.len() - 1)
+                        }
+                    };
+
+                    // Admissible because paths can't be shorter than the straight line.
+                    let priority = new_cost + next_entry.distance(end);
+                    queue.push(Reverse((priority.to_bits(), next_index)));
+                }
+            }
+        }
+
+        let (_, mut index, mut path) = best?;
+        let mut legs = Vec::new();
+        while let Some(parent) = nodes[index].parent {
+            legs.push(index);
+            index = parent;
+        }
+        let mut full_path: Vec<_> = legs
+            .into_iter()
+            .rev()
+            .flat_map(|index| nodes[index].leg.iter().copied())
+            .collect();
+        full_path.append(&mut path);
+
+        Some(full_path)
+    }
+
+    /// Returns walkable crossings from the tile to the adjacent tile.
+    ///
+    /// Each crossing is a pair of points on both sides of the shared border.
+    fn portals(&self, tile: UVec2, neighbour: UVec2, height: f32) -> Vec<(Vec3, Vec3)> {
+        let rect = tile_rect(tile);
+        let normal = (neighbour.as_ivec2() - tile.as_ivec2()).as_vec2();
+        let along = normal.perp().abs();
+        let border = if normal.element_sum() > 0.0 {
+            rect.max
+        } else {
+            rect.min
+        };
+        let origin = border * normal.abs() + rect.min * along;
+
+        let crossing = |step: usize| {
+            // Sample between steps to avoid tile corners.
+            let point = origin + along * (step as f32 + 0.5) * PORTAL_STEP;
+            let exit = point - normal * BORDER_OFFSET;
+            let entry = point + normal * BORDER_OFFSET;
+            (
+                Vec3::new(exit.x, height, exit.y),
+                Vec3::new(entry.x, height, entry.y),
+            )
+        };
+
+        let steps = (TILE_SIZE / PORTAL_STEP) as usize;
+        let max_piece_steps = (MAX_PORTAL_WIDTH / PORTAL_STEP) as usize;
+        let mut portals = Vec::new();
+        let mut piece: Option<(usize, usize)> = None;
+        for step in 0..=steps {
+            // The extra step closes the last piece.
+            let walkable = step < steps && {
+                let (exit, entry) = crossing(step);
+                self.is_in_mesh(exit) && self.is_in_mesh(entry)
+            };
+
+            match piece {
+                Some((first, _)) if walkable && step - first < max_piece_steps => {
+                    piece = Some((first, step))
+                }
+                Some((first, last)) => {
+                    portals.push(crossing((first + last) / 2));
+                    piece = walkable.then_some((step, step));
+                }
+                None => piece = walkable.then_some((step, step)),
+            }
+        }
+
+        portals
+    }
+}
+
+/// Node of the portal search.
+struct PortalNode {
+    /// Tile in which the node is located.
+    tile: UVec2,
+
+    /// Point inside the tile.
+    entry: Vec3,
+
+    /// Length of the path from the start.
+    cost: f32,
+
+    /// Index of the previous node.
+    parent: Option<usize>,
+
+    /// Path points from the previous node to the entry.
+    leg: Vec<Vec3>,
+}
+
+/// Returns tiles that share a border with the tile.
+fn neighbours(tile: UVec2) -> impl Iterator<Item = UVec2> {
+    [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+        .into_iter()
+        .map(move |offset| tile.as_ivec2() + offset)
+        .filter(|neighbour| {
+            neighbour.cmpge(IVec2::ZERO).all()
+                && neighbour.cmplt(IVec2::splat(TILES_PER_SIDE as i32)).all()
+        })
+        .map(|neighbour| neighbour.as_uvec2())
+}
@@ -1,6 +1,9 @@
-use bevy::{color::palettes::css::BLUE_VIOLET, prelude::*};
+use bevy::prelude::*;
 
-use super::NavPath;
+use super::{
+    nav_area::{self, NavAreas},
+    NavPath,
+};
 use crate::{common_conditions::in_any_state, game_world::WorldState, settings::Settings};
 
 pub(super) struct PathDebugPlugin;
@@ -16,16 +19,25 @@ impl Plugin for PathDebugPlugin {
     }
 }
 
+/// Draws paths colored by areas they pass through.
 fn draw_lines(
     mut gizmos: Gizmos,
+    areas: NavAreas,
     actors: Query<(&NavPath, &Parent)>,
     cities: Query<&GlobalTransform>,
 ) {
     for (path, parent) in &actors {
         let transform = cities.get(**parent).unwrap();
-        gizmos.linestrip(
-            path.iter().map(|&point| transform.transform_point(point)),
-            BLUE_VIOLET,
-        );
+        let area_map = areas.map(**parent);
+        for points in path.windows(2) {
+            for (start, end) in nav_area::samples(points[0], points[1]) {
+                let area = area_map.area_at(start.xz().midpoint(end.xz()));
+                gizmos.line(
+                    transform.transform_point(start),
+                    transform.transform_point(end),
+                    area.color(),
+                );
+            }
+        }
     }
 }
@@ -1,4 +1,5 @@
-pub(crate) mod door;
+pub mod door;
+pub(crate) mod lamp;
 pub mod placing_object;
 pub(crate) mod wall_mount;
 
@@ -18,10 +19,12 @@ use super::{
         CommandConfirmation, CommandId, CommandRequest, ConfirmableCommand, EntityRecorder,
         PendingCommand,
     },
+    family::{bills::Owner, FamilyControllers},
     highlighting::HIGHLIGHTING_VOLUME,
 };
 use crate::{asset::manifest::object_manifest::ObjectManifest, game_world::Layer};
-use door::DoorPlugin;
+use door::{DoorLock, DoorPlugin};
+use lamp::LampPlugin;
 use placing_object::PlacingObjectPlugin;
 use wall_mount::WallMountPlugin;
 
@@ -29,7 +32,7 @@ pub(super) struct ObjectPlugin;
 
 impl Plugin for ObjectPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((DoorPlugin, PlacingObjectPlugin, WallMountPlugin))
+        app.add_plugins((DoorPlugin, LampPlugin, PlacingObjectPlugin, WallMountPlugin))
             .register_type::<Object>()
             .replicate_group::<(Object, Transform)>()
             .add_mapped_client_event::<CommandRequest<ObjectCommand>>(ChannelKind::Unordered)
@@ -82,7 +85,9 @@ fn apply_command(
     mut commands: Commands,
     mut request_events: EventReader<FromClient<CommandRequest<ObjectCommand>>>,
     mut confirm_events: EventWriter<ToClients<CommandConfirmation>>,
+    controllers: Res<FamilyControllers>,
     mut objects: Query<&mut Transform, Without<City>>,
+    mut locks: Query<(&mut DoorLock, Option<&Owner>)>,
 ) {
     for FromClient { client_id, event } in request_events.read().cloned() {
         // TODO: validate if command can be applied.
@@ -91,6 +96,7 @@ fn apply_command(
             ObjectCommand::Buy {
                 manifest_path,
                 city_entity,
+                family_entity,
                 translation,
                 rotation,
             } => {
@@ -98,13 +104,22 @@ fn apply_command(
                     error!("received translation {translation} with 'y' outside of city size");
                     continue;
                 }
+                if let Some(family_entity) = family_entity {
+                    if !controllers.controls(client_id, family_entity) {
+                        error!("`{client_id:?}` tried to buy object for another family");
+                        continue;
+                    }
+                }
 
                 info!("`{client_id:?}` buys object {manifest_path:?}");
                 commands.entity(city_entity).with_children(|parent| {
                     let transform =
                         Transform::from_translation(translation).with_rotation(rotation);
-                    let entity = parent.spawn((Object(manifest_path), transform)).id();
-                    confirmation.entity = Some(entity);
+                    let mut entity = parent.spawn((Object(manifest_path), transform));
+                    if let Some(family_entity) = family_entity {
+                        entity.insert(Owner { family_entity });
+                    }
+                    confirmation.entity = Some(entity.id());
                 });
             }
             ObjectCommand::Move {
@@ -121,8 +136,22 @@ fn apply_command(
             },
             ObjectCommand::Sell { entity } => {
                 info!("`{client_id:?}` sells object `{entity}`");
-                commands.entity(entity).despawn_recursive();
+                sell(&mut commands, entity);
             }
+            ObjectCommand::SetLock { entity, lock } => match locks.get_mut(entity) {
+                Ok((mut current, owner)) => {
+                    if owner
+                        .is_some_and(|owner| !controllers.controls(client_id, owner.family_entity))
+                    {
+                        error!("`{client_id:?}` tried to lock door `{entity}` of another family");
+                        continue;
+                    }
+
+                    info!("`{client_id:?}` sets `{lock:?}` for door `{entity}`");
+                    *current = lock;
+                }
+                Err(e) => error!("unable to set lock for door `{entity}`: {e}"),
+            },
         }
 
         confirm_events.send(ToClients {
@@ -132,6 +161,14 @@ fn apply_command(
     }
 }
 
+/// Removes the object from the world.
+///
+/// Shared between [`ObjectCommand::Sell`] and repossession for unpaid bills,
+/// so both follow the same rules. Buying is free, so selling doesn't refund anything.
+pub(super) fn sell(commands: &mut Commands, entity: Entity) {
+    commands.entity(entity).despawn_recursive();
+}
+
 /// Contains path to the object info.
 #[derive(Clone, Component, Debug, Default, Reflect, Serialize, Deserialize, Deref)]
 #[reflect(Component)]
@@ -154,6 +191,10 @@ enum ObjectCommand {
     Buy {
         manifest_path: AssetPath<'static>,
         city_entity: Entity,
+        /// Family that will own the object.
+        ///
+        /// [`None`] for city objects.
+        family_entity: Option<Entity>,
         translation: Vec3,
         rotation: Quat,
     },
@@ -165,6 +206,10 @@ enum ObjectCommand {
     Sell {
         entity: Entity,
     },
+    SetLock {
+        entity: Entity,
+        lock: DoorLock,
+    },
 }
 
 impl PendingCommand for ObjectCommand {
@@ -187,15 +232,21 @@ impl PendingCommand for ObjectCommand {
                     rotation: transform.rotation,
                 }
             }
+            Self::SetLock { entity, .. } => {
+                let lock = *world.get::<DoorLock>(entity).unwrap();
+                Self::SetLock { entity, lock }
+            }
             Self::Sell { entity } => {
                 recorder.record(entity);
                 let entity = world.entity(entity);
                 let manifest_path = entity.get::<Object>().unwrap().0.clone();
                 let parent = entity.get::<Parent>().unwrap();
+                let owner = entity.get::<Owner>();
                 let transform = entity.get::<Transform>().unwrap();
                 Self::Buy {
                     manifest_path,
                     city_entity: **parent,
+                    family_entity: owner.map(|owner| owner.family_entity),
                     translation: transform.translation,
                     rotation: transform.rotation,
                 }
@@ -228,9 +279,17 @@ impl ConfirmableCommand for ObjectCommand {
 impl MapEntities for ObjectCommand {
     fn map_entities<T: EntityMapper>(&mut self, entity_mapper: &mut T) {
         match self {
-            Self::Buy { .. } => (),
+            Self::Buy {
+                city_entity,
+                family_entity,
+                ..
+            } => {
+                *city_entity = entity_mapper.map_entity(*city_entity);
+                *family_entity = family_entity.map(|entity| entity_mapper.map_entity(entity));
+            }
             Self::Move { entity, .. } => *entity = entity_mapper.map_entity(*entity),
             Self::Sell { entity } => *entity = entity_mapper.map_entity(*entity),
+            Self::SetLock { entity, .. } => *entity = entity_mapper.map_entity(*entity),
         };
     }
 }
@@ -1,15 +1,25 @@
 use std::path::Path;
 
-use bevy::{asset::AssetPath, prelude::*};
+use bevy::{asset::AssetPath, ecs::system::SystemParam, prelude::*};
+use bevy_replicon::prelude::*;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
 
+use super::{placing_object::PlacingObject, ObjectCommand};
 use crate::{
     asset::{
         self,
         manifest::{MapPaths, ReflectMapPaths},
     },
     core::GameState,
-    game_world::{actor::Actor, navigation::NavPath, segment::Segment},
+    game_world::{
+        actor::Actor,
+        commands_history::CommandsHistory,
+        family::bills::Owner,
+        navigation::{NavDestination, NavPath},
+        segment::Segment,
+    },
 };
 
 pub(super) struct DoorPlugin;
@@ -17,11 +27,22 @@ pub(super) struct DoorPlugin;
 impl Plugin for DoorPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Door>()
+            .register_type::<DoorLock>()
+            .replicate::<DoorLock>()
             .add_observer(cleanup_passing_actors)
+            .add_observer(init_placing_lock)
+            .add_systems(
+                Update,
+                (
+                    (update_passing_actors, play_animation).chain(),
+                    apply_placing_lock,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
             .add_systems(
                 Update,
-                (update_passing_actors, play_animation)
-                    .chain()
+                reroute_passing_actors
+                    .run_if(server_or_singleplayer)
                     .run_if(in_state(GameState::InGame)),
             );
     }
@@ -48,11 +69,7 @@ fn update_passing_actors(
                     continue;
                 }
 
-                let door_point = Vec3::X * door.half_width;
-                let door_start = door_transform.transform_point(door_point).xz();
-                let door_end = door_transform.transform_point(-door_point).xz();
-                let door_segment = Segment::new(door_start, door_end);
-                if nav_segment.intersects(door_segment) {
+                if nav_segment.intersects(door.segment(door_transform)) {
                     debug!("marking path of actor `{actor_entity}` as passing");
                     door_state.passing_actors.push(actor_entity);
                 }
@@ -124,6 +141,59 @@ fn play_animation(
     }
 }
 
+/// Recalculates paths of actors that pass through doors with changed locks.
+fn reroute_passing_actors(
+    doors: Query<&DoorState, Changed<DoorLock>>,
+    mut actors: Query<&mut NavDestination>,
+) {
+    for door_state in &doors {
+        let mut iter = actors.iter_many_mut(&door_state.passing_actors);
+        while let Some(mut dest) = iter.fetch_next() {
+            debug!("rerouting actor after lock change");
+            dest.set_changed();
+        }
+    }
+}
+
+/// Copies lock of the moving door to let players edit it.
+fn init_placing_lock(
+    trigger: Trigger<OnAdd, PlacingObject>,
+    mut commands: Commands,
+    placing_objects: Query<&PlacingObject>,
+    doors: Query<&DoorLock>,
+) {
+    let &placing_object = placing_objects.get(trigger.entity()).unwrap();
+    if let PlacingObject::Moving(object_entity) = placing_object {
+        if let Ok(&lock) = doors.get(object_entity) {
+            commands.entity(trigger.entity()).insert(lock);
+        }
+    }
+}
+
+fn apply_placing_lock(
+    mut history: CommandsHistory,
+    placing_objects: Query<(&PlacingObject, Ref<DoorLock>)>,
+    doors: Query<&DoorLock, Without<PlacingObject>>,
+) {
+    for (&placing_object, lock) in &placing_objects {
+        let PlacingObject::Moving(entity) = placing_object else {
+            continue;
+        };
+        if lock.is_added() || !lock.is_changed() {
+            continue;
+        }
+        if doors.get(entity).is_ok_and(|&current| current == *lock) {
+            continue;
+        }
+
+        info!("changing lock for door `{entity}` to `{:?}`", *lock);
+        history.push_pending(ObjectCommand::SetLock {
+            entity,
+            lock: *lock,
+        });
+    }
+}
+
 fn cleanup_passing_actors(trigger: Trigger<OnRemove, Actor>, mut objects: Query<&mut DoorState>) {
     for mut door_state in &mut objects {
         debug!("removing path of deleted actor `{}`", trigger.entity());
@@ -136,7 +206,7 @@ fn cleanup_passing_actors(trigger: Trigger<OnRemove, Actor>, mut objects: Query<
 /// Will trigger open animation when an actor passes through.
 #[derive(Component, Reflect, Default)]
 #[reflect(Component, MapPaths)]
-#[require(DoorState)]
+#[require(DoorState, DoorLock)]
 pub(crate) struct Door {
     half_width: f32,
     /// Distance on which animation will be triggered.
@@ -147,6 +217,16 @@ pub(crate) struct Door {
     open_animation: AssetPath<'static>,
 }
 
+impl Door {
+    /// Returns the door opening on the ground plane.
+    fn segment(&self, transform: &Transform) -> Segment {
+        let door_point = Vec3::X * self.half_width;
+        let start = transform.transform_point(door_point).xz();
+        let end = transform.transform_point(-door_point).xz();
+        Segment::new(start, end)
+    }
+}
+
 impl MapPaths for Door {
     fn map_paths(&mut self, dir: &Path) {
         asset::change_parent_dir(&mut self.open_animation, dir);
@@ -174,3 +254,73 @@ impl DoorState {
         }
     }
 }
+
+/// Who can pass through a door.
+#[derive(
+    Clone, Component, Copy, Debug, Default, Deserialize, EnumIter, PartialEq, Reflect, Serialize,
+)]
+#[reflect(Component)]
+pub enum DoorLock {
+    #[default]
+    Everyone,
+    /// Only members of the family that owns the door.
+    Household,
+    Nobody,
+}
+
+impl DoorLock {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Everyone => "🔓",
+            Self::Household => "🏠",
+            Self::Nobody => "🔒",
+        }
+    }
+
+    fn allows(self, member: bool) -> bool {
+        match self {
+            Self::Everyone => true,
+            Self::Household => member,
+            Self::Nobody => false,
+        }
+    }
+}
+
+/// Door openings that actors can't pass due to [`DoorLock`].
+#[derive(SystemParam)]
+pub(crate) struct LockedDoors<'w, 's> {
+    doors: Query<
+        'w,
+        's,
+        (
+            &'static Parent,
+            &'static Transform,
+            &'static Door,
+            &'static DoorLock,
+            Option<&'static Owner>,
+        ),
+    >,
+    actors: Query<'w, 's, (&'static Parent, &'static Actor)>,
+}
+
+impl LockedDoors<'_, '_> {
+    /// Returns openings of doors in the actor's city that are locked for it.
+    ///
+    /// Household doors are passable only for members of the family that owns the door.
+    /// If the door has no owner, nobody is a member.
+    pub(crate) fn blocked_for(&self, actor_entity: Entity) -> Vec<Segment> {
+        let Ok((city_entity, actor)) = self.actors.get(actor_entity) else {
+            return Vec::new();
+        };
+
+        self.doors
+            .iter()
+            .filter(|&(door_parent, ..)| door_parent == city_entity)
+            .filter(|(.., lock, owner)| {
+                let member = owner.is_some_and(|owner| owner.family_entity == actor.family_entity);
+                !lock.allows(member)
+            })
+            .map(|(_, transform, door, ..)| door.segment(transform))
+            .collect()
+    }
+}
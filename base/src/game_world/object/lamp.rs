@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::GameState,
+    game_world::{city::daylight::SunLight, clock::WorldClock},
+};
+
+pub(super) struct LampPlugin;
+
+impl Plugin for LampPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Lamp>().add_observer(init).add_systems(
+            Update,
+            switch
+                .never_param_warn()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+fn init(trigger: Trigger<OnAdd, Lamp>, mut commands: Commands, lamps: Query<&Lamp>) {
+    let lamp = lamps.get(trigger.entity()).unwrap();
+    debug!("initializing lamp for `{}`", trigger.entity());
+    commands.entity(trigger.entity()).with_children(|parent| {
+        parent.spawn((
+            LampLight,
+            PointLight {
+                intensity: lamp.intensity,
+                range: lamp.range,
+                shadows_enabled: true,
+                ..Default::default()
+            },
+            Transform::from_translation(lamp.offset),
+        ));
+    });
+}
+
+/// Turns lamps on at night and off during the day.
+fn switch(clock: Res<WorldClock>, mut lights: Query<&mut Visibility, With<LampLight>>) {
+    let visibility = if SunLight::new(clock.time_of_day(), clock.season()).is_night() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    for mut light_visibility in &mut lights {
+        light_visibility.set_if_neq(visibility);
+    }
+}
+
+/// Marks object as a light source that turns on at night.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub(crate) struct Lamp {
+    /// Light position relative to the object.
+    offset: Vec3,
+    /// Luminous power in lumens.
+    intensity: f32,
+    range: f32,
+}
+
+#[derive(Component)]
+#[require(Name(|| Name::new("Lamp light")), Visibility(|| Visibility::Hidden))]
+struct LampLight;
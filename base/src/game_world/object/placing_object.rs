@@ -20,7 +20,7 @@ use crate::{
     game_world::{
         city::CityMode,
         commands_history::{CommandsHistory, PendingDespawn},
-        family::building::BuildingMode,
+        family::{building::BuildingMode, SelectedFamily},
         highlighting::HighlightDisabler,
         object::{Object, ObjectCommand},
         player_camera::{CameraCaster, PlayerCamera},
@@ -220,6 +220,7 @@ fn confirm(
         &PlacingObjectState,
         &CollidingEntities,
     )>,
+    selected_family: Option<Single<Entity, With<SelectedFamily>>>,
 ) {
     let (parent, translation, &placing_object, state, colliding_entities) = *placing_object;
 
@@ -235,6 +236,7 @@ fn confirm(
             history.push_pending(ObjectCommand::Buy {
                 manifest_path: manifest_path.into_owned(),
                 city_entity: **parent,
+                family_entity: selected_family.as_deref().copied(),
                 translation: translation.translation,
                 rotation: translation.rotation,
             })
@@ -298,6 +300,7 @@ fn update_alpha(
             Layer::PlacingObject,
             Layer::Wall,
             Layer::PlacingWall,
+            Layer::Water,
         ],
     )),
 )]
@@ -11,7 +11,7 @@ use strum::EnumIter;
 use crate::{
     asset::collection::{AssetCollection, Collection},
     common_conditions::in_any_state,
-    game_world::WorldState,
+    game_world::{city::terrain::Heightmap, WorldState},
     settings::Settings,
 };
 
@@ -258,7 +258,7 @@ impl Default for SpringArm {
 #[derive(SystemParam)]
 pub(super) struct CameraCaster<'w, 's> {
     window: Single<'w, &'static Window>,
-    cities: Query<'w, 's, &'static GlobalTransform>,
+    cities: Query<'w, 's, (&'static GlobalTransform, Option<&'static Heightmap>)>,
     camera: Option<
         Single<
             'w,
@@ -269,17 +269,32 @@ pub(super) struct CameraCaster<'w, 's> {
 }
 
 impl CameraCaster<'_, '_> {
+    /// Returns cursor position on the terrain in city coordinates.
     pub(super) fn intersect_ground(&self) -> Option<Vec3> {
+        // Move the plane to the terrain height under the previous hit to refine it.
+        const REFINE_STEPS: usize = 4;
+
         let (parent, &transform, camera) = self.camera.as_deref()?;
         let cursor_pos = self.window.cursor_position()?;
         let ray = camera.viewport_to_world(&transform, cursor_pos).ok()?;
-        let distance = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Y))?;
-        let global_point = ray.get_point(distance);
-        let city_transform = self.cities.get(***parent).unwrap();
-        let local_point = city_transform
-            .affine()
-            .inverse()
-            .transform_point3(global_point);
+        let (city_transform, heightmap) = self.cities.get(***parent).unwrap();
+        let inverse = city_transform.affine().inverse();
+
+        let mut height = 0.0;
+        let mut local_point = Vec3::ZERO;
+        for _ in 0..=REFINE_STEPS {
+            let plane_origin = city_transform.transform_point(Vec3::Y * height);
+            let distance = ray.intersect_plane(plane_origin, InfinitePlane3d::new(Vec3::Y))?;
+            local_point = inverse.transform_point3(ray.get_point(distance));
+
+            let Some(heightmap) = heightmap else {
+                break;
+            };
+            height = heightmap.height_at(local_point.xz());
+        }
+
+        local_point.y = height;
+
         Some(local_point)
     }
 }
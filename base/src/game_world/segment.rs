@@ -34,7 +34,9 @@ impl Plugin for SegmentPlugin {
     }
 }
 
-fn update_transform(mut changed_segments: Query<(&mut Transform, &Segment), Changed<Segment>>) {
+pub(super) fn update_transform(
+    mut changed_segments: Query<(&mut Transform, &Segment), Changed<Segment>>,
+) {
     for (mut transform, segment) in &mut changed_segments {
         transform.translation = Vec3::new(segment.start.x, 0.0, segment.start.y);
         transform.rotation = Quat::from_rotation_y(-segment.displacement().to_angle());
@@ -0,0 +1,203 @@
+//! Headless apps for gameplay tests.
+//!
+//! Apps contain all game logic from [`CorePlugins`] and real manifests from the app assets,
+//! but render nothing. Updates use a fixed time step, so tests can advance them tick by tick.
+//!
+//! Available to benchmarks with the `test_app` feature.
+
+use std::time::{Duration, Instant};
+
+use avian3d::{prelude::*, sync::SyncConfig};
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_enhanced_input::prelude::*;
+use bevy_replicon::{
+    prelude::*,
+    server::{ServerPlugin, TickPolicy},
+    test_app::ServerTestAppExt,
+};
+use vleue_navigator::prelude::*;
+
+use super::{
+    actor::{human::Human, Actor, FirstName, LastName},
+    city::{City, CityNavMesh},
+    family::Family,
+    navigation::{NavDestination, Navigation, Obstacle},
+};
+use crate::{
+    asset::manifest::ManifestsDir, core::GameState, headless::HeadlessPlugins, settings::Settings,
+    CorePlugins,
+};
+
+/// Duration of a single [`App::update`].
+pub const TICK: Duration = Duration::from_millis(33);
+
+/// Assets of the game app with real manifests.
+const ASSETS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../app/assets");
+
+/// How long to wait for assets and navmeshes before failing a test.
+const LOADING_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Creates an app with loaded manifests in [`GameState::Menu`].
+pub fn new_app() -> App {
+    let mut app = App::new();
+    app.insert_resource(SyncConfig {
+        position_to_transform: false,
+        ..Default::default()
+    })
+    .insert_resource(Time::<Fixed>::from_duration(TICK))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+    .insert_resource(ManifestsDir(ASSETS_DIR.into()))
+    // Normally loaded on startup, but requires a window.
+    .init_resource::<Settings>()
+    .add_plugins(HeadlessPlugins.set(AssetPlugin {
+        file_path: ASSETS_DIR.to_string(),
+        ..Default::default()
+    }))
+    .add_plugins((
+        RepliconPlugins.set(ServerPlugin {
+            tick_policy: TickPolicy::EveryFrame,
+            ..Default::default()
+        }),
+        EnhancedInputPlugin,
+        VleueNavigatorPlugin,
+        NavmeshUpdaterPlugin::<Collider, Obstacle>::default(),
+        PhysicsPlugins::default()
+            .build()
+            .disable::<CcdPlugin>()
+            .disable::<SleepingPlugin>(),
+        CorePlugins,
+    ));
+
+    app.update_until("manifests loading", |world| {
+        *world.resource::<State<GameState>>() == GameState::Menu
+    });
+
+    app
+}
+
+/// Creates a server app and a client app connected to it.
+///
+/// Messages are passed in memory with [`ServerTestAppExt`], so use [`exchange`] instead of [`App::update`].
+pub fn new_connected_apps() -> (App, App) {
+    let mut server_app = new_app();
+    let mut client_app = new_app();
+
+    server_app.start_game();
+    server_app.connect_client(&mut client_app);
+    exchange(&mut server_app, &mut client_app);
+
+    (server_app, client_app)
+}
+
+/// Updates both apps and passes messages between them.
+pub fn exchange(server_app: &mut App, client_app: &mut App) {
+    server_app.update();
+    server_app.exchange_with_client(client_app);
+    client_app.update();
+    server_app.exchange_with_client(client_app);
+}
+
+pub trait GameTestAppExt {
+    /// Enters [`GameState::InGame`] without loading a world.
+    fn start_game(&mut self);
+
+    /// Spawns a city and waits until its navmesh is built.
+    fn spawn_city(&mut self) -> Entity;
+
+    /// Spawns a family of humans in the city.
+    ///
+    /// Returns the family and its actors in the order of `first_names`.
+    fn spawn_family(&mut self, city_entity: Entity, first_names: &[&str]) -> (Entity, Vec<Entity>);
+
+    /// Waits until all navmesh tiles of the city are built.
+    ///
+    /// Needs to be called after placing obstacles to make paths go around them.
+    fn wait_for_navmesh(&mut self, city_entity: Entity);
+
+    /// Starts walking of the actor to the destination in the city space.
+    fn walk_to(&mut self, actor_entity: Entity, destination: Vec3);
+
+    /// Updates the app until the condition is met.
+    ///
+    /// Panics after [`LOADING_TIMEOUT`] of real time.
+    fn update_until(&mut self, what: &str, condition: impl FnMut(&mut World) -> bool);
+
+    /// Updates the app the specified number of times.
+    fn update_ticks(&mut self, ticks: usize);
+}
+
+impl GameTestAppExt for App {
+    fn start_game(&mut self) {
+        self.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::InGame);
+        self.update();
+    }
+
+    fn spawn_city(&mut self) -> Entity {
+        let city_entity = self.world_mut().spawn((City, Name::new("Test"))).id();
+        self.wait_for_navmesh(city_entity);
+        city_entity
+    }
+
+    fn spawn_family(&mut self, city_entity: Entity, first_names: &[&str]) -> (Entity, Vec<Entity>) {
+        let family_entity = self.world_mut().spawn((Family, Name::new("Test"))).id();
+        let actors = first_names
+            .iter()
+            .map(|&first_name| {
+                self.world_mut()
+                    .spawn((
+                        Actor { family_entity },
+                        Human,
+                        FirstName(first_name.to_string()),
+                        LastName("Test".to_string()),
+                    ))
+                    .set_parent(city_entity)
+                    .id()
+            })
+            .collect();
+        self.update();
+
+        (family_entity, actors)
+    }
+
+    fn wait_for_navmesh(&mut self, city_entity: Entity) {
+        // Let requested rebuilds start first.
+        self.update();
+        self.update_until("navmesh building", |world| {
+            let tile_entities = world.get::<CityNavMesh>(city_entity).unwrap();
+            tile_entities.iter().all(|&entity| {
+                matches!(
+                    world.get::<NavMeshStatus>(entity),
+                    Some(NavMeshStatus::Built)
+                ) && !matches!(
+                    world.get::<NavMeshUpdateMode>(entity),
+                    Some(NavMeshUpdateMode::OnDemand(true))
+                )
+            })
+        });
+    }
+
+    fn walk_to(&mut self, actor_entity: Entity, destination: Vec3) {
+        let mut actor = self.world_mut().entity_mut(actor_entity);
+        *actor.get_mut::<Navigation>().unwrap() = Navigation::new(2.0);
+        **actor.get_mut::<NavDestination>().unwrap() = Some(destination);
+    }
+
+    fn update_until(&mut self, what: &str, mut condition: impl FnMut(&mut World) -> bool) {
+        let start = Instant::now();
+        while !condition(self.world_mut()) {
+            assert!(
+                start.elapsed() < LOADING_TIMEOUT,
+                "{what} should finish in {LOADING_TIMEOUT:?}"
+            );
+            self.update();
+        }
+    }
+
+    fn update_ticks(&mut self, ticks: usize) {
+        for _ in 0..ticks {
+            self.update();
+        }
+    }
+}
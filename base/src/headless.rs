@@ -0,0 +1,56 @@
+//! Engine plugins for apps without a window and a GPU.
+//!
+//! Used by the dedicated server and by test apps.
+//! Base plugins register render assets like meshes, materials and animation graphs,
+//! so the asset part of rendering plugins is kept, but without a GPU backend.
+
+use bevy::{
+    animation::AnimationPlugin,
+    app::PluginGroupBuilder,
+    gizmos::GizmoPlugin,
+    gltf::GltfPlugin,
+    input::InputPlugin,
+    pbr::PbrPlugin,
+    prelude::*,
+    render::{
+        settings::{RenderCreation, WgpuSettings},
+        RenderPlugin,
+    },
+    scene::ScenePlugin,
+    state::app::StatesPlugin,
+    text::TextPlugin,
+    window::ExitCondition,
+};
+
+pub struct HeadlessPlugins;
+
+impl PluginGroup for HeadlessPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add_group(MinimalPlugins)
+            .add(TransformPlugin)
+            .add(HierarchyPlugin)
+            .add(InputPlugin)
+            .add(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .add(AssetPlugin::default())
+            .add(ScenePlugin)
+            .add(RenderPlugin {
+                render_creation: RenderCreation::Automatic(WgpuSettings {
+                    backends: None,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .add(ImagePlugin::default())
+            .add(TextPlugin)
+            .add(PbrPlugin::default())
+            .add(GltfPlugin::default())
+            .add(AnimationPlugin)
+            .add(GizmoPlugin)
+            .add(StatesPlugin)
+    }
+}
@@ -8,6 +8,7 @@ pub mod error_message;
 pub mod game_paths;
 pub mod game_world;
 mod ghost;
+pub mod headless;
 pub mod network;
 pub mod settings;
 
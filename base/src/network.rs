@@ -3,11 +3,11 @@ use std::{
     time::SystemTime,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use bevy::prelude::*;
 use bevy_replicon_renet::netcode::{
     ClientAuthentication, NetcodeClientTransport, NetcodeServerTransport, ServerAuthentication,
-    ServerConfig,
+    ServerConfig, NETCODE_USER_DATA_BYTES,
 };
 
 pub const DEFAULT_PORT: u16 = 4761;
@@ -31,7 +31,14 @@ pub fn create_server(port: u16) -> Result<NetcodeServerTransport> {
     Ok(transport)
 }
 
-pub fn create_client(ip: IpAddr, port: u16) -> Result<NetcodeClientTransport> {
+/// Creates a client transport.
+///
+/// The admin password is passed in the connection user data and checked by the server on connection.
+pub fn create_client(
+    ip: IpAddr,
+    port: u16,
+    admin_password: Option<&str>,
+) -> Result<NetcodeClientTransport> {
     info!("creating client transport for {ip}:{port}");
 
     let current_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
@@ -42,9 +49,22 @@ pub fn create_client(ip: IpAddr, port: u16) -> Result<NetcodeClientTransport> {
         client_id,
         protocol_id: PROTOCOL_ID,
         server_addr,
-        user_data: None,
+        user_data: admin_password.map(password_data).transpose()?,
     };
     let transport = NetcodeClientTransport::new(current_time, authentication, socket)?;
 
     Ok(transport)
 }
+
+/// Encodes the password into connection user data.
+pub(crate) fn password_data(password: &str) -> Result<[u8; NETCODE_USER_DATA_BYTES]> {
+    let bytes = password.as_bytes();
+    if bytes.len() > NETCODE_USER_DATA_BYTES {
+        bail!("password shouldn't be longer than {NETCODE_USER_DATA_BYTES} bytes");
+    }
+
+    let mut user_data = [0; NETCODE_USER_DATA_BYTES];
+    user_data[..bytes.len()].copy_from_slice(bytes);
+
+    Ok(user_data)
+}
@@ -10,7 +10,9 @@ use bevy_enhanced_input::prelude::*;
 use serde::{Deserialize, Serialize};
 use vleue_navigator::prelude::*;
 
-use super::{error_message::error_message, game_paths::GamePaths};
+use super::{
+    error_message::error_message, game_paths::GamePaths, game_world::city::weather::Weather,
+};
 
 pub(super) struct SettingsPlugin;
 
@@ -199,4 +201,6 @@ pub struct DeveloperSettings {
     pub colliders: bool,
     pub paths: bool,
     pub nav_mesh: bool,
+    /// Overrides weather rolls in all cities.
+    pub forced_weather: Option<Weather>,
 }
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+use bevy_simple_text_input::{TextInputInactive, TextInputSubmitEvent};
+use project_harmonia_base::{
+    core::GameState,
+    game_world::chat::{ChatChannel, ChatHistory, ChatSend},
+};
+use project_harmonia_widgets::{
+    button::{ButtonKind, ExclusiveButton, Toggled},
+    label::LabelKind,
+    scroll_view::ScrollView,
+    text_edit::TextEdit,
+    theme::Theme,
+};
+use strum::IntoEnumIterator;
+
+pub(super) struct ChatNodePlugin;
+
+impl Plugin for ChatNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), setup)
+            .add_systems(
+                Update,
+                (
+                    send,
+                    update_messages.run_if(resource_changed::<ChatHistory>),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+const CHAT_WIDTH: f32 = 350.0;
+const MESSAGES_HEIGHT: f32 = 200.0;
+
+fn setup(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    root_entity: Single<Entity, (With<Node>, Without<Parent>)>,
+) {
+    debug!("showing chat");
+    let mut edit_entity = Entity::PLACEHOLDER;
+    commands.entity(*root_entity).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(GameState::InGame),
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    bottom: Val::Percent(25.0),
+                    width: Val::Px(CHAT_WIDTH),
+                    flex_direction: FlexDirection::Column,
+                    padding: theme.padding.normal,
+                    row_gap: theme.gap.normal,
+                    ..Default::default()
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    ChatMessages,
+                    ScrollView,
+                    Node {
+                        height: Val::Px(MESSAGES_HEIGHT),
+                        ..Default::default()
+                    },
+                ));
+
+                parent
+                    .spawn(Node {
+                        column_gap: theme.gap.normal,
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for channel in ChatChannel::iter() {
+                            parent
+                                .spawn((
+                                    channel,
+                                    ButtonKind::Symbol,
+                                    ExclusiveButton,
+                                    Toggled(channel == Default::default()),
+                                ))
+                                .with_child(Text::new(channel.glyph()));
+                        }
+
+                        edit_entity = parent
+                            .spawn((
+                                ChatEdit,
+                                Node {
+                                    flex_grow: 1.0,
+                                    ..Default::default()
+                                },
+                            ))
+                            .id();
+                    });
+            });
+    });
+
+    // Inserted after the theme activates the edit to not steal keyboard input from the game.
+    commands.entity(edit_entity).insert(TextInputInactive(true));
+}
+
+fn send(
+    mut submit_events: EventReader<TextInputSubmitEvent>,
+    mut send_events: EventWriter<ChatSend>,
+    mut edits: Query<&mut TextInputInactive, With<ChatEdit>>,
+    buttons: Query<(&Toggled, &ChatChannel)>,
+) {
+    for event in submit_events.read() {
+        let Ok(mut inactive) = edits.get_mut(event.entity) else {
+            continue;
+        };
+
+        // Return keyboard to the game after sending.
+        inactive.0 = true;
+        if event.value.trim().is_empty() {
+            continue;
+        }
+
+        let channel = buttons
+            .iter()
+            .find(|(toggled, _)| toggled.0)
+            .map(|(_, &channel)| channel)
+            .unwrap_or_default();
+
+        info!("sending chat message to {channel:?}");
+        send_events.send(ChatSend {
+            channel,
+            text: event.value.clone(),
+        });
+    }
+}
+
+fn update_messages(
+    mut commands: Commands,
+    history: Res<ChatHistory>,
+    messages_node: Single<(Entity, &mut ScrollPosition), With<ChatMessages>>,
+) {
+    let (messages_entity, mut scroll_position) = messages_node.into_inner();
+    trace!("updating {} chat messages", history.len());
+
+    commands
+        .entity(messages_entity)
+        .despawn_descendants()
+        .with_children(|parent| {
+            for message in history.iter() {
+                let text = match &message.sender {
+                    Some(sender) => format!("[{:?}] {sender}: {}", message.channel, message.text),
+                    None => message.text.clone(),
+                };
+                parent.spawn((Text::new(text), LabelKind::Small));
+            }
+        });
+
+    // Clamped by the layout.
+    scroll_position.offset_y = f32::MAX;
+}
+
+#[derive(Component)]
+#[require(TextEdit)]
+struct ChatEdit;
+
+#[derive(Component)]
+struct ChatMessages;
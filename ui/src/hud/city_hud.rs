@@ -1,8 +1,11 @@
 mod roads_node;
+mod terrain_node;
+mod water_node;
 
 use bevy::prelude::*;
 use project_harmonia_base::{
     asset::manifest::{
+        ground_manifest::GroundManifest,
         object_manifest::{ObjectCategory, ObjectManifest},
         road_manifest::RoadManifest,
     },
@@ -16,12 +19,14 @@ use strum::IntoEnumIterator;
 
 use crate::hud::{objects_node, tools_node};
 use roads_node::RoadsNodePlugin;
+use terrain_node::TerrainNodePlugin;
+use water_node::WaterNodePlugin;
 
 pub(super) struct CityHudPlugin;
 
 impl Plugin for CityHudPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(RoadsNodePlugin)
+        app.add_plugins((RoadsNodePlugin, TerrainNodePlugin, WaterNodePlugin))
             .add_systems(OnEnter(WorldState::City), setup)
             .add_systems(Update, set_city_mode.run_if(in_state(WorldState::City)));
     }
@@ -34,6 +39,7 @@ fn setup(
     asset_server: Res<AssetServer>,
     object_manifests: Res<Assets<ObjectManifest>>,
     road_manifests: Res<Assets<RoadManifest>>,
+    ground_manifests: Res<Assets<GroundManifest>>,
     root_entity: Single<Entity, (With<Node>, Without<Parent>)>,
 ) {
     debug!("showing city HUD");
@@ -91,6 +97,14 @@ fn setup(
                                 &theme,
                                 &road_manifests,
                             ),
+                            CityMode::Terrain => terrain_node::setup(
+                                parent,
+                                &mut tab_commands,
+                                &asset_server,
+                                &theme,
+                                &ground_manifests,
+                            ),
+                            CityMode::Water => water_node::setup(parent),
                         })
                         .id();
 
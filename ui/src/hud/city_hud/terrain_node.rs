@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use project_harmonia_base::{
+    asset::manifest::ground_manifest::GroundManifest,
+    game_world::city::{
+        terrain::{sculpting_brush::PaintGroundId, TerrainTool},
+        CityMode,
+    },
+};
+use project_harmonia_widgets::{
+    button::{ButtonKind, ExclusiveButton, TabContent, Toggled},
+    label::LabelKind,
+    popup::Popup,
+    theme::Theme,
+};
+use strum::IntoEnumIterator;
+
+pub(super) struct TerrainNodePlugin;
+
+impl Plugin for TerrainNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(CityMode::Terrain), sync_terrain_tool)
+            .add_systems(
+                Update,
+                (select, show_popup, set_terrain_tool).run_if(in_state(CityMode::Terrain)),
+            );
+    }
+}
+
+fn select(mut commands: Commands, buttons: Query<(&Toggled, &GroundButton), Changed<Toggled>>) {
+    for (toggled, ground_button) in &buttons {
+        if toggled.0 {
+            debug!("selecting ground `{:?}` for painting", ground_button.0);
+            commands.insert_resource(PaintGroundId(ground_button.0));
+        }
+    }
+}
+
+fn show_popup(
+    mut commands: Commands,
+    manifests: Res<Assets<GroundManifest>>,
+    root_entity: Single<Entity, (With<Node>, Without<Parent>)>,
+    buttons: Query<(Entity, &Interaction, &GroundButton), Changed<Interaction>>,
+) {
+    for (button_entity, &interaction, &ground_button) in &buttons {
+        if interaction != Interaction::Hovered {
+            continue;
+        }
+
+        let manifest = manifests.get(*ground_button).unwrap();
+        info!("showing popup for ground '{}'", manifest.general.name);
+        commands.entity(*root_entity).with_children(|parent| {
+            parent
+                .spawn(Popup { button_entity })
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            LabelKind::Normal,
+                            Text::new(manifest.general.name.clone() + "\n\n"),
+                        ))
+                        .with_child((
+                            LabelKind::Small,
+                            TextSpan::new(format!(
+                                "{}\n{}",
+                                manifest.general.license, manifest.general.author,
+                            )),
+                        ));
+                });
+        });
+    }
+}
+
+fn set_terrain_tool(
+    mut commands: Commands,
+    buttons: Query<(Ref<Toggled>, &TerrainTool), Changed<Toggled>>,
+) {
+    for (toggled, &tool) in &buttons {
+        if toggled.0 && !toggled.is_added() {
+            info!("changing terrain tool to `{tool:?}`");
+            commands.set_state(tool);
+        }
+    }
+}
+
+/// Sets tool to the last selected.
+///
+/// Needed because on swithicng tab the tool resets, but selected button doesn't.
+fn sync_terrain_tool(mut commands: Commands, buttons: Query<(&Toggled, &TerrainTool)>) {
+    for (toggled, &tool) in &buttons {
+        if toggled.0 {
+            debug!("syncing terrain tool to `{tool:?}`");
+            commands.set_state(tool);
+        }
+    }
+}
+
+pub(super) fn setup(
+    parent: &mut ChildBuilder,
+    tab_commands: &mut Commands,
+    asset_server: &AssetServer,
+    theme: &Theme,
+    manifests: &Assets<GroundManifest>,
+) {
+    let tabs_entity = parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        })
+        .id();
+
+    for tool in TerrainTool::iter() {
+        let mut button_entity = tab_commands.spawn((
+            tool,
+            ExclusiveButton,
+            Toggled(tool == Default::default()),
+            ButtonKind::Symbol,
+        ));
+
+        button_entity
+            .with_child(Text::new(tool.glyph()))
+            .set_parent(tabs_entity);
+
+        if tool == TerrainTool::Paint {
+            let content_entity = parent
+                .spawn(Node {
+                    display: Display::Grid,
+                    column_gap: theme.gap.normal,
+                    row_gap: theme.gap.normal,
+                    padding: theme.padding.normal,
+                    grid_template_columns: vec![GridTrack::auto(); 8],
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for (id, manifest) in manifests.iter() {
+                        parent.spawn(GroundButton(id)).with_child(ImageNode {
+                            image: asset_server.load(manifest.preview.clone()),
+                            ..Default::default()
+                        });
+                    }
+                })
+                .id();
+
+            button_entity.insert(TabContent(content_entity));
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Deref)]
+#[require(
+    Name(|| Name::new("Ground button")),
+    ButtonKind(|| ButtonKind::Image),
+    ExclusiveButton
+)]
+struct GroundButton(AssetId<GroundManifest>);
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use project_harmonia_base::game_world::city::{water::WaterTool, CityMode};
+use project_harmonia_widgets::button::{ButtonKind, ExclusiveButton, Toggled};
+use strum::IntoEnumIterator;
+
+pub(super) struct WaterNodePlugin;
+
+impl Plugin for WaterNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(CityMode::Water), sync_water_tool)
+            .add_systems(Update, set_water_tool.run_if(in_state(CityMode::Water)));
+    }
+}
+
+fn set_water_tool(
+    mut commands: Commands,
+    buttons: Query<(Ref<Toggled>, &WaterTool), Changed<Toggled>>,
+) {
+    for (toggled, &tool) in &buttons {
+        if toggled.0 && !toggled.is_added() {
+            info!("changing water tool to `{tool:?}`");
+            commands.set_state(tool);
+        }
+    }
+}
+
+/// Sets tool to the last selected.
+///
+/// Needed because on swithicng tab the tool resets, but selected button doesn't.
+fn sync_water_tool(mut commands: Commands, buttons: Query<(&Toggled, &WaterTool)>) {
+    for (toggled, &tool) in &buttons {
+        if toggled.0 {
+            debug!("syncing water tool to `{tool:?}`");
+            commands.set_state(tool);
+        }
+    }
+}
+
+pub(super) fn setup(parent: &mut ChildBuilder) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for tool in WaterTool::iter() {
+                parent
+                    .spawn((
+                        tool,
+                        ButtonKind::Symbol,
+                        ExclusiveButton,
+                        Toggled(tool == Default::default()),
+                    ))
+                    .with_child(Text::new(tool.glyph()));
+            }
+        });
+}
@@ -6,13 +6,13 @@ mod tasks_node;
 
 use bevy::prelude::*;
 use project_harmonia_base::{
-    asset::manifest::object_manifest::ObjectManifest,
+    asset::manifest::{career_manifest::CareerManifest, object_manifest::ObjectManifest},
     game_world::{
         actor::{
             task::{ActiveTask, Task},
             SelectedActor,
         },
-        family::{self, Budget, FamilyMembers, FamilyMode, SelectedFamily},
+        family::{self, bills::PropertyValue, Budget, FamilyMembers, FamilyMode, SelectedFamily},
         WorldState,
     },
 };
@@ -46,9 +46,10 @@ fn setup(
     mut tab_commands: Commands,
     theme: Res<Theme>,
     object_manifests: Res<Assets<ObjectManifest>>,
+    career_manifests: Res<Assets<CareerManifest>>,
     root_entity: Single<Entity, (With<Node>, Without<Parent>)>,
     actor_children: Single<&Children, With<SelectedActor>>,
-    selected_family: Single<(&Budget, &FamilyMembers), With<SelectedFamily>>,
+    selected_family: Single<(&Budget, &PropertyValue, &FamilyMembers), With<SelectedFamily>>,
     selected_entity: Single<Entity, With<SelectedActor>>,
     tasks: Query<(Entity, Has<ActiveTask>), With<Task>>,
 ) {
@@ -90,10 +91,15 @@ fn setup(
                             FamilyMode::Life => {
                                 tasks_node::setup(parent, &theme, *actor_children, &tasks);
 
-                                let (&budget, members) = *selected_family;
-                                portrait_node::setup(parent, &theme, budget);
+                                let (&budget, &property_value, members) = *selected_family;
+                                portrait_node::setup(parent, &theme, budget, property_value);
                                 members_node::setup(parent, &theme, members, *selected_entity);
-                                info_node::setup(parent, &mut tab_commands, &theme);
+                                info_node::setup(
+                                    parent,
+                                    &mut tab_commands,
+                                    &theme,
+                                    &career_manifests,
+                                );
                             }
                             FamilyMode::Building => building_hud::setup(
                                 parent,
@@ -1,3 +1,4 @@
+mod door_lock_node;
 mod walls_node;
 
 use bevy::prelude::*;
@@ -12,13 +13,14 @@ use project_harmonia_widgets::{
 use strum::IntoEnumIterator;
 
 use crate::hud::{objects_node, tools_node};
+use door_lock_node::DoorLockNodePlugin;
 use walls_node::WallsNodePlugin;
 
 pub(super) struct BuildingHudPlugin;
 
 impl Plugin for BuildingHudPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(WallsNodePlugin)
+        app.add_plugins((DoorLockNodePlugin, WallsNodePlugin))
             .add_systems(OnEnter(FamilyMode::Building), sync_building_mode);
     }
 }
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use project_harmonia_base::game_world::object::{door::DoorLock, placing_object::PlacingObject};
+use project_harmonia_widgets::{
+    button::{ButtonKind, ExclusiveButton, Toggled},
+    theme::Theme,
+};
+use strum::IntoEnumIterator;
+
+pub(super) struct DoorLockNodePlugin;
+
+impl Plugin for DoorLockNodePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(setup)
+            .add_observer(cleanup)
+            .add_systems(Update, set_lock.never_param_warn());
+    }
+}
+
+/// Shows lock buttons when a door is picked.
+fn setup(
+    trigger: Trigger<OnAdd, DoorLock>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    root_entity: Single<Entity, (With<Node>, Without<Parent>)>,
+    placing_objects: Query<&DoorLock, With<PlacingObject>>,
+) {
+    let Ok(&current_lock) = placing_objects.get(trigger.entity()) else {
+        return;
+    };
+
+    debug!("showing lock buttons for `{}`", trigger.entity());
+    commands.entity(*root_entity).with_children(|parent| {
+        parent
+            .spawn((
+                LockNode,
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    align_self: AlignSelf::Center,
+                    padding: theme.padding.normal,
+                    column_gap: theme.gap.normal,
+                    ..Default::default()
+                },
+                theme.panel_background,
+            ))
+            .with_children(|parent| {
+                for lock in DoorLock::iter() {
+                    parent
+                        .spawn((
+                            lock,
+                            ButtonKind::Symbol,
+                            ExclusiveButton,
+                            Toggled(lock == current_lock),
+                        ))
+                        .with_child(Text::new(lock.glyph()));
+                }
+            });
+    });
+}
+
+fn set_lock(
+    mut placing_lock: Single<&mut DoorLock, With<PlacingObject>>,
+    buttons: Query<(Ref<Toggled>, &DoorLock), Without<PlacingObject>>,
+) {
+    for (toggled, &lock) in &buttons {
+        if toggled.0 && toggled.is_changed() && !toggled.is_added() {
+            info!("changing door lock to `{lock:?}`");
+            **placing_lock = lock;
+        }
+    }
+}
+
+fn cleanup(
+    trigger: Trigger<OnRemove, PlacingObject>,
+    mut commands: Commands,
+    lock_node: Option<Single<Entity, With<LockNode>>>,
+) {
+    if let Some(lock_node) = lock_node {
+        debug!("hiding lock buttons for `{}`", trigger.entity());
+        commands.entity(*lock_node).despawn_recursive();
+    }
+}
+
+#[derive(Component)]
+struct LockNode;
@@ -1,13 +1,20 @@
 use bevy::prelude::*;
-use project_harmonia_base::game_world::{
-    actor::{
-        needs::{Need, NeedGlyph},
-        SelectedActor,
+use project_harmonia_base::{
+    asset::manifest::career_manifest::CareerManifest,
+    game_world::{
+        actor::{
+            job::{Job, JobChange},
+            needs::{Need, NeedGlyph},
+            skills::{Skill, SkillGlyph, SkillKind},
+            travel::{TravelRequest, Traveling},
+            SelectedActor,
+        },
+        city::City,
+        WorldState,
     },
-    WorldState,
 };
 use project_harmonia_widgets::{
-    button::{ButtonKind, TabContent, Toggled},
+    button::{ButtonKind, ExclusiveButton, TabContent, Toggled},
     label::LabelKind,
     progress_bar::ProgressBar,
     theme::Theme,
@@ -18,10 +25,18 @@ pub(super) struct InfoNodePlugin;
 
 impl Plugin for InfoNodePlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(cleanup_need_bars).add_systems(
-            Update,
-            update_need_bars.run_if(in_state(WorldState::Family)),
-        );
+        app.add_observer(cleanup_need_bars)
+            .add_observer(cleanup_skill_rows)
+            .add_systems(
+                Update,
+                (
+                    update_need_bars,
+                    update_skill_rows,
+                    update_job,
+                    update_travel,
+                )
+                    .run_if(in_state(WorldState::Family)),
+            );
     }
 }
 
@@ -76,7 +91,190 @@ fn cleanup_need_bars(
     }
 }
 
-pub(super) fn setup(parent: &mut ChildBuilder, tab_commands: &mut Commands, theme: &Theme) {
+fn update_skill_rows(
+    mut commands: Commands,
+    selected_actor: Single<(&Children, Ref<SelectedActor>)>,
+    skills: Query<(Entity, &SkillGlyph, &SkillKind, Ref<Skill>)>,
+    tabs: Query<(&TabContent, &InfoTab)>,
+    mut progress_bars: Query<(&mut ProgressBar, &RowSkill)>,
+    mut texts: Query<(&mut Text, &RowSkill)>,
+) {
+    let (children, selected_actor) = selected_actor.into_inner();
+    let (tab_content, _) = tabs
+        .iter()
+        .find(|(_, &tab)| tab == InfoTab::Skills)
+        .expect("tab with skills should be spawned on state enter");
+
+    if selected_actor.is_added() {
+        commands.entity(tab_content.0).despawn_descendants();
+    }
+
+    for (entity, glyph, kind, skill) in skills
+        .iter_many(children)
+        .filter(|(.., skill)| skill.is_changed() || selected_actor.is_added())
+    {
+        let level = format!("{}/{}", skill.level(), Skill::MAX_LEVEL);
+        if let Some((mut progress_bar, _)) = progress_bars
+            .iter_mut()
+            .find(|(_, row_skill)| row_skill.0 == entity)
+        {
+            trace!("updating row with `{skill:?}` for `{entity}`");
+            progress_bar.0 = skill.progress() * 100.0;
+            let (mut text, _) = texts
+                .iter_mut()
+                .find(|(_, row_skill)| row_skill.0 == entity)
+                .expect("skill rows should have level text");
+            text.0 = level;
+        } else {
+            trace!("creating row with `{skill:?}` for `{entity}`");
+            commands.entity(tab_content.0).with_children(|parent| {
+                parent.spawn((RowSkill(entity), LabelKind::Symbol, Text::new(glyph.0)));
+                parent.spawn((RowSkill(entity), LabelKind::Normal, Text::new(kind.name())));
+                parent.spawn((RowSkill(entity), LabelKind::Normal, Text::new(level)));
+                parent.spawn((RowSkill(entity), ProgressBar(skill.progress() * 100.0)));
+            });
+        }
+    }
+}
+
+fn cleanup_skill_rows(
+    trigger: Trigger<OnRemove, Skill>,
+    mut commands: Commands,
+    rows: Query<(Entity, &RowSkill)>,
+) {
+    for (entity, _) in rows
+        .iter()
+        .filter(|(_, row_skill)| row_skill.0 == trigger.entity())
+    {
+        debug!("despawning `{entity}` for skill `{}`", trigger.entity());
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn update_job(
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<CareerManifest>>,
+    mut removed_jobs: RemovedComponents<Job>,
+    selected_actor: Single<(Option<Ref<Job>>, Ref<SelectedActor>)>,
+    mut job_text: Single<&mut Text, With<JobLabel>>,
+    mut buttons: Query<(&mut Toggled, &CareerButton)>,
+) {
+    let (job, selected_actor) = selected_actor.into_inner();
+    let job_changed = job.as_ref().is_some_and(|job| job.is_changed());
+    if !selected_actor.is_added() && !job_changed && removed_jobs.read().count() == 0 {
+        return;
+    }
+
+    let career_id = job
+        .as_ref()
+        .and_then(|job| asset_server.get_handle(&job.career))
+        .map(|handle| handle.id());
+    for (mut toggled, career_button) in &mut buttons {
+        toggled.0 = career_button.0 == career_id;
+    }
+
+    let manifest = career_id.and_then(|id| manifests.get(id));
+    job_text.0 = match (job, manifest) {
+        (Some(job), Some(manifest)) => {
+            let level = &manifest.levels[job.level.min(manifest.levels.len() - 1)];
+            format!(
+                "{} {}\nPay: {}, hours: {}-{}\nPerformance: {:.0}/{}",
+                manifest.glyph,
+                level.name,
+                level.pay,
+                level.start_hour,
+                level.end_hour,
+                job.performance,
+                Job::MAX_PERFORMANCE,
+            )
+        }
+        _ => "Unemployed".to_string(),
+    };
+}
+
+fn change_job(
+    trigger: Trigger<Pointer<Click>>,
+    asset_server: Res<AssetServer>,
+    mut change_events: EventWriter<JobChange>,
+    buttons: Query<&CareerButton>,
+    actor_entity: Single<Entity, With<SelectedActor>>,
+) {
+    let career_button = buttons.get(trigger.entity()).unwrap();
+    let career = career_button
+        .0
+        .and_then(|id| asset_server.get_path(id))
+        .map(|path| path.into_owned());
+    info!("changing job for `{}` to '{career:?}'", *actor_entity);
+    change_events.send(JobChange {
+        actor_entity: *actor_entity,
+        career,
+    });
+}
+
+fn update_travel(
+    mut commands: Commands,
+    mut removed_travels: RemovedComponents<Traveling>,
+    selected_actor: Single<(Ref<Parent>, Option<Ref<Traveling>>, Ref<SelectedActor>)>,
+    mut travel_text: Single<&mut Text, With<TravelLabel>>,
+    buttons_entity: Single<Entity, With<CityButtons>>,
+    cities: Query<(Entity, &Name), With<City>>,
+) {
+    let (parent, traveling, selected_actor) = selected_actor.into_inner();
+    let traveling_changed = traveling
+        .as_ref()
+        .is_some_and(|traveling| traveling.is_changed());
+    if !selected_actor.is_added()
+        && !parent.is_changed()
+        && !traveling_changed
+        && removed_travels.read().count() == 0
+    {
+        return;
+    }
+
+    let city_name = |entity| {
+        cities
+            .get(entity)
+            .map(|(_, name)| name.as_str())
+            .unwrap_or_default()
+    };
+    travel_text.0 = match traveling {
+        Some(traveling) => format!("On the way to {}", city_name(traveling.city_entity)),
+        None => format!("In {}", city_name(**parent)),
+    };
+
+    commands.entity(*buttons_entity).despawn_descendants();
+    commands
+        .entity(*buttons_entity)
+        .with_children(|parent_node| {
+            for (city_entity, name) in cities.iter().filter(|&(entity, _)| entity != **parent) {
+                parent_node
+                    .spawn(TravelButton(city_entity))
+                    .with_child(Text::new(name.as_str()))
+                    .observe(travel);
+            }
+        });
+}
+
+fn travel(
+    trigger: Trigger<Pointer<Click>>,
+    mut travel_events: EventWriter<TravelRequest>,
+    buttons: Query<&TravelButton>,
+    actor_entity: Single<Entity, With<SelectedActor>>,
+) {
+    let travel_button = buttons.get(trigger.entity()).unwrap();
+    info!("sending `{}` to `{}`", *actor_entity, travel_button.0);
+    travel_events.send(TravelRequest {
+        actor_entity: *actor_entity,
+        city_entity: travel_button.0,
+    });
+}
+
+pub(super) fn setup(
+    parent: &mut ChildBuilder,
+    tab_commands: &mut Commands,
+    theme: &Theme,
+    career_manifests: &Assets<CareerManifest>,
+) {
     parent
         .spawn(Node {
             flex_direction: FlexDirection::ColumnReverse,
@@ -99,7 +297,25 @@ pub(super) fn setup(parent: &mut ChildBuilder, tab_commands: &mut Commands, them
 
             for (index, tab) in InfoTab::iter().enumerate() {
                 let content_entity = match tab {
-                    InfoTab::Skills => parent.spawn(Node::default()).id(),
+                    InfoTab::Skills => parent
+                        .spawn((
+                            Node {
+                                display: Display::Grid,
+                                width: Val::Px(400.0),
+                                column_gap: theme.gap.normal,
+                                row_gap: theme.gap.normal,
+                                padding: theme.padding.normal,
+                                grid_template_columns: vec![
+                                    GridTrack::auto(),
+                                    GridTrack::auto(),
+                                    GridTrack::auto(),
+                                    GridTrack::flex(1.0),
+                                ],
+                                ..Default::default()
+                            },
+                            theme.panel_background,
+                        ))
+                        .id(),
                     InfoTab::Needs => parent
                         .spawn((
                             Node {
@@ -119,6 +335,72 @@ pub(super) fn setup(parent: &mut ChildBuilder, tab_commands: &mut Commands, them
                             theme.panel_background,
                         ))
                         .id(),
+                    InfoTab::Job => parent
+                        .spawn((
+                            Node {
+                                flex_direction: FlexDirection::Column,
+                                width: Val::Px(400.0),
+                                row_gap: theme.gap.normal,
+                                padding: theme.padding.normal,
+                                ..Default::default()
+                            },
+                            theme.panel_background,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((JobLabel, LabelKind::Normal, Text::default()));
+                            parent
+                                .spawn(Node {
+                                    flex_wrap: FlexWrap::Wrap,
+                                    column_gap: theme.gap.normal,
+                                    row_gap: theme.gap.normal,
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    parent
+                                        .spawn(CareerButton(None))
+                                        .with_child(Text::new("Unemployed"))
+                                        .observe(change_job);
+
+                                    let mut manifests: Vec<_> = career_manifests.iter().collect();
+                                    manifests.sort_by(|(_, a), (_, b)| {
+                                        a.general.name.cmp(&b.general.name)
+                                    });
+                                    for (id, manifest) in manifests {
+                                        parent
+                                            .spawn(CareerButton(Some(id)))
+                                            .with_child(Text::new(format!(
+                                                "{} {}",
+                                                manifest.glyph, manifest.general.name
+                                            )))
+                                            .observe(change_job);
+                                    }
+                                });
+                        })
+                        .id(),
+                    InfoTab::Travel => parent
+                        .spawn((
+                            Node {
+                                flex_direction: FlexDirection::Column,
+                                width: Val::Px(400.0),
+                                row_gap: theme.gap.normal,
+                                padding: theme.padding.normal,
+                                ..Default::default()
+                            },
+                            theme.panel_background,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((TravelLabel, LabelKind::Normal, Text::default()));
+                            parent.spawn((
+                                CityButtons,
+                                Node {
+                                    flex_wrap: FlexWrap::Wrap,
+                                    column_gap: theme.gap.normal,
+                                    row_gap: theme.gap.normal,
+                                    ..Default::default()
+                                },
+                            ));
+                        })
+                        .id(),
                 };
 
                 tab_commands
@@ -137,10 +419,38 @@ pub(super) fn setup(parent: &mut ChildBuilder, tab_commands: &mut Commands, them
 #[derive(Component)]
 struct BarNeed(Entity);
 
+/// Points to the skill displayed by the row element.
+#[derive(Component)]
+struct RowSkill(Entity);
+
+#[derive(Component)]
+struct JobLabel;
+
+#[derive(Component)]
+#[require(
+    Name(|| Name::new("Career button")),
+    ButtonKind(|| ButtonKind::Normal),
+    ExclusiveButton,
+)]
+struct CareerButton(Option<AssetId<CareerManifest>>);
+
+#[derive(Component)]
+struct TravelLabel;
+
+/// Container for [`TravelButton`]s.
+#[derive(Component)]
+struct CityButtons;
+
+#[derive(Component)]
+#[require(Name(|| Name::new("Travel button")), ButtonKind(|| ButtonKind::Normal))]
+struct TravelButton(Entity);
+
 #[derive(Component, EnumIter, Clone, Copy, PartialEq)]
 enum InfoTab {
     Skills,
     Needs,
+    Job,
+    Travel,
 }
 
 impl InfoTab {
@@ -148,6 +458,8 @@ impl InfoTab {
         match self {
             InfoTab::Skills => "💡",
             InfoTab::Needs => "📈",
+            InfoTab::Job => "💼",
+            InfoTab::Travel => "🚌",
         }
     }
 }
@@ -1,9 +1,19 @@
 use bevy::prelude::*;
-use project_harmonia_base::game_world::{
-    family::{Budget, SelectedFamily},
-    WorldState,
+use project_harmonia_base::{
+    asset::manifest::mood_manifest::MoodManifest,
+    game_world::{
+        actor::{
+            mood::{Mood, MoodModifiers},
+            SelectedActor,
+        },
+        clock::WorldClock,
+        family::{bills::PropertyValue, Budget, SelectedFamily},
+        WorldState,
+    },
+};
+use project_harmonia_widgets::{
+    label::LabelKind, popup::Popup, progress_bar::ProgressBar, theme::Theme,
 };
-use project_harmonia_widgets::{label::LabelKind, theme::Theme};
 
 pub(super) struct PortraitNodePlugin;
 
@@ -11,8 +21,12 @@ impl Plugin for PortraitNodePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            update_budget
-                .never_param_warn()
+            (
+                update_budget.never_param_warn(),
+                update_property_value.never_param_warn(),
+                update_mood.never_param_warn(),
+                show_mood_popup,
+            )
                 .run_if(in_state(WorldState::Family)),
         );
     }
@@ -26,23 +40,154 @@ fn update_budget(
     ***budget_label = current_budget.to_string();
 }
 
-pub(super) fn setup(parent: &mut ChildBuilder, theme: &Theme, budget: Budget) {
+fn update_property_value(
+    property_value: Single<&PropertyValue, (With<SelectedFamily>, Changed<PropertyValue>)>,
+    mut property_label: Single<&mut Text, With<PropertyLabel>>,
+) {
+    debug!("changing property value to `{:?}`", **property_value);
+    ***property_label = property_text(**property_value);
+}
+
+fn property_text(property_value: PropertyValue) -> String {
+    format!("🏠 {}", *property_value)
+}
+
+fn update_mood(
+    selected_actor: Single<(Ref<Mood>, Ref<SelectedActor>)>,
+    mut mood_label: Single<&mut Text, With<MoodLabel>>,
+    mood_bar: Single<&Children, With<MoodBar>>,
+    mut progress_bars: Query<&mut ProgressBar>,
+) {
+    let (mood, selected_actor) = selected_actor.into_inner();
+    if !mood.is_changed() && !selected_actor.is_added() {
+        return;
+    }
+
+    trace!("changing mood to `{:?}`", *mood);
+    ***mood_label = mood.level().glyph().to_string();
+    let mut iter = progress_bars.iter_many_mut(*mood_bar);
+    let mut progress_bar = iter
+        .fetch_next()
+        .expect("mood bar should contain progress bar");
+    progress_bar.0 = mood.0;
+}
+
+fn show_mood_popup(
+    mut commands: Commands,
+    clock: Res<WorldClock>,
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<MoodManifest>>,
+    root_entity: Single<Entity, (With<Node>, Without<Parent>)>,
+    buttons: Query<(Entity, &Interaction), (Changed<Interaction>, With<MoodBar>)>,
+    modifiers: Single<&MoodModifiers, With<SelectedActor>>,
+) {
+    for (button_entity, &interaction) in &buttons {
+        if interaction != Interaction::Hovered {
+            continue;
+        }
+
+        debug!("showing mood popup");
+        commands.entity(*root_entity).with_children(|parent| {
+            parent
+                .spawn(Popup { button_entity })
+                .with_children(|parent| {
+                    if modifiers.is_empty() {
+                        parent.spawn((LabelKind::Normal, Text::new("No mood modifiers")));
+                    }
+
+                    for modifier in modifiers.iter() {
+                        let Some(manifest) = asset_server
+                            .get_handle(&modifier.path)
+                            .and_then(|handle| manifests.get(&handle))
+                        else {
+                            continue;
+                        };
+
+                        parent
+                            .spawn((
+                                LabelKind::Normal,
+                                Text::new(format!(
+                                    "{} {} ({:+})",
+                                    manifest.glyph, manifest.general.name, manifest.value
+                                )),
+                            ))
+                            .with_children(|parent| {
+                                if let Some(expires) = modifier.expires {
+                                    let hours = expires.saturating_sub(clock.minutes()) / 60;
+                                    parent.spawn((
+                                        LabelKind::Small,
+                                        TextSpan::new(format!(" {hours}h left")),
+                                    ));
+                                }
+                            });
+                    }
+                });
+        });
+    }
+}
+
+pub(super) fn setup(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    budget: Budget,
+    property_value: PropertyValue,
+) {
     parent
         .spawn((
             Node {
                 width: Val::Px(180.0),
-                height: Val::Px(30.0),
+                flex_direction: FlexDirection::Column,
                 align_self: AlignSelf::FlexEnd,
-                align_items: AlignItems::Center,
+                row_gap: theme.gap.normal,
+                padding: theme.padding.normal,
                 ..Default::default()
             },
             theme.panel_background,
         ))
         .with_children(|parent| {
             parent.spawn((BudgetLabel, Text::new(budget.to_string())));
+            parent.spawn((PropertyLabel, Text::new(property_text(property_value))));
+            parent
+                .spawn(Node {
+                    align_items: AlignItems::Center,
+                    column_gap: theme.gap.normal,
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(MoodLabel);
+                    parent.spawn(MoodBar).with_child((
+                        ProgressBar(Mood::default().0),
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..Default::default()
+                        },
+                    ));
+                });
         });
 }
 
 #[derive(Component)]
 #[require(LabelKind(|| LabelKind::Normal))]
 struct BudgetLabel;
+
+#[derive(Component)]
+#[require(LabelKind(|| LabelKind::Normal))]
+struct PropertyLabel;
+
+#[derive(Component)]
+#[require(LabelKind(|| LabelKind::Symbol), Text)]
+struct MoodLabel;
+
+/// Hoverable node to show mood modifiers in a popup.
+#[derive(Component)]
+#[require(
+    Name(|| Name::new("Mood bar")),
+    Button,
+    Node(|| Node {
+        width: Val::Px(130.0),
+        height: Val::Px(12.0),
+        ..Default::default()
+    }),
+)]
+struct MoodBar;
@@ -1,10 +1,19 @@
 use bevy::prelude::*;
 
-use project_harmonia_base::game_world::actor::{
-    task::{ActiveTask, Task, TaskCancel},
-    SelectedActor,
+use project_harmonia_base::game_world::{
+    actor::{
+        task::{
+            self, ActiveTask, ExpectedDuration, Task, TaskCancel, TaskMove, TaskOrder,
+            TaskPrioritize, TaskPriority,
+        },
+        SelectedActor,
+    },
+    clock::WorldClock,
+    WorldState,
+};
+use project_harmonia_widgets::{
+    button::ButtonKind, label::LabelKind, progress_bar::ProgressBar, theme::Theme,
 };
-use project_harmonia_widgets::{button::ButtonKind, theme::Theme};
 
 pub(super) struct TasksNodePlugin;
 
@@ -13,7 +22,16 @@ impl Plugin for TasksNodePlugin {
         app.add_observer(change_actor.never_param_warn())
             .add_observer(add_task.never_param_warn())
             .add_observer(activate_task.never_param_warn())
-            .add_observer(cleanup);
+            .add_observer(cleanup)
+            .add_systems(
+                Update,
+                (
+                    sort_queued.never_param_warn(),
+                    update_priority_labels,
+                    update_progress.never_param_warn(),
+                )
+                    .run_if(in_state(WorldState::Family)),
+            );
     }
 }
 
@@ -92,10 +110,148 @@ fn cancel(
     mut cancel_events: EventWriter<TaskCancel>,
     buttons: Query<&TaskButton>,
 ) {
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+
     let task_button = buttons.get(trigger.entity()).unwrap();
     cancel_events.send(TaskCancel(task_button.task_entity));
 }
 
+fn change_priority(
+    trigger: Trigger<Pointer<Click>>,
+    mut prioritize_events: EventWriter<TaskPrioritize>,
+    buttons: Query<&TaskButton>,
+    tasks: Query<&TaskPriority>,
+) {
+    if trigger.button != PointerButton::Secondary {
+        return;
+    }
+
+    let task_button = buttons.get(trigger.entity()).unwrap();
+    let Ok(&priority) = tasks.get(task_button.task_entity) else {
+        return;
+    };
+
+    let priority = priority.next();
+    info!(
+        "changing priority of `{}` to `{priority:?}`",
+        task_button.task_entity
+    );
+    prioritize_events.send(TaskPrioritize {
+        task_entity: task_button.task_entity,
+        priority,
+    });
+}
+
+/// Moves the dragged queued task to the position of the task it was dropped on.
+///
+/// Dropping on an active task moves the dragged task to the front of the queue.
+fn reorder(
+    trigger: Trigger<Pointer<DragDrop>>,
+    mut move_events: EventWriter<TaskMove>,
+    queued_node: Single<&Children, With<QueuedTasksNode>>,
+    buttons: Query<&TaskButton>,
+    parents: Query<&Parent>,
+) {
+    // Dragging could start on the button image.
+    let Some(dropped_entity) = [trigger.dropped]
+        .into_iter()
+        .chain(parents.get(trigger.dropped).map(|parent| **parent))
+        .find(|&entity| buttons.get(entity).is_ok())
+    else {
+        return;
+    };
+    if dropped_entity == trigger.entity() || !queued_node.contains(&dropped_entity) {
+        return;
+    }
+
+    let index = queued_node
+        .iter()
+        .position(|&entity| entity == trigger.entity())
+        .unwrap_or_default();
+    let dropped_button = buttons.get(dropped_entity).unwrap();
+    info!("moving task `{}` to {index}", dropped_button.task_entity);
+    move_events.send(TaskMove {
+        task_entity: dropped_button.task_entity,
+        index,
+    });
+}
+
+/// Sorts queued task buttons in activation order.
+fn sort_queued(
+    mut commands: Commands,
+    queued_node: Single<(Entity, &Children), With<QueuedTasksNode>>,
+    changed_tasks: Query<(), Or<(Changed<TaskOrder>, Changed<TaskPriority>)>>,
+    added_buttons: Query<(), Added<TaskButton>>,
+    buttons: Query<(Entity, &TaskButton)>,
+    tasks: Query<(&TaskPriority, &TaskOrder)>,
+) {
+    if changed_tasks.is_empty() && added_buttons.is_empty() {
+        return;
+    }
+
+    let (node_entity, children) = *queued_node;
+    let mut sorted: Vec<_> = buttons
+        .iter_many(children)
+        .filter_map(|(entity, task_button)| {
+            let (&priority, &order) = tasks.get(task_button.task_entity).ok()?;
+            Some((entity, task::queue_key(priority, order)))
+        })
+        .collect();
+    sorted.sort_by_key(|&(_, key)| key);
+
+    let sorted: Vec<_> = sorted.into_iter().map(|(entity, _)| entity).collect();
+    if **children != *sorted {
+        debug!("sorting queued task buttons");
+        commands.entity(node_entity).insert_children(0, &sorted);
+    }
+}
+
+fn update_priority_labels(
+    buttons: Query<(Ref<TaskButton>, &Children)>,
+    tasks: Query<Ref<TaskPriority>>,
+    mut labels: Query<&mut Text, With<PriorityLabel>>,
+) {
+    for (task_button, children) in &buttons {
+        let Ok(priority) = tasks.get(task_button.task_entity) else {
+            continue;
+        };
+        if !task_button.is_added() && !priority.is_changed() {
+            continue;
+        }
+
+        let mut iter = labels.iter_many_mut(children);
+        if let Some(mut text) = iter.fetch_next() {
+            text.0 = priority.glyph().to_string();
+        }
+    }
+}
+
+/// Updates progress bars of active tasks based on their expected duration.
+///
+/// Measured on the world clock, so the progress respects game speed and pause.
+fn update_progress(
+    clock: Res<WorldClock>,
+    active_node: Single<&Children, With<ActiveTasksNode>>,
+    buttons: Query<(&TaskButton, &Children)>,
+    tasks: Query<(&ActiveTask, &ExpectedDuration)>,
+    mut progress_bars: Query<&mut ProgressBar>,
+) {
+    for (task_button, children) in buttons.iter_many(*active_node) {
+        let Ok((active_task, expected_duration)) = tasks.get(task_button.task_entity) else {
+            continue;
+        };
+
+        let elapsed = active_task.elapsed(&clock);
+        let progress = elapsed.as_secs_f32() / expected_duration.as_secs_f32() * 100.0;
+        let mut iter = progress_bars.iter_many_mut(children);
+        if let Some(mut progress_bar) = iter.fetch_next() {
+            progress_bar.0 = progress.min(100.0);
+        }
+    }
+}
+
 fn cleanup(
     trigger: Trigger<OnRemove, Task>,
     mut commands: Commands,
@@ -188,8 +344,31 @@ pub(super) fn setup(
 fn spawn_button(parent: &mut ChildBuilder, task_entity: Entity) {
     parent
         .spawn(TaskButton { task_entity })
-        .with_child(ImageNode::default())
-        .observe(cancel);
+        .with_children(|parent| {
+            parent.spawn(ImageNode::default());
+            parent.spawn((
+                PriorityLabel,
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    right: Val::Px(2.0),
+                    ..Default::default()
+                },
+            ));
+            parent.spawn((
+                ProgressBar(0.0),
+                Node {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(0.0),
+                    width: Val::Percent(100.0),
+                    height: Val::Px(4.0),
+                    ..Default::default()
+                },
+            ));
+        })
+        .observe(cancel)
+        .observe(change_priority)
+        .observe(reorder);
 }
 
 #[derive(Component)]
@@ -205,3 +384,7 @@ struct QueuedTasksNode;
 struct TaskButton {
     task_entity: Entity,
 }
+
+#[derive(Component)]
+#[require(LabelKind(|| LabelKind::Small), Text)]
+struct PriorityLabel;
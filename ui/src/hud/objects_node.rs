@@ -48,7 +48,10 @@ fn show_popup(
                     parent
                         .spawn((
                             LabelKind::Normal,
-                            Text::new(manifest.general.name.clone() + "\n\n"),
+                            Text::new(format!(
+                                "{}\n💲{}\n\n",
+                                manifest.general.name, manifest.price
+                            )),
                         ))
                         .with_child((
                             LabelKind::Small,
@@ -2,18 +2,29 @@ use std::mem;
 
 use bevy::prelude::*;
 use bevy_simple_text_input::TextInputValue;
+use strum::IntoEnumIterator;
 
 use crate::preview::{Preview, PreviewProcessed};
-use project_harmonia_base::game_world::{
-    city::City,
-    family::{
-        editor::{
-            EditorActor, EditorFamily, EditorFamilyReset, EditorFirstName, EditorLastName,
-            EditorSelectedActor, EditorSex, FamilyScene,
+use project_harmonia_base::{
+    asset::manifest::{
+        outfit_manifest::{OutfitCategory, OutfitManifest},
+        trait_manifest::TraitManifest,
+    },
+    game_world::{
+        actor::{
+            appearance::{Appearance, BodyShape},
+            traits::Traits,
+        },
+        city::City,
+        family::{
+            editor::{
+                EditorActor, EditorAppearance, EditorFamily, EditorFamilyReset, EditorFirstName,
+                EditorLastName, EditorSelectedActor, EditorSex, EditorTraits, FamilyScene,
+            },
+            FamilyCreate,
         },
-        FamilyCreate,
+        WorldState,
     },
-    WorldState,
 };
 use project_harmonia_widgets::{
     button::{ButtonKind, ExclusiveButton, Toggled},
@@ -30,12 +41,14 @@ impl Plugin for EditorMenuPlugin {
         app.add_observer(create_actor_buttons)
             .add_observer(remove_actor_buttons)
             .add_observer(display_actor_data)
+            .add_observer(display_appearance)
             .add_systems(OnEnter(WorldState::FamilyEditor), setup)
             .add_systems(
                 Update,
                 (
                     apply_first_name.never_param_warn(),
                     apply_last_name.never_param_warn(),
+                    apply_traits.never_param_warn(),
                     update_previews,
                 )
                     .run_if(in_state(WorldState::FamilyEditor)),
@@ -46,6 +59,9 @@ impl Plugin for EditorMenuPlugin {
 fn setup(
     mut commands: Commands,
     theme: Res<Theme>,
+    asset_server: Res<AssetServer>,
+    trait_manifests: Res<Assets<TraitManifest>>,
+    outfit_manifests: Res<Assets<OutfitManifest>>,
     root_entity: Single<Entity, (With<Node>, Without<Parent>)>,
 ) {
     info!("entering family editor");
@@ -60,7 +76,8 @@ fn setup(
                 },
             ))
             .with_children(|parent| {
-                setup_personality_node(parent, &theme);
+                setup_personality_node(parent, &theme, &asset_server, &trait_manifests);
+                setup_appearance_node(parent, &theme, &asset_server, &outfit_manifests);
                 setup_actors_node(parent, &theme);
                 setup_family_menu_buttons(parent, &theme);
             });
@@ -115,12 +132,14 @@ fn remove_actor_buttons(
 // Updates UI with parameters of the current actor.
 fn display_actor_data(
     trigger: Trigger<OnAdd, EditorSelectedActor>,
-    actors: Query<(&EditorSex, &EditorFirstName, &EditorLastName)>,
+    asset_server: Res<AssetServer>,
+    actors: Query<(&EditorSex, &EditorFirstName, &EditorLastName, &EditorTraits)>,
     mut sex_buttons: Query<(&mut Toggled, &EditorSex), Without<ActorButton>>,
+    mut trait_buttons: Query<(&mut Toggled, &TraitButton), Without<EditorSex>>,
     mut first_name_edits: Query<&mut TextInputValue, With<FirstNameEdit>>,
     mut last_name_edits: Query<&mut TextInputValue, (With<LastNameEdit>, Without<FirstNameEdit>)>,
 ) {
-    let (&actor_sex, first_name, last_name) = actors.get(trigger.entity()).unwrap();
+    let (&actor_sex, first_name, last_name, traits) = actors.get(trigger.entity()).unwrap();
     first_name_edits.single_mut().0.clone_from(first_name);
     last_name_edits.single_mut().0.clone_from(last_name);
 
@@ -129,6 +148,33 @@ fn display_actor_data(
         .find(|(_, &sex)| sex == actor_sex)
         .expect("sex buttons should be spawned for each variant");
     sex_toggled.0 = true;
+
+    for (mut toggled, trait_button) in &mut trait_buttons {
+        let path = asset_server.get_path(trait_button.0);
+        toggled.0 = path.is_some_and(|path| traits.contains(&path.into_owned()));
+    }
+}
+
+fn display_appearance(
+    trigger: Trigger<OnAdd, EditorSelectedActor>,
+    asset_server: Res<AssetServer>,
+    actors: Query<&EditorAppearance>,
+    mut skin_tone_buttons: Query<(&mut Toggled, &SkinToneButton)>,
+    mut outfit_buttons: Query<(&mut Toggled, &OutfitButton), Without<SkinToneButton>>,
+) {
+    let appearance = actors.get(trigger.entity()).unwrap();
+
+    for (mut toggled, skin_tone_button) in &mut skin_tone_buttons {
+        toggled.0 = skin_tone_button.0 == appearance.skin_tone();
+    }
+
+    for (mut toggled, outfit_button) in &mut outfit_buttons {
+        let path = outfit_button
+            .id
+            .and_then(|id| asset_server.get_path(id))
+            .map(|path| path.into_owned());
+        toggled.0 = path.as_ref() == appearance.outfit(outfit_button.category);
+    }
 }
 
 fn apply_first_name(
@@ -178,7 +224,49 @@ fn update_previews(
     }
 }
 
-fn setup_personality_node(parent: &mut ChildBuilder, theme: &Theme) {
+/// Collects toggled trait buttons into the selected actor traits.
+fn apply_traits(
+    asset_server: Res<AssetServer>,
+    mut buttons: Query<(Entity, &mut Toggled, &TraitButton)>,
+    mut traits: Single<&mut EditorTraits, With<EditorSelectedActor>>,
+) {
+    let changed_entities: Vec<_> = buttons
+        .iter_mut()
+        .filter(|(_, toggled, _)| toggled.is_changed())
+        .map(|(entity, ..)| entity)
+        .collect();
+    if changed_entities.is_empty() {
+        return;
+    }
+
+    let toggled_count = buttons.iter().filter(|(_, toggled, _)| toggled.0).count();
+    if toggled_count > Traits::MAX {
+        for &entity in &changed_entities {
+            let (_, mut toggled, _) = buttons.get_mut(entity).unwrap();
+            if toggled.0 {
+                debug!("reached traits limit, untoggling `{entity}`");
+                toggled.0 = false;
+            }
+        }
+    }
+
+    traits.clear();
+    for (_, toggled, trait_button) in &buttons {
+        if toggled.0 {
+            if let Some(path) = asset_server.get_path(trait_button.0) {
+                traits.push(path.into_owned());
+            }
+        }
+    }
+    debug!("updating traits to {:?}", **traits);
+}
+
+fn setup_personality_node(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    asset_server: &AssetServer,
+    trait_manifests: &Assets<TraitManifest>,
+) {
     parent
         .spawn((
             Node {
@@ -221,6 +309,33 @@ fn setup_personality_node(parent: &mut ChildBuilder, theme: &Theme) {
                     .with_child(Text::new("Female"))
                     .observe(apply_sex);
             });
+
+            parent.spawn((
+                LabelKind::Normal,
+                Text::new(format!("Traits (up to {})", Traits::MAX)),
+            ));
+            parent
+                .spawn(Node {
+                    display: Display::Grid,
+                    column_gap: theme.gap.normal,
+                    row_gap: theme.gap.normal,
+                    grid_template_columns: vec![GridTrack::auto(); 2],
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    let mut manifests: Vec<_> = trait_manifests.iter().collect();
+                    manifests.sort_by(|(_, a), (_, b)| a.general.name.cmp(&b.general.name));
+                    for (id, manifest) in manifests {
+                        debug!(
+                            "creating button for trait '{:?}'",
+                            asset_server.get_path(id)
+                        );
+                        parent.spawn(TraitButton(id)).with_child(Text::new(format!(
+                            "{} {}",
+                            manifest.glyph, manifest.general.name
+                        )));
+                    }
+                });
         });
 }
 
@@ -234,6 +349,158 @@ fn apply_sex(
     **actor_sex = button_sex;
 }
 
+fn setup_appearance_node(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    asset_server: &AssetServer,
+    outfit_manifests: &Assets<OutfitManifest>,
+) {
+    parent
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                padding: theme.padding.normal,
+                row_gap: theme.gap.normal,
+                ..Default::default()
+            },
+            theme.panel_background,
+        ))
+        .with_children(|parent| {
+            parent.spawn((LabelKind::Normal, Text::new("Skin tone")));
+            parent
+                .spawn(Node {
+                    column_gap: theme.gap.normal,
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    let default_tone = Appearance::default().skin_tone();
+                    for (index, &color) in Appearance::SKIN_TONES.iter().enumerate() {
+                        parent
+                            .spawn((SkinToneButton(index), Toggled(index == default_tone)))
+                            .with_child((
+                                Node {
+                                    width: Val::Px(20.0),
+                                    height: Val::Px(20.0),
+                                    ..Default::default()
+                                },
+                                BackgroundColor(color),
+                            ))
+                            .observe(apply_skin_tone);
+                    }
+                });
+
+            parent
+                .spawn(Node {
+                    display: Display::Grid,
+                    column_gap: theme.gap.normal,
+                    row_gap: theme.gap.normal,
+                    grid_template_columns: vec![GridTrack::auto(); 3],
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for shape in BodyShape::iter() {
+                        parent.spawn((LabelKind::Normal, Text::new(shape.name())));
+                        for (delta, text) in [(-BODY_SHAPE_STEP, "-"), (BODY_SHAPE_STEP, "+")] {
+                            parent
+                                .spawn(BodyShapeButton { shape, delta })
+                                .with_child(Text::new(text))
+                                .observe(apply_body_shape);
+                        }
+                    }
+                });
+
+            let mut manifests: Vec<_> = outfit_manifests.iter().collect();
+            manifests.sort_by(|(_, a), (_, b)| a.general.name.cmp(&b.general.name));
+            for category in OutfitCategory::iter() {
+                let category_manifests: Vec<_> = manifests
+                    .iter()
+                    .filter(|(_, manifest)| manifest.category == category)
+                    .collect();
+                if category_manifests.is_empty() {
+                    continue;
+                }
+
+                parent
+                    .spawn(Node {
+                        flex_wrap: FlexWrap::Wrap,
+                        column_gap: theme.gap.normal,
+                        row_gap: theme.gap.normal,
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent
+                            .spawn((OutfitButton { category, id: None }, Toggled(true)))
+                            .with_child(Text::new(format!("{} None", category.glyph())))
+                            .observe(apply_outfit);
+                        for &(id, manifest) in category_manifests {
+                            debug!(
+                                "creating button for outfit '{:?}'",
+                                asset_server.get_path(id)
+                            );
+                            parent
+                                .spawn(OutfitButton {
+                                    category,
+                                    id: Some(id),
+                                })
+                                .with_child(Text::new(format!(
+                                    "{} {}",
+                                    category.glyph(),
+                                    manifest.general.name
+                                )))
+                                .observe(apply_outfit);
+                        }
+                    });
+            }
+        });
+}
+
+fn apply_skin_tone(
+    trigger: Trigger<Pointer<Click>>,
+    buttons: Query<&SkinToneButton>,
+    mut appearance: Single<&mut EditorAppearance, With<EditorSelectedActor>>,
+) {
+    let skin_tone_button = buttons.get(trigger.entity()).unwrap();
+    info!("changing skin tone to {}", skin_tone_button.0);
+    appearance.set_skin_tone(skin_tone_button.0);
+}
+
+/// Body shape weight change per click.
+const BODY_SHAPE_STEP: f32 = 0.25;
+
+fn apply_body_shape(
+    trigger: Trigger<Pointer<Click>>,
+    buttons: Query<&BodyShapeButton>,
+    mut appearance: Single<&mut EditorAppearance, With<EditorSelectedActor>>,
+) {
+    let body_shape_button = buttons.get(trigger.entity()).unwrap();
+    let weight = appearance.body_shape(body_shape_button.shape) + body_shape_button.delta;
+    info!(
+        "changing body shape '{:?}' to {weight}",
+        body_shape_button.shape
+    );
+    appearance.set_body_shape(body_shape_button.shape, weight);
+}
+
+fn apply_outfit(
+    trigger: Trigger<Pointer<Click>>,
+    asset_server: Res<AssetServer>,
+    buttons: Query<&OutfitButton>,
+    mut appearance: Single<&mut EditorAppearance, With<EditorSelectedActor>>,
+) {
+    let outfit_button = buttons.get(trigger.entity()).unwrap();
+    let path = outfit_button
+        .id
+        .and_then(|id| asset_server.get_path(id))
+        .map(|path| path.into_owned());
+    info!(
+        "changing outfit '{:?}' to '{path:?}'",
+        outfit_button.category
+    );
+    appearance.set_outfit(outfit_button.category, path);
+}
+
 fn setup_actors_node(parent: &mut ChildBuilder, theme: &Theme) {
     parent
         .spawn((
@@ -524,6 +791,43 @@ struct ActorsNode;
 )]
 struct ActorButton(Entity);
 
+#[derive(Component, Clone, Copy)]
+#[require(
+    Name(|| Name::new("Trait button")),
+    ButtonKind(|| ButtonKind::Normal),
+    Toggled,
+)]
+struct TraitButton(AssetId<TraitManifest>);
+
+#[derive(Component, Clone, Copy)]
+#[require(
+    Name(|| Name::new("Skin tone button")),
+    ButtonKind(|| ButtonKind::Normal),
+    ExclusiveButton,
+)]
+struct SkinToneButton(usize);
+
+#[derive(Component, Clone, Copy)]
+#[require(
+    Name(|| Name::new("Body shape button")),
+    ButtonKind(|| ButtonKind::Symbol),
+)]
+struct BodyShapeButton {
+    shape: BodyShape,
+    delta: f32,
+}
+
+#[derive(Component, Clone, Copy)]
+#[require(
+    Name(|| Name::new("Outfit button")),
+    ButtonKind(|| ButtonKind::Normal),
+    ExclusiveButton,
+)]
+struct OutfitButton {
+    category: OutfitCategory,
+    id: Option<AssetId<OutfitManifest>>,
+}
+
 #[derive(Component)]
 struct FamilyNameEdit;
 
@@ -493,7 +493,7 @@ fn confirm_join(
         client_channels_config: network_channels.get_client_configs(),
         ..Default::default()
     });
-    let transport = network::create_client(port.0.parse()?, ip.0.parse()?)
+    let transport = network::create_client(port.0.parse()?, ip.0.parse()?, None)
         .context("unable to create connection")?;
 
     commands.insert_resource(client);
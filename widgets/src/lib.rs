@@ -4,6 +4,7 @@ pub mod dialog;
 pub mod label;
 pub mod popup;
 pub mod progress_bar;
+pub mod scroll_view;
 pub mod text_edit;
 pub mod theme;
 
@@ -15,6 +16,7 @@ use dialog::DialogPlugin;
 use label::LabelPlugin;
 use popup::PopupPlugin;
 use progress_bar::ProgressBarPlugin;
+use scroll_view::ScrollViewPlugin;
 use text_edit::TextEditPlugin;
 use theme::ThemePlugin;
 
@@ -29,6 +31,7 @@ impl Plugin for WidgetsPlugin {
             CheckboxPlugin,
             PopupPlugin,
             ProgressBarPlugin,
+            ScrollViewPlugin,
             TextEditPlugin,
             ThemePlugin,
         ));
@@ -0,0 +1,66 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    picking::focus::HoverMap,
+    prelude::*,
+};
+
+use crate::theme::Theme;
+
+pub(super) struct ScrollViewPlugin;
+
+impl Plugin for ScrollViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(init).add_systems(Update, scroll);
+    }
+}
+
+fn init(
+    trigger: Trigger<OnAdd, ScrollView>,
+    theme: Res<Theme>,
+    mut scroll_views: Query<(&mut Node, &mut BackgroundColor)>,
+) {
+    let (mut node, mut background) = scroll_views.get_mut(trigger.entity()).unwrap();
+    node.flex_direction = FlexDirection::Column;
+    node.overflow = Overflow::scroll_y();
+    node.padding = theme.padding.normal;
+    *background = theme.scroll_view.background_color;
+}
+
+/// Scrolls the hovered view with the mouse wheel.
+///
+/// Content inside the view can be hovered, so the view is searched among the ancestors.
+fn scroll(
+    theme: Res<Theme>,
+    hover_map: Res<HoverMap>,
+    mut wheel_events: EventReader<MouseWheel>,
+    parents: Query<&Parent>,
+    mut scroll_views: Query<&mut ScrollPosition, With<ScrollView>>,
+) {
+    for event in wheel_events.read() {
+        let offset = match event.unit {
+            MouseScrollUnit::Line => event.y * theme.scroll_view.line_height,
+            MouseScrollUnit::Pixel => event.y,
+        };
+
+        for &entity in hover_map.values().flat_map(|hits| hits.keys()) {
+            let Some(view_entity) = [entity]
+                .into_iter()
+                .chain(parents.iter_ancestors(entity))
+                .find(|&entity| scroll_views.contains(entity))
+            else {
+                continue;
+            };
+
+            let mut position = scroll_views.get_mut(view_entity).unwrap();
+            position.offset_y -= offset;
+            break;
+        }
+    }
+}
+
+/// A column with vertical scrolling.
+///
+/// Set [`ScrollPosition::offset_y`] to [`f32::MAX`] to scroll to the end.
+#[derive(Component)]
+#[require(Node, ScrollPosition)]
+pub struct ScrollView;
@@ -20,6 +20,7 @@ pub struct Theme {
     pub checkbox: CheckboxTheme,
     pub text_edit: TextEditTheme,
     pub progress_bar: ProgressBarTheme,
+    pub scroll_view: ScrollViewTheme,
     pub gap: GapTheme,
     pub padding: PaddingTheme,
     pub modal_background: BackgroundColor,
@@ -112,6 +113,10 @@ impl FromWorld for Theme {
                 background_color: Color::srgb(0.5, 0.5, 0.5).into(),
                 fill_color: Color::srgb(0.35, 0.75, 0.35).into(),
             },
+            scroll_view: ScrollViewTheme {
+                background_color: Color::srgba(0.15, 0.15, 0.15, 0.6).into(),
+                line_height: 20.0,
+            },
             gap: GapTheme {
                 normal: Val::Px(10.0),
                 large: Val::Px(20.0),
@@ -193,6 +198,11 @@ pub struct ProgressBarTheme {
     pub fill_color: BackgroundColor,
 }
 
+pub struct ScrollViewTheme {
+    pub background_color: BackgroundColor,
+    pub line_height: f32,
+}
+
 pub struct GapTheme {
     pub normal: Val,
     pub large: Val,